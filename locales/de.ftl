@@ -0,0 +1,160 @@
+# Deutsche Meldungen. Fehlende Meldungen werden aus dem französischen
+# Katalog übernommen.
+
+## Oberfläche
+
+tab-create-product = Produkt anlegen
+tab-create-client = Kunde anlegen
+tab-generate = Dokument erstellen
+tab-delivery-notes = Lieferscheine
+tab-invoices = Rechnungen
+tab-quotes = Angebote
+tab-clients = Kunden
+tab-products = Produkte
+tab-settings = Einstellungen
+language = Sprache
+format = Format
+format-pdf = PDF
+format-html = HTML
+format-text = Text
+format-csv = CSV
+file-written = Datei geschrieben: { $path }
+open-folder = Ordner öffnen
+open-folder-failed = Ordner { $folder } konnte nicht geöffnet werden: { $error }
+
+create-client = Kunde anlegen
+field-id = ID
+field-name = Name
+field-address1 = Adresse 1
+field-address2 = Adresse 2
+field-postal-code = Postleitzahl
+field-city = Ort
+field-country = Land
+field-language = Sprache der Dokumente
+save-client = Kunde speichern
+
+create-product = Produkt anlegen
+field-description = Beschreibung
+field-reference = Artikelnummer
+field-unit = Einheit
+field-vat-rate = USt.-Satz
+save-product = Produkt speichern
+
+clients = Kunden
+products = Produkte
+filter = Filtern
+save = Speichern
+cancel = Abbrechen
+confirm-remove = Löschen bestätigen
+confirm-remove-client = Kunde { $id } löschen?
+confirm-remove-product = Produkt { $id } löschen?
+
+error-required = Pflichtfeld
+error-postal-code = Keine gültige Postleitzahl für { $country }
+error-not-a-number = Zahl erwartet
+error-not-positive = Muss größer als null sein
+error-negative = Darf nicht negativ sein
+error-unit = Unbekannte Einheit „{ $unit }“, erwartet: { $units }
+error-wrong-unit = Das Produkt wird in { $unit } gezählt
+error-taken = Bereits vergeben
+
+create-report = Dokument erstellen
+sender = Absender
+select-client = Kunde auswählen
+add-products = Produkte hinzufügen
+select-product = Produkt auswählen
+select-quantity = Menge auswählen
+unit-price = Einzelpreis netto
+choose-sender = Wählen Sie ein Absenderprofil in den Einstellungen.
+generate-delivery-note = Lieferschein erstellen
+generate-quote = Angebot erstellen
+
+delivery-notes = Lieferscheine
+column-number = Nummer
+column-date = Datum
+column-client = Kunde
+column-products = Produkte
+reprint = Erneut drucken
+create-invoice = Rechnung stellen
+invoices = Rechnungen
+column-total = Gesamtbetrag
+quotes = Angebote
+column-valid-until = Gültig bis
+column-delivery-note = Lieferschein
+accept = Annehmen
+
+sender-profiles = Absenderprofile
+field-company = Firmenname
+field-siret = SIRET
+field-vat-number = USt-IdNr.
+field-phone = Telefon
+field-email = E-Mail
+field-logo = Logo (PNG)
+field-footer = Fußzeile
+edit = Bearbeiten
+remove = Löschen
+save-profile = Profil speichern
+new-profile = Neues Profil
+profile-needs-id = Das Profil braucht eine ID.
+output-files = Erzeugte Dateien
+output-directory = Ordner
+output-pattern = Dateinamen
+output-pattern-help = { $values } werden durch die Werte des Dokuments ersetzt.
+output-overwrite = Vorhandene Dateien ersetzen
+apply = Übernehmen
+numbering = Nummerierung
+numbering-documents = Dokumente
+field-prefix = Präfix
+field-width = Stellen
+apply-numbering = Nummerierung übernehmen
+
+## Dokumente
+
+delivery-note = Lieferschein
+delivery-note-number = Lieferschein Nr. { $number }
+invoice = Rechnung
+invoice-number = Rechnung Nr. { $number }
+quote = Angebot
+quote-number = Angebot Nr. { $number }
+number = Nr. { $number }
+date = Datum: { $date }
+due-date = Fällig am: { $due_date }
+delivery-note-reference = Lieferschein: { $delivery_note }
+valid-until = Gültig bis: { $valid_until }
+recipient = An:
+page = Seite { $page } / { $pages }
+
+reference = Artikelnr.
+description = Beschreibung
+quantity = Menge
+quantity-short = Menge
+total-quantity-line = Gesamt: { $total_quantity }
+unit-piece = Stk.
+unit-kilogram = kg
+unit-metre = m
+unit-square-metre = m²
+unit-litre = l
+unit-pallet = Pal.
+unit-price-excluding-tax = Einzelpreis
+vat = USt.
+total-excluding-tax = Netto
+
+signature-name = Name:
+signature-visa = Kürzel:
+quote-agreement = Angenommen am:
+signature = Unterschrift:
+
+vat-line = USt. { $rate } % auf { $base } €: { $amount } €
+total-excluding-tax-line = Summe netto: { $total_excluding_tax } €
+total-vat-line = Summe USt.: { $total_vat } €
+total-including-tax-line = Gesamtbetrag: { $total_including_tax } €
+pay-before = Zahlbar bis { $due_date }
+no-discount = Kein Skonto bei vorzeitiger Zahlung.
+late-penalties = Bei Zahlungsverzug werden Verzugszinsen in Höhe des Dreifachen des französischen gesetzlichen Zinssatzes fällig (Art. L441-10 des französischen Handelsgesetzbuchs).
+recovery-fee = Pauschale für Beitreibungskosten: 40 €.
+prices-excluding-tax = Preise in Euro, zuzüglich USt.
+quote-valid-until = Angebot gültig bis { $valid_until }.
+
+sender-siret = SIRET { $siret }
+sender-vat-number = USt-IdNr. { $vat_number }
+sender-phone = Tel. { $phone }
@@ -0,0 +1,160 @@
+# English messages. The messages missing here are taken from the French
+# catalog.
+
+## Interface
+
+tab-create-product = Create product
+tab-create-client = Create client
+tab-generate = Create document
+tab-delivery-notes = Delivery notes
+tab-invoices = Invoices
+tab-quotes = Quotes
+tab-clients = Clients
+tab-products = Products
+tab-settings = Settings
+language = Language
+format = Format
+format-pdf = PDF
+format-html = HTML
+format-text = Text
+format-csv = CSV
+file-written = File written: { $path }
+open-folder = Open folder
+open-folder-failed = Could not open the folder { $folder }: { $error }
+
+create-client = Create a Client
+field-id = ID
+field-name = Name
+field-address1 = Address 1
+field-address2 = Address 2
+field-postal-code = Postal code
+field-city = City
+field-country = Country
+field-language = Document language
+save-client = Save client
+
+create-product = Create a Product
+field-description = Description
+field-reference = Reference
+field-unit = Unit
+field-vat-rate = VAT rate
+save-product = Save product
+
+clients = Clients
+products = Products
+filter = Filter
+save = Save
+cancel = Cancel
+confirm-remove = Confirm removal
+confirm-remove-client = Remove the client { $id }?
+confirm-remove-product = Remove the product { $id }?
+
+error-required = Required
+error-postal-code = Not a postal code of { $country }
+error-not-a-number = Expected a number
+error-not-positive = Must be greater than zero
+error-negative = Cannot be negative
+error-unit = Unknown unit "{ $unit }", expected one of { $units }
+error-wrong-unit = The product is counted in { $unit }
+error-taken = Already taken
+
+create-report = Create a Document
+sender = Sender
+select-client = Select a client
+add-products = Add products
+select-product = Select a product
+select-quantity = Select a quantity
+unit-price = Unit price excl. VAT
+choose-sender = Choose a sender profile in Settings.
+generate-delivery-note = Generate delivery note
+generate-quote = Generate quote
+
+delivery-notes = Delivery Notes
+column-number = Number
+column-date = Date
+column-client = Client
+column-products = Products
+reprint = Reprint
+create-invoice = Invoice
+invoices = Invoices
+column-total = Total due
+quotes = Quotes
+column-valid-until = Valid until
+column-delivery-note = Delivery note
+accept = Accept
+
+sender-profiles = Sender profiles
+field-company = Company name
+field-siret = SIRET
+field-vat-number = VAT number
+field-phone = Phone
+field-email = Email
+field-logo = Logo (PNG)
+field-footer = Footer
+edit = Edit
+remove = Remove
+save-profile = Save profile
+new-profile = New profile
+profile-needs-id = The profile needs an ID.
+output-files = Generated files
+output-directory = Folder
+output-pattern = File names
+output-pattern-help = { $values } are replaced by those of the document.
+output-overwrite = Replace existing files
+apply = Apply
+numbering = Numbering
+numbering-documents = Documents
+field-prefix = Prefix
+field-width = Digits
+apply-numbering = Apply the numbering
+
+## Documents
+
+delivery-note = Delivery Note
+delivery-note-number = Delivery Note No. { $number }
+invoice = Invoice
+invoice-number = Invoice No. { $number }
+quote = Quote
+quote-number = Quote No. { $number }
+number = No. { $number }
+date = Date: { $date }
+due-date = Due date: { $due_date }
+delivery-note-reference = Delivery note: { $delivery_note }
+valid-until = Valid until: { $valid_until }
+recipient = To:
+page = Page { $page } / { $pages }
+
+reference = Reference
+description = Description
+quantity = Quantity
+quantity-short = Qty
+total-quantity-line = Total: { $total_quantity }
+unit-piece = pcs
+unit-kilogram = kg
+unit-metre = m
+unit-square-metre = m²
+unit-litre = l
+unit-pallet = pal.
+unit-price-excluding-tax = Unit price
+vat = VAT
+total-excluding-tax = Net total
+
+signature-name = Name:
+signature-visa = Initials:
+quote-agreement = Accepted on:
+signature = Signature:
+
+vat-line = VAT { $rate } % on € { $base }: € { $amount }
+total-excluding-tax-line = Net total: € { $total_excluding_tax }
+total-vat-line = VAT total: € { $total_vat }
+total-including-tax-line = Total due: € { $total_including_tax }
+pay-before = Payable by { $due_date }
+no-discount = No discount for early payment.
+late-penalties = Late payments incur penalties of three times the French legal interest rate (art. L441-10 of the French Commercial Code).
+recovery-fee = Fixed recovery fee: € 40.
+prices-excluding-tax = Prices in euros, excluding VAT.
+quote-valid-until = Quote valid until { $valid_until }.
+
+sender-siret = SIRET { $siret }
+sender-vat-number = VAT { $vat_number }
+sender-phone = Tel. { $phone }
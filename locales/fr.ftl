@@ -0,0 +1,161 @@
+# Messages en français, la langue par défaut : les autres catalogues
+# reprennent ces messages, et ceux qui leur manquent sont pris ici.
+# Les modèles de documents utilisent un message avec {t.identifiant}.
+
+## Interface
+
+tab-create-product = Créer un produit
+tab-create-client = Créer un client
+tab-generate = Créer un document
+tab-delivery-notes = Bons de livraison
+tab-invoices = Factures
+tab-quotes = Devis
+tab-clients = Clients
+tab-products = Produits
+tab-settings = Paramètres
+language = Langue
+format = Format
+format-pdf = PDF
+format-html = HTML
+format-text = Texte
+format-csv = CSV
+file-written = Fichier écrit : { $path }
+open-folder = Ouvrir le dossier
+open-folder-failed = Impossible d'ouvrir le dossier { $folder } : { $error }
+
+create-client = Créer un Client
+field-id = ID
+field-name = Nom
+field-address1 = Adresse 1
+field-address2 = Adresse 2
+field-postal-code = Code Postal
+field-city = Ville
+field-country = Pays
+field-language = Langue des documents
+save-client = Sauvegarder Client
+
+create-product = Créer un Produit
+field-description = Description
+field-reference = Référence
+field-unit = Unité
+field-vat-rate = Taux de TVA
+save-product = Sauvegarder un produit
+
+clients = Clients
+products = Produits
+filter = Filtrer
+save = Enregistrer
+cancel = Annuler
+confirm-remove = Confirmer la suppression
+confirm-remove-client = Supprimer le client { $id } ?
+confirm-remove-product = Supprimer le produit { $id } ?
+
+error-required = Champ obligatoire
+error-postal-code = Code postal invalide pour { $country }
+error-not-a-number = Nombre attendu
+error-not-positive = Doit être supérieur à zéro
+error-negative = Ne peut pas être négatif
+error-unit = Unité « { $unit } » inconnue, attendu : { $units }
+error-wrong-unit = Le produit se compte en { $unit }
+error-taken = Déjà utilisé
+
+create-report = Créer un Rapport
+sender = Émetteur
+select-client = Sélectionner un client
+add-products = Ajouter des produits
+select-product = Sélectionner un produit
+select-quantity = Sélectionner une quantité
+unit-price = Prix unitaire HT
+choose-sender = Choisissez un profil émetteur dans Paramètres.
+generate-delivery-note = Générer un bon
+generate-quote = Générer un devis
+
+delivery-notes = Bons de Livraison
+column-number = Numéro
+column-date = Date
+column-client = Client
+column-products = Produits
+reprint = Réimprimer
+create-invoice = Facturer
+invoices = Factures
+column-total = Total TTC
+quotes = Devis
+column-valid-until = Valable jusqu'au
+column-delivery-note = Bon de livraison
+accept = Accepter
+
+sender-profiles = Profils émetteurs
+field-company = Raison sociale
+field-siret = SIRET
+field-vat-number = N° TVA
+field-phone = Téléphone
+field-email = Email
+field-logo = Logo (PNG)
+field-footer = Pied de page
+edit = Modifier
+remove = Supprimer
+save-profile = Sauvegarder le profil
+new-profile = Nouveau profil
+profile-needs-id = Le profil doit avoir un ID.
+output-files = Fichiers générés
+output-directory = Dossier
+output-pattern = Nom des fichiers
+output-pattern-help = { $values } sont remplacés par ceux du document.
+output-overwrite = Remplacer les fichiers existants
+apply = Appliquer
+numbering = Numérotation
+numbering-documents = Documents
+field-prefix = Préfixe
+field-width = Chiffres
+apply-numbering = Appliquer la numérotation
+
+## Documents
+
+delivery-note = Bon de Livraison
+delivery-note-number = Bon de Livraison N° { $number }
+invoice = Facture
+invoice-number = Facture N° { $number }
+quote = Devis
+quote-number = Devis N° { $number }
+number = N° { $number }
+date = Date : { $date }
+due-date = Échéance : { $due_date }
+delivery-note-reference = Bon de livraison : { $delivery_note }
+valid-until = Valable jusqu'au : { $valid_until }
+recipient = Pour:
+page = page { $page } / { $pages }
+
+reference = Référence
+description = Description
+quantity = Quantité
+quantity-short = Qté
+total-quantity-line = Total : { $total_quantity }
+unit-piece = pce
+unit-kilogram = kg
+unit-metre = m
+unit-square-metre = m²
+unit-litre = l
+unit-pallet = pal.
+unit-price-excluding-tax = PU HT
+vat = TVA
+total-excluding-tax = Total HT
+
+signature-name = Nom :
+signature-visa = Visa :
+quote-agreement = Bon pour accord, le :
+signature = Signature :
+
+vat-line = TVA { $rate } % sur { $base } € : { $amount } €
+total-excluding-tax-line = Total HT : { $total_excluding_tax } €
+total-vat-line = Total TVA : { $total_vat } €
+total-including-tax-line = Total TTC : { $total_including_tax } €
+pay-before = À payer avant le { $due_date }
+no-discount = Pas d'escompte pour paiement anticipé.
+late-penalties = En cas de retard de paiement, des pénalités égales à trois fois le taux d'intérêt légal sont exigibles (art. L441-10 du Code de commerce).
+recovery-fee = Indemnité forfaitaire pour frais de recouvrement : 40 €.
+prices-excluding-tax = Prix en euros, hors taxes.
+quote-valid-until = Devis valable jusqu'au { $valid_until }.
+
+sender-siret = SIRET { $siret }
+sender-vat-number = TVA { $vat_number }
+sender-phone = Tél. { $phone }
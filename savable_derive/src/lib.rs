@@ -0,0 +1,231 @@
+//! `#[derive(Savable)]` for the entities of the delivery note generator.
+//!
+//! Every field is stored as a column named after the field. Fields are written
+//! with `Display` and read back with `FromStr`, so any type implementing both
+//! can be stored. Columns are matched by name when loading, and unknown
+//! columns are ignored.
+//!
+//! Container attributes:
+//! - `#[savable(name = "client")]`: name of the entity store, the type name by default.
+//! - `#[savable(version = 2)]`: schema version of the fields, 1 by default.
+//!
+//! Field attributes:
+//! - `#[savable(id)]`: the field identifying the entity, the `id` field by default.
+//! - `#[savable(rename = "column")]`: store the field under another column name.
+//! - `#[savable(default)]`: use `Default::default()` when the column is missing.
+//! - `#[savable(default = "path::to::function")]`: call a function when the column is missing.
+//! - `#[savable(skip)]`: do not store the field, and use `Default::default()` when loading.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitInt, LitStr, Path};
+
+#[proc_macro_derive(Savable, attributes(savable))]
+pub fn derive_savable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+enum FieldDefault {
+    Required,
+    Trait,
+    Function(Path),
+}
+
+struct Field {
+    ident: syn::Ident,
+    ty: syn::Type,
+    column: String,
+    default: FieldDefault,
+    skip: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut savable_name = name.to_string();
+    let mut version = 1u32;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("savable")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                savable_name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("version") {
+                version = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unknown savable attribute"))
+            }
+        })?;
+    }
+
+    let named = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "Savable can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "Savable can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut fields = Vec::new();
+    let mut id_column = None;
+    for field in named {
+        let ident = field.ident.clone().unwrap();
+        let mut column = ident.to_string();
+        let mut default = FieldDefault::Required;
+        let mut skip = false;
+        let mut is_id = false;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("savable")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("id") {
+                    is_id = true;
+                } else if meta.path.is_ident("rename") {
+                    column = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("default") {
+                    default = if meta.input.peek(syn::Token![=]) {
+                        FieldDefault::Function(meta.value()?.parse::<LitStr>()?.parse()?)
+                    } else {
+                        FieldDefault::Trait
+                    };
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                } else {
+                    return Err(meta.error("unknown savable attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        if is_id {
+            if skip {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "the id field cannot be skipped",
+                ));
+            }
+            if id_column.is_some() {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "only one field can be the id",
+                ));
+            }
+            id_column = Some(column.clone());
+        }
+        fields.push(Field {
+            ident,
+            ty: field.ty.clone(),
+            column,
+            default,
+            skip,
+        });
+    }
+    let id_column = id_column.or_else(|| {
+        fields
+            .iter()
+            .find(|f| !f.skip && f.ident == "id")
+            .map(|f| f.column.clone())
+    });
+    let id_column = match id_column {
+        Some(column) => column,
+        None => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "Savable needs an `id` field or a field marked #[savable(id)]",
+            ))
+        }
+    };
+
+    let stored = fields.iter().filter(|f| !f.skip).collect::<Vec<&Field>>();
+    let to_fields = stored.iter().map(|f| {
+        let ident = &f.ident;
+        let column = &f.column;
+        quote! { (#column.to_string(), self.#ident.to_string()) }
+    });
+    let vars = fields
+        .iter()
+        .map(|f| quote::format_ident!("__{}", f.ident))
+        .collect::<Vec<_>>();
+    let declarations = stored.iter().map(|f| {
+        let var = quote::format_ident!("__{}", f.ident);
+        let ty = &f.ty;
+        quote! { let mut #var: ::std::option::Option<#ty> = ::std::option::Option::None; }
+    });
+    let arms = stored.iter().map(|f| {
+        let var = quote::format_ident!("__{}", f.ident);
+        let ty = &f.ty;
+        let column = &f.column;
+        quote! {
+            #column => {
+                #var = ::std::option::Option::Some(
+                    <#ty as ::std::str::FromStr>::from_str(&value).map_err(|e| {
+                        crate::storage::StorageError::invalid_value(#column, &value, e.to_string())
+                    })?,
+                );
+            }
+        }
+    });
+    let initializers = fields.iter().zip(vars.iter()).map(|(f, var)| {
+        let ident = &f.ident;
+        let column = &f.column;
+        if f.skip {
+            return quote! { #ident: ::std::default::Default::default() };
+        }
+        let value = match &f.default {
+            FieldDefault::Required => quote! {
+                #var.ok_or_else(|| crate::storage::StorageError::missing_field(#column))?
+            },
+            FieldDefault::Trait => quote! { #var.unwrap_or_default() },
+            FieldDefault::Function(path) => quote! { #var.unwrap_or_else(#path) },
+        };
+        quote! { #ident: #value }
+    });
+
+    Ok(quote! {
+        impl #impl_generics crate::storage::Savable for #name #ty_generics #where_clause {
+            fn savable_name() -> String {
+                #savable_name.to_string()
+            }
+
+            fn schema_version() -> u32 {
+                #version
+            }
+
+            fn id_column() -> String {
+                #id_column.to_string()
+            }
+
+            fn to_fields(&self) -> Vec<(String, String)> {
+                vec![#(#to_fields),*]
+            }
+
+            fn from_fields(
+                fields: Vec<(String, String)>,
+            ) -> Result<Self, crate::storage::StorageError> {
+                #(#declarations)*
+                for (key, value) in fields {
+                    match key.as_str() {
+                        #(#arms)*
+                        _ => {}
+                    }
+                }
+                Ok(Self {
+                    #(#initializers),*
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,723 @@
+//! Command-line interface, to manage the data and generate delivery notes
+//! from scripts without opening the graphical interface.
+//!
+//! Exit codes: 0 on success, `EXIT_FAILURE` for any failure not listed
+//! below, `EXIT_USAGE` for invalid arguments, `EXIT_NOT_FOUND` when a
+//! client, product or document does not exist and `EXIT_CONFLICT` when the
+//! command conflicts with the saved data, such as a duplicate id.
+
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use chrono::{Local, NaiveDate};
+use clap::{Args, Parser, Subcommand};
+use rust_decimal::Decimal;
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use crate::{
+    engine::Engine,
+    error::Error,
+    generators::{delivery_note::DeliveryNote, formats::Format, output::OutputSettings, Report},
+    i18n::Language,
+    models::{
+        amount::{parse_decimal, split_quantity},
+        client::Client,
+        country::Country,
+        item::Item,
+        product::{standard_vat_rate, Product},
+        unit::Unit,
+    },
+    server::DEFAULT_ADDRESS,
+    storage::{file_storage::load_file_lines, Savable},
+};
+
+pub const EXIT_FAILURE: u8 = 1;
+/// Also the exit code of clap when the arguments cannot be parsed.
+pub const EXIT_USAGE: u8 = 2;
+pub const EXIT_NOT_FOUND: u8 = 3;
+pub const EXIT_CONFLICT: u8 = 4;
+
+#[derive(Debug, Parser)]
+#[command(about = "Delivery notes, quotes and invoices")]
+pub struct Cli {
+    /// Without a command, the graphical interface is opened.
+    #[command(subcommand)]
+    pub mode: Option<Mode>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Mode {
+    /// Serves the clients, products and delivery notes over HTTP, until the
+    /// process is stopped.
+    Serve(ServeArgs),
+    /// Lists the migrations the database needs without running them.
+    DryRunMigrations,
+    #[command(flatten)]
+    Command(Command),
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// Address and port to listen on. Other computers can only connect
+    /// when it is not a loopback address.
+    #[arg(long, default_value = DEFAULT_ADDRESS)]
+    pub address: String,
+    /// Token the requests must send in an `Authorization: Bearer` header.
+    #[arg(long, env = "DELIVERY_NOTES_TOKEN", hide_env_values = true)]
+    pub token: String,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Adds, lists, shows or deletes clients.
+    #[command(subcommand)]
+    Client(ClientCommand),
+    /// Adds, lists or imports products.
+    #[command(subcommand)]
+    Product(ProductCommand),
+    /// Generates or reprints delivery notes.
+    #[command(subcommand)]
+    Note(NoteCommand),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ClientCommand {
+    Add(ClientArgs),
+    List {
+        #[arg(long)]
+        json: bool,
+    },
+    Show {
+        id: String,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Fails if a delivery note was made for the client.
+    Delete {
+        id: String,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ClientArgs {
+    #[arg(long)]
+    id: String,
+    #[arg(long)]
+    name: String,
+    #[arg(long, default_value = "")]
+    address1: String,
+    #[arg(long, default_value = "")]
+    address2: String,
+    #[arg(long, default_value = "")]
+    postal_code: String,
+    #[arg(long, default_value = "")]
+    city: String,
+    /// Country of the address, as its ISO code such as FR or BE.
+    #[arg(long, default_value_t)]
+    country: Country,
+    /// Language of the documents: fr, en or de.
+    #[arg(long, default_value_t)]
+    language: Language,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProductCommand {
+    Add {
+        #[arg(long)]
+        id: String,
+        #[arg(long, default_value = "")]
+        description: String,
+        #[arg(long, default_value = "")]
+        reference: String,
+        /// Unit the quantities are in: pcs, kg, m, m2, l or pal.
+        #[arg(long, default_value_t)]
+        unit: Unit,
+        /// VAT rate the product is billed at, in percent.
+        #[arg(long, default_value_t = standard_vat_rate())]
+        vat_rate: Decimal,
+    },
+    List {
+        #[arg(long)]
+        json: bool,
+    },
+    /// Adds the products of a CSV file separated by `;`, whose first line
+    /// names the columns `id`, `description` and `reference`. Products with
+    /// an existing id are replaced.
+    Import { file: PathBuf },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NoteCommand {
+    Generate(GenerateArgs),
+    /// Writes a saved delivery note again.
+    Reprint {
+        number: String,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
+    /// Id of the client.
+    #[arg(long)]
+    client: String,
+    /// `REF:QTY` or `REF:QTY:PRICE`, where `REF` is the reference or the id
+    /// of a product. The price is 0 when left out.
+    #[arg(long = "item", value_name = "REF:QTY[:PRICE]", required = true, value_parser = parse_item)]
+    items: Vec<ItemArg>,
+    /// Id of the sender profile, which may be left out when there is only
+    /// one.
+    #[arg(long)]
+    sender: Option<String>,
+    /// Date of the note, as YYYY-MM-DD. Today when left out.
+    #[arg(long)]
+    date: Option<NaiveDate>,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct OutputArgs {
+    /// File to write. By default, the file is named after the note in the
+    /// reports folder.
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// pdf, html, txt or csv. By default, the extension of `--out`, or pdf.
+    #[arg(long, value_parser = parse_format)]
+    format: Option<Format>,
+    /// Replaces the file if it exists.
+    #[arg(long)]
+    overwrite: bool,
+    /// Prints the number and the path of the note as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+/// An item of a delivery note as written on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemArg {
+    product: String,
+    quantity: String,
+    price: String,
+}
+
+fn parse_item(value: &str) -> Result<ItemArg, String> {
+    let parts = value.split(':').collect::<Vec<&str>>();
+    let (product, quantity, price) = match parts.as_slice() {
+        [product, quantity] => (product, quantity, "0"),
+        [product, quantity, price] => (product, quantity, *price),
+        _ => return Err("expected REF:QTY or REF:QTY:PRICE".to_string()),
+    };
+    if product.is_empty() {
+        return Err("the product reference is empty".to_string());
+    }
+    for (name, number) in [("quantity", split_quantity(quantity).0), ("price", price)] {
+        if parse_decimal(number).is_none() {
+            return Err(format!("the {} \"{}\" is not a number", name, number));
+        }
+    }
+    return Ok(ItemArg {
+        product: product.to_string(),
+        quantity: quantity.to_string(),
+        price: price.to_string(),
+    });
+}
+
+fn parse_format(value: &str) -> Result<Format, String> {
+    return Format::from_extension(value).ok_or_else(|| {
+        let formats = Format::ALL.map(|format| format.extension()).join(", ");
+        return format!("expected one of {}", formats);
+    });
+}
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("{0}")]
+    Usage(String),
+    #[error(transparent)]
+    Engine(#[from] Error),
+    #[error("Cannot write the output: {0}")]
+    Output(#[from] io::Error),
+    #[error("Delivery note {number} was saved but not written, reprint it: {error}")]
+    NotWritten {
+        number: String,
+        #[source]
+        error: Box<CliError>,
+    },
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> ExitCode {
+        let code = match self {
+            CliError::Usage(_)
+            | CliError::Engine(Error::SenderNotChosen { .. } | Error::Invalid { .. }) => EXIT_USAGE,
+            CliError::Engine(Error::NotFound { .. }) => EXIT_NOT_FOUND,
+            CliError::Engine(
+                Error::DuplicateId { .. } | Error::InUse { .. } | Error::FileExists { .. },
+            ) => EXIT_CONFLICT,
+            CliError::Engine(_) | CliError::Output(_) | CliError::NotWritten { .. } => EXIT_FAILURE,
+        };
+        return ExitCode::from(code);
+    }
+}
+
+/// The stored fields of an entity as a JSON object.
+pub fn to_json(savable: &impl Savable) -> Value {
+    let fields = savable
+        .to_fields()
+        .into_iter()
+        .map(|(key, value)| (key, Value::String(value)))
+        .collect::<Map<String, Value>>();
+    return Value::Object(fields);
+}
+
+/// Prints the entities as a JSON array, or one per line with their fields
+/// separated by tabs.
+fn print_list<S: Savable>(out: &mut impl Write, savables: &[S], json: bool) -> io::Result<()> {
+    if json {
+        let list = savables.iter().map(to_json).collect::<Vec<Value>>();
+        return writeln!(out, "{}", Value::Array(list));
+    }
+    for savable in savables {
+        let values = savable
+            .to_fields()
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Vec<String>>();
+        writeln!(out, "{}", values.join("\t"))?;
+    }
+    return Ok(());
+}
+
+/// Runs the command and prints its result on `out`.
+pub fn run(engine: &mut Engine, command: Command, out: &mut impl Write) -> Result<(), CliError> {
+    return match command {
+        Command::Client(command) => run_client(engine, command, out),
+        Command::Product(command) => run_product(engine, command, out),
+        Command::Note(command) => run_note(engine, command, out),
+    };
+}
+
+fn run_client(
+    engine: &mut Engine,
+    command: ClientCommand,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    match command {
+        ClientCommand::Add(args) => {
+            engine.create_client(Client {
+                id: args.id,
+                name: args.name,
+                address1: args.address1,
+                address2: args.address2,
+                postal_code: args.postal_code,
+                city: args.city,
+                country: args.country,
+                language: args.language,
+            })?;
+        }
+        ClientCommand::List { json } => print_list(out, engine.get_clients(), json)?,
+        ClientCommand::Show { id, json } => {
+            let client = engine.find_client(&id)?;
+            if json {
+                writeln!(out, "{}", to_json(client))?;
+            } else {
+                for (key, value) in client.to_fields() {
+                    writeln!(out, "{}: {}", key, value)?;
+                }
+            }
+        }
+        ClientCommand::Delete { id } => {
+            engine.remove_client(&id)?;
+        }
+    }
+    return Ok(());
+}
+
+fn run_product(
+    engine: &mut Engine,
+    command: ProductCommand,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    match command {
+        ProductCommand::Add {
+            id,
+            description,
+            reference,
+            unit,
+            vat_rate,
+        } => {
+            let mut product = Product::new(&id, &description, &reference);
+            *product.unit_mut() = unit;
+            *product.vat_rate_mut() = vat_rate;
+            engine.create_product(product)?;
+        }
+        ProductCommand::List { json } => print_list(out, engine.get_products(), json)?,
+        ProductCommand::Import { file } => {
+            let products = load_file_lines::<Product>(&file).map_err(Error::from)?;
+            let count = products.len();
+            let added = engine.import_products(products)?;
+            writeln!(
+                out,
+                "{} products imported, {} new, {} replaced",
+                count,
+                added,
+                count - added
+            )?;
+        }
+    }
+    return Ok(());
+}
+
+/// The format asked by `args`, once it is known that the file may be written,
+/// so that no note is saved for a command that cannot succeed.
+fn output_format(engine: &mut Engine, args: &OutputArgs) -> Result<Format, CliError> {
+    let extension_format = args
+        .out
+        .as_ref()
+        .and_then(|path| path.extension())
+        .and_then(|extension| Format::from_extension(&extension.to_string_lossy()));
+    let format = match (args.format, &args.out) {
+        (Some(format), _) => format,
+        (None, None) => Format::default(),
+        (None, Some(path)) => extension_format.ok_or_else(|| {
+            CliError::Usage(format!(
+                "Cannot tell the format of {}, choose one with --format",
+                path.display()
+            ))
+        })?,
+    };
+    if args.overwrite {
+        engine.set_output(OutputSettings {
+            overwrite: true,
+            ..engine.output().clone()
+        });
+    }
+    if let Some(path) = &args.out {
+        if path.exists() && !engine.output().overwrite {
+            return Err(Error::FileExists { path: path.clone() }.into());
+        }
+    }
+    return Ok(format);
+}
+
+/// Writes the report in `format` as asked by `args` and prints where.
+fn write_report(
+    engine: &mut Engine,
+    report: impl Report,
+    format: Format,
+    args: &OutputArgs,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    let number = report.name().number;
+    let path = match &args.out {
+        Some(path) => {
+            engine.write_report(report, format, path)?;
+            path.clone()
+        }
+        None => engine.generate_report(report, format)?,
+    };
+    if args.json {
+        let result = json!({ "number": number, "path": path.display().to_string() });
+        writeln!(out, "{}", result)?;
+    } else {
+        writeln!(out, "{}", path.display())?;
+    }
+    return Ok(());
+}
+
+fn run_note(
+    engine: &mut Engine,
+    command: NoteCommand,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    match command {
+        NoteCommand::Generate(args) => {
+            let client = engine.find_client(&args.client)?.clone();
+            let sender = engine.find_sender(args.sender.as_deref())?.clone();
+            let mut items = Vec::new();
+            for item in &args.items {
+                let product = engine.find_product(&item.product)?.clone();
+                let item = Item::parse(product, &item.price, &item.quantity).map_err(|errors| {
+                    Error::Invalid {
+                        kind: "item",
+                        id: item.product.clone(),
+                        errors,
+                    }
+                })?;
+                items.push(item);
+            }
+            let date = args.date.unwrap_or_else(|| Local::now().date_naive());
+            let format = output_format(engine, &args.output)?;
+            let note = engine.create_delivery_note(sender, client, &items, date)?;
+            let number = note.number.clone();
+            // The note is saved: its number is told for it to be reprinted rather
+            // than generated again
+            write_report(engine, DeliveryNote::new(note), format, &args.output, out).map_err(
+                |error| CliError::NotWritten {
+                    number,
+                    error: Box::new(error),
+                },
+            )?;
+        }
+        NoteCommand::Reprint { number, output } => {
+            let note = engine.find_delivery_note(&number)?.clone();
+            let format = output_format(engine, &output)?;
+            write_report(engine, DeliveryNote::new(note), format, &output, out)?;
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::sender::Sender, storage::StorageType};
+    use rust_decimal::dec;
+    use std::{fs, path::Path};
+
+    fn setup_test_engine(folder: &'static str) -> Engine {
+        let path = Path::new(folder);
+        if path.exists() {
+            fs::remove_dir_all(path).unwrap();
+        }
+        fs::create_dir(path).unwrap();
+        Engine::new(StorageType::FileStorage(folder)).unwrap()
+    }
+
+    /// Runs the command line `args` and returns what it printed.
+    fn run_args(engine: &mut Engine, args: &[&str]) -> Result<String, CliError> {
+        let cli = Cli::try_parse_from(["delivery_note_generator"].iter().chain(args)).unwrap();
+        let mut out = Vec::new();
+        let Some(Mode::Command(command)) = cli.mode else {
+            panic!("expected a command");
+        };
+        run(engine, command, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_parse_item() {
+        assert_eq!(
+            parse_item("CH-01:3"),
+            Ok(ItemArg {
+                product: "CH-01".to_string(),
+                quantity: "3".to_string(),
+                price: "0".to_string(),
+            })
+        );
+        assert_eq!(parse_item("CH-01:2,5:12.50").unwrap().price, "12.50");
+        assert!(parse_item("CH-01").is_err());
+        assert!(parse_item("CH-01:trois").is_err());
+        assert!(parse_item(":3").is_err());
+
+        let args = [
+            "delivery_note_generator",
+            "note",
+            "generate",
+            "--client",
+            "1",
+        ];
+        let error = Cli::try_parse_from(args).unwrap_err();
+        assert_eq!(error.exit_code(), i32::from(EXIT_USAGE));
+    }
+
+    #[test]
+    fn test_dry_run_migrations() {
+        let cli = Cli::try_parse_from(["delivery_note_generator", "dry-run-migrations"]).unwrap();
+        assert!(matches!(cli.mode, Some(Mode::DryRunMigrations)));
+        let args = ["delivery_note_generator", "dry-run-migrations", "serve"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_clients() {
+        let mut engine = setup_test_engine("test_cli_clients");
+        let add = [
+            "client",
+            "add",
+            "--id",
+            "1",
+            "--name",
+            "Alice",
+            "--address1",
+            "1 place Bellecour",
+            "--postal-code",
+            "1000",
+            "--city",
+            "Lyon",
+        ];
+        match run_args(&mut engine, &add) {
+            Err(e) => assert_eq!(e.exit_code(), ExitCode::from(EXIT_USAGE)),
+            Ok(_) => panic!("expected an invalid postal code"),
+        }
+        let add = [&add[..], &["--country", "BE"]].concat();
+        run_args(&mut engine, &add).unwrap();
+        match run_args(&mut engine, &add) {
+            Err(e) => assert_eq!(e.exit_code(), ExitCode::from(EXIT_CONFLICT)),
+            Ok(_) => panic!("expected a duplicate client"),
+        }
+
+        let list = run_args(&mut engine, &["client", "list", "--json"]).unwrap();
+        let list: Value = serde_json::from_str(&list).unwrap();
+        assert_eq!(list[0]["name"], "Alice");
+        assert_eq!(list[0]["language"], "fr");
+        assert_eq!(list[0]["country"], "BE");
+        let shown = run_args(&mut engine, &["client", "show", "1"]).unwrap();
+        assert!(shown.contains("city: Lyon\n"), "{}", shown);
+
+        run_args(&mut engine, &["client", "delete", "1"]).unwrap();
+        match run_args(&mut engine, &["client", "show", "1"]) {
+            Err(e) => assert_eq!(e.exit_code(), ExitCode::from(EXIT_NOT_FOUND)),
+            Ok(_) => panic!("expected the client to be deleted"),
+        }
+    }
+
+    #[test]
+    fn test_generate_note() {
+        let folder = "test_cli_notes";
+        let mut engine = setup_test_engine(folder);
+        engine
+            .save_sender(Sender {
+                id: "main".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        run_args(
+            &mut engine,
+            &[
+                "client",
+                "add",
+                "--id",
+                "1",
+                "--name",
+                "Alice",
+                "--address1",
+                "1 place Bellecour",
+                "--postal-code",
+                "69002",
+                "--city",
+                "Lyon",
+            ],
+        )
+        .unwrap();
+        let products = Path::new(folder).join("products.csv");
+        fs::write(&products, "reference;id;description\nCH-01;1;Chaise\n").unwrap();
+        let imported = run_args(
+            &mut engine,
+            &["product", "import", products.to_str().unwrap()],
+        )
+        .unwrap();
+        assert_eq!(imported, "1 products imported, 1 new, 0 replaced\n");
+        // The description is optional
+        run_args(&mut engine, &["product", "add", "--id", "2"]).unwrap();
+        run_args(
+            &mut engine,
+            &["product", "add", "--id", "4", "--vat-rate", "5.5"],
+        )
+        .unwrap();
+        assert_eq!(engine.find_product("4").unwrap().vat_rate(), dec!(5.5));
+        fs::write(&products, "reference;id\nTA-01;3\nBA-01;\n").unwrap();
+        let error = run_args(
+            &mut engine,
+            &["product", "import", products.to_str().unwrap()],
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("on line 3"), "{}", error);
+
+        let out = Path::new(folder).join("note.pdf");
+        let generated = run_args(
+            &mut engine,
+            &[
+                "note",
+                "generate",
+                "--client",
+                "1",
+                "--item",
+                "CH-01:3",
+                "--date",
+                "2026-05-02",
+                "--out",
+                out.to_str().unwrap(),
+                "--json",
+            ],
+        )
+        .unwrap();
+        let generated: Value = serde_json::from_str(&generated).unwrap();
+        assert_eq!(generated["number"], "BL-2026-00001");
+        assert!(fs::read(&out).unwrap().starts_with(b"%PDF"));
+
+        let reprint = [
+            "note",
+            "reprint",
+            "BL-2026-00001",
+            "--out",
+            out.to_str().unwrap(),
+        ];
+        match run_args(&mut engine, &reprint) {
+            Err(e) => assert_eq!(e.exit_code(), ExitCode::from(EXIT_CONFLICT)),
+            Ok(_) => panic!("expected the file to exist"),
+        }
+        let text = Path::new(folder).join("note.txt");
+        let reprint = [
+            "note",
+            "reprint",
+            "BL-2026-00001",
+            "--out",
+            text.to_str().unwrap(),
+        ];
+        run_args(&mut engine, &reprint).unwrap();
+        assert!(fs::read_to_string(&text).unwrap().contains("Chaise"));
+
+        // No note is saved when its file cannot be written
+        let unknown = Path::new(folder).join("note.xyz");
+        for (path, code) in [(&out, EXIT_CONFLICT), (&unknown, EXIT_USAGE)] {
+            let generate = [
+                "note",
+                "generate",
+                "--client",
+                "1",
+                "--item",
+                "CH-01:1",
+                "--out",
+                path.to_str().unwrap(),
+            ];
+            match run_args(&mut engine, &generate) {
+                Err(e) => assert_eq!(e.exit_code(), ExitCode::from(code)),
+                Ok(_) => panic!("expected {} to be refused", path.display()),
+            }
+        }
+        assert_eq!(engine.get_delivery_notes().len(), 1);
+
+        // A note saved but not written tells its number
+        engine
+            .save_sender(Sender {
+                id: "missing-logo".to_string(),
+                logo_path: format!("{}/missing.png", folder),
+                ..Default::default()
+            })
+            .unwrap();
+        let broken = Path::new(folder).join("broken.pdf");
+        let generate = [
+            "note",
+            "generate",
+            "--client",
+            "1",
+            "--sender",
+            "missing-logo",
+            "--item",
+            "CH-01:1",
+            "--date",
+            "2026-05-02",
+            "--out",
+            broken.to_str().unwrap(),
+        ];
+        let error = run_args(&mut engine, &generate).unwrap_err();
+        assert!(error.to_string().contains("BL-2026-00002"), "{}", error);
+    }
+}
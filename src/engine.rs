@@ -1,57 +1,1179 @@
-use std::{fs::File, io::BufWriter};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, NaiveDate};
 
 use crate::{
-    generators::Report,
-    models::{client::Client, product::Product},
-    storage::{Storage, StorageType},
+    error::Error,
+    generators::{formats::Format, output::OutputSettings, Report},
+    models::{
+        self,
+        client::Client,
+        counter::{Counter, Numbering},
+        delivery_note::DeliveryNote,
+        invoice::Invoice,
+        item::Item,
+        product::Product,
+        quote::Quote,
+        sender::Sender,
+        validation::{check, Validate},
+    },
+    storage::{
+        migrate, migration::MigrationReport, sqlite_storage::SqliteStorage, Savable, Storage,
+        StorageError, StorageType,
+    },
 };
 
 pub struct Engine {
     storage: StorageType,
     clients: Vec<Client>,
     products: Vec<Product>,
+    delivery_notes: Vec<DeliveryNote>,
+    invoices: Vec<Invoice>,
+    quotes: Vec<Quote>,
+    senders: Vec<Sender>,
+    numbering: Numbering,
+    invoice_numbering: Numbering,
+    quote_numbering: Numbering,
+    output: OutputSettings,
 }
 
 impl Engine {
-    pub fn new(storage_type: StorageType) -> Result<Self, String> {
+    pub fn new(storage_type: StorageType) -> Result<Self, StorageError> {
         let storage = storage_type.build();
         storage.init()?;
+        Self::run_migrations(&storage, false)?;
         let clients = storage.load::<Client>()?;
         let products = storage.load::<Product>()?;
+        let delivery_notes = storage.load::<DeliveryNote>()?;
+        let invoices = storage.load::<Invoice>()?;
+        let quotes = storage.load::<Quote>()?;
+        let senders = storage.load::<Sender>()?;
+        let numberings = storage.load::<Numbering>()?;
+        let output = storage.load::<OutputSettings>()?.into_iter().next();
+        let numbering = |default: Numbering| {
+            numberings
+                .iter()
+                .find(|numbering| numbering.id == default.id)
+                .cloned()
+                .unwrap_or(default)
+        };
         return Ok(Engine {
             storage: storage_type,
             clients,
             products,
+            delivery_notes,
+            invoices,
+            quotes,
+            senders,
+            numbering: numbering(Numbering::default()),
+            invoice_numbering: numbering(Numbering::invoice()),
+            quote_numbering: numbering(Numbering::quote()),
+            output: output.unwrap_or_default(),
         });
     }
 
+    /// Tells how the stores would be migrated by `Engine::new`, without changing them.
+    pub fn check_migrations(
+        storage_type: &StorageType,
+    ) -> Result<Vec<MigrationReport>, StorageError> {
+        let storage = storage_type.build();
+        storage.init()?;
+        Self::run_migrations(&storage, true)
+    }
+
+    fn run_migrations(
+        storage: &impl Storage,
+        dry_run: bool,
+    ) -> Result<Vec<MigrationReport>, StorageError> {
+        let migrations = models::migrations();
+        let reports = [
+            migrations.run::<Client>(storage, dry_run)?,
+            migrations.run::<Product>(storage, dry_run)?,
+            migrations.run::<DeliveryNote>(storage, dry_run)?,
+            migrations.run::<Invoice>(storage, dry_run)?,
+            migrations.run::<Quote>(storage, dry_run)?,
+            migrations.run::<Sender>(storage, dry_run)?,
+            migrations.run::<Counter>(storage, dry_run)?,
+            migrations.run::<Numbering>(storage, dry_run)?,
+            migrations.run::<OutputSettings>(storage, dry_run)?,
+        ];
+        Ok(reports.into_iter().flatten().collect())
+    }
+
+    /// Copies all clients and products of `from` into a new SQLite database at
+    /// `path`, e.g. to move the CSV files of a `FileStorage` into a database.
+    /// They are copied into a temporary file that only becomes `path` once
+    /// both are copied, so a failed import leaves no database behind and is
+    /// tried again on the next start.
+    pub fn migrate(from: &StorageType, path: &str) -> Result<(), StorageError> {
+        let temporary = format!("{}.import", path);
+        // Left by an import that was interrupted
+        if Path::new(&temporary).exists() {
+            fs::remove_file(&temporary).map_err(|e| StorageError::io(Path::new(&temporary), e))?;
+        }
+        let from = from.build();
+        let to = SqliteStorage::new(&temporary);
+        let copied = from
+            .init()
+            .and_then(|_| to.init())
+            .and_then(|_| migrate::<Client>(&from, &to))
+            .and_then(|_| migrate::<Product>(&from, &to));
+        if let Err(e) = copied {
+            let _ = fs::remove_file(&temporary);
+            return Err(e);
+        }
+        fs::rename(&temporary, path).map_err(|e| StorageError::io(Path::new(path), e))
+    }
+
     pub fn get_products(&self) -> &Vec<Product> {
         &self.products
     }
 
-    pub fn add_product(&mut self, product: Product) {
+    /// Adds and saves a product, unless it is invalid or its id is taken.
+    pub fn create_product(&mut self, product: Product) -> Result<(), Error> {
+        check("product", product.id(), &product)?;
+        if self.products.iter().any(|p| p.id() == product.id()) {
+            return Err(Error::DuplicateId {
+                kind: "product",
+                id: product.id().clone(),
+            });
+        }
+        self.storage.build().save(&product)?;
         self.products.push(product);
+        Ok(())
+    }
+
+    /// The product whose reference, or else whose id, is `reference`.
+    pub fn find_product(&self, reference: &str) -> Result<&Product, Error> {
+        self.products
+            .iter()
+            .find(|p| p.reference() == reference)
+            .or_else(|| self.products.iter().find(|p| p.id() == reference))
+            .ok_or_else(|| Error::NotFound {
+                kind: "product",
+                id: reference.to_owned(),
+            })
+    }
+
+    /// Replaces and saves the product whose id is `id`. When the id changes, the
+    /// product is saved under its new id and the old row is deleted from the storage.
+    pub fn update_product(&mut self, id: &str, product: Product) -> Result<(), Error> {
+        let idx = match self.products.iter().position(|p| p.id() == id) {
+            Some(idx) => idx,
+            None => {
+                return Err(Error::NotFound {
+                    kind: "product",
+                    id: id.to_owned(),
+                })
+            }
+        };
+        check("product", id, &product)?;
+        if product.id() != id && self.products.iter().any(|p| p.id() == product.id()) {
+            return Err(Error::DuplicateId {
+                kind: "product",
+                id: product.id().clone(),
+            });
+        }
+        let storage = self.storage.build();
+        storage.save(&product)?;
+        if product.id() != id {
+            storage.delete::<Product>(id)?;
+        }
+        self.products[idx] = product;
+        Ok(())
+    }
+
+    pub fn remove_product(&mut self, id: &str) -> Result<Product, Error> {
+        let idx = match self.products.iter().position(|p| p.id() == id) {
+            Some(idx) => idx,
+            None => {
+                return Err(Error::NotFound {
+                    kind: "product",
+                    id: id.to_owned(),
+                })
+            }
+        };
+        self.check_not_in_notes("product", id, |note| {
+            note.items.iter().any(|item| item.product().id() == id)
+        })?;
+        self.storage.build().delete::<Product>(id)?;
+        Ok(self.products.remove(idx))
+    }
+
+    /// Adds the products read from a file, each with the line it comes from,
+    /// replacing those with the same id. Nothing is saved when one is invalid,
+    /// and the error tells its line. Returns how many were new.
+    pub fn import_products(&mut self, products: Vec<(usize, Product)>) -> Result<usize, Error> {
+        for (line, product) in &products {
+            let errors = product.validate();
+            if let Some(error) = errors.first() {
+                let value = product
+                    .to_fields()
+                    .into_iter()
+                    .find(|field| field.0 == error.field)
+                    .map(|field| field.1)
+                    .unwrap_or_default();
+                let message = errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                return Err(StorageError::invalid_value(&error.field, &value, message)
+                    .at_line(*line)
+                    .into());
+            }
+        }
+        let products = products
+            .into_iter()
+            .map(|(_, product)| product)
+            .collect::<Vec<Product>>();
+        self.storage.build().save_all(&products)?;
+        let mut added = 0;
+        for product in products {
+            match self.products.iter().position(|p| p.id() == product.id()) {
+                Some(idx) => self.products[idx] = product,
+                None => {
+                    self.products.push(product);
+                    added += 1;
+                }
+            }
+        }
+        Ok(added)
     }
 
     pub fn get_clients(&self) -> &Vec<Client> {
         &self.clients
     }
 
-    pub fn add_client(&mut self, client: Client) {
+    /// Adds and saves a client, unless it is invalid or its id is taken.
+    pub fn create_client(&mut self, client: Client) -> Result<(), Error> {
+        check("client", &client.id, &client)?;
+        if self.clients.iter().any(|c| c.id == client.id) {
+            return Err(Error::DuplicateId {
+                kind: "client",
+                id: client.id.clone(),
+            });
+        }
+        self.storage.build().save(&client)?;
         self.clients.push(client);
+        Ok(())
+    }
+
+    pub fn find_client(&self, id: &str) -> Result<&Client, Error> {
+        self.clients
+            .iter()
+            .find(|c| c.id == id)
+            .ok_or_else(|| Error::NotFound {
+                kind: "client",
+                id: id.to_owned(),
+            })
+    }
+
+    /// Replaces and saves the client whose id is `id`. When the id changes, the
+    /// client is saved under its new id and the old row is deleted from the storage.
+    pub fn update_client(&mut self, id: &str, client: Client) -> Result<(), Error> {
+        let idx = match self.clients.iter().position(|c| c.id == id) {
+            Some(idx) => idx,
+            None => {
+                return Err(Error::NotFound {
+                    kind: "client",
+                    id: id.to_owned(),
+                })
+            }
+        };
+        check("client", id, &client)?;
+        if client.id != id && self.clients.iter().any(|c| c.id == client.id) {
+            return Err(Error::DuplicateId {
+                kind: "client",
+                id: client.id.clone(),
+            });
+        }
+        let storage = self.storage.build();
+        storage.save(&client)?;
+        if client.id != id {
+            storage.delete::<Client>(id)?;
+        }
+        self.clients[idx] = client;
+        Ok(())
+    }
+
+    pub fn remove_client(&mut self, id: &str) -> Result<Client, Error> {
+        let idx = match self.clients.iter().position(|c| c.id == id) {
+            Some(idx) => idx,
+            None => {
+                return Err(Error::NotFound {
+                    kind: "client",
+                    id: id.to_owned(),
+                })
+            }
+        };
+        self.check_not_in_notes("client", id, |note| note.client.id == id)?;
+        self.storage.build().delete::<Client>(id)?;
+        Ok(self.clients.remove(idx))
+    }
+
+    pub fn get_senders(&self) -> &Vec<Sender> {
+        &self.senders
+    }
+
+    /// Saves a company profile, replacing the profile with the same id.
+    pub fn save_sender(&mut self, sender: Sender) -> Result<(), Error> {
+        check("sender", &sender.id, &sender)?;
+        self.storage.build().save(&sender)?;
+        match self.senders.iter().position(|s| s.id == sender.id) {
+            Some(idx) => self.senders[idx] = sender,
+            None => self.senders.push(sender),
+        }
+        Ok(())
+    }
+
+    /// The sender profile `id`, or the only one when no id is given.
+    pub fn find_sender(&self, id: Option<&str>) -> Result<&Sender, Error> {
+        if let Some(id) = id {
+            return self
+                .senders
+                .iter()
+                .find(|s| s.id == id)
+                .ok_or_else(|| Error::NotFound {
+                    kind: "sender",
+                    id: id.to_owned(),
+                });
+        }
+        match self.senders.as_slice() {
+            [sender] => Ok(sender),
+            senders => Err(Error::SenderNotChosen {
+                count: senders.len(),
+            }),
+        }
+    }
+
+    /// Removes a company profile. The documents keep their copy of it.
+    pub fn remove_sender(&mut self, id: &str) -> Result<Sender, Error> {
+        let idx = match self.senders.iter().position(|s| s.id == id) {
+            Some(idx) => idx,
+            None => {
+                return Err(Error::NotFound {
+                    kind: "sender",
+                    id: id.to_owned(),
+                })
+            }
+        };
+        self.storage.build().delete::<Sender>(id)?;
+        Ok(self.senders.remove(idx))
+    }
+
+    /// Fails if saved delivery notes still refer to the removed entity.
+    fn check_not_in_notes(
+        &self,
+        kind: &'static str,
+        id: &str,
+        refers: impl Fn(&DeliveryNote) -> bool,
+    ) -> Result<(), Error> {
+        let notes = self
+            .delivery_notes
+            .iter()
+            .filter(|note| refers(note))
+            .map(|note| note.number.clone())
+            .collect::<Vec<String>>();
+        if notes.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InUse {
+                kind,
+                id: id.to_owned(),
+                notes,
+            })
+        }
+    }
+
+    pub fn get_delivery_notes(&self) -> &Vec<DeliveryNote> {
+        &self.delivery_notes
+    }
+
+    pub fn find_delivery_note(&self, number: &str) -> Result<&DeliveryNote, Error> {
+        self.delivery_notes
+            .iter()
+            .find(|note| note.number == number)
+            .ok_or_else(|| Error::NotFound {
+                kind: "delivery note",
+                id: number.to_owned(),
+            })
+    }
+
+    /// The numberings of delivery notes, quotes and invoices.
+    pub fn get_numberings(&self) -> [&Numbering; 3] {
+        [
+            &self.numbering,
+            &self.quote_numbering,
+            &self.invoice_numbering,
+        ]
+    }
+
+    /// Saves and uses the numbering of the documents `numbering.id`, unless it
+    /// is invalid. The numbers already given are kept.
+    pub fn set_numbering(&mut self, numbering: Numbering) -> Result<(), Error> {
+        check("numbering", &numbering.id, &numbering)?;
+        let current = [
+            &mut self.numbering,
+            &mut self.quote_numbering,
+            &mut self.invoice_numbering,
+        ]
+        .into_iter()
+        .find(|current| current.id == numbering.id)
+        .ok_or_else(|| Error::NotFound {
+            kind: "numbering",
+            id: numbering.id.clone(),
+        })?;
+        self.storage.build().save(&numbering)?;
+        *current = numbering;
+        Ok(())
+    }
+
+    /// The next number of `numbering` for the year of `date`, skipping the
+    /// numbers already `taken`, and the counter to save once it is used.
+    fn next_number(
+        &self,
+        numbering: &Numbering,
+        date: NaiveDate,
+        taken: impl Fn(&str) -> bool,
+    ) -> Result<(String, Counter), StorageError> {
+        let counter_id = numbering.counter_id(date.year());
+        let mut counter = self
+            .storage
+            .build()
+            .load::<Counter>()?
+            .into_iter()
+            .find(|counter| counter.id == counter_id)
+            .unwrap_or(Counter {
+                id: counter_id,
+                value: 0,
+            });
+        // A document may have been saved without its counter being updated
+        loop {
+            counter.value += 1;
+            let number = numbering.format(date.year(), counter.value);
+            if !taken(&number) {
+                return Ok((number, counter));
+            }
+        }
+    }
+
+    /// Creates and saves a delivery note with the next number of the year of `date`.
+    /// The counter is only increased once the note is saved, so numbers have no gaps.
+    pub fn create_delivery_note(
+        &mut self,
+        sender: Sender,
+        client: Client,
+        items: &[Item],
+        date: NaiveDate,
+    ) -> Result<DeliveryNote, Error> {
+        self.add_delivery_note(sender, client, items.to_vec(), date, String::new())
+    }
+
+    fn add_delivery_note(
+        &mut self,
+        sender: Sender,
+        client: Client,
+        items: Vec<Item>,
+        date: NaiveDate,
+        quote: String,
+    ) -> Result<DeliveryNote, Error> {
+        let (number, counter) = self.next_number(&self.numbering, date, |number| {
+            self.delivery_notes.iter().any(|note| note.number == number)
+        })?;
+
+        let note = DeliveryNote {
+            number,
+            date,
+            sender,
+            client,
+            items,
+            quote,
+        };
+        check("delivery note", &note.number, &note)?;
+        let storage = self.storage.build();
+        storage.save(&note)?;
+        storage.save(&counter)?;
+        self.delivery_notes.push(note.clone());
+        Ok(note)
+    }
+
+    pub fn get_quotes(&self) -> &Vec<Quote> {
+        &self.quotes
+    }
+
+    /// Creates and saves a quote with the next number of the year of `date`.
+    pub fn create_quote(
+        &mut self,
+        sender: Sender,
+        client: Client,
+        items: &[Item],
+        date: NaiveDate,
+        valid_until: NaiveDate,
+    ) -> Result<Quote, Error> {
+        let (number, counter) = self.next_number(&self.quote_numbering, date, |number| {
+            self.quotes.iter().any(|quote| quote.number == number)
+        })?;
+
+        let quote = Quote {
+            number,
+            date,
+            valid_until,
+            sender,
+            client,
+            items: items.to_vec(),
+            delivery_note: String::new(),
+        };
+        check("quote", &quote.number, &quote)?;
+        let storage = self.storage.build();
+        storage.save(&quote)?;
+        storage.save(&counter)?;
+        self.quotes.push(quote.clone());
+        Ok(quote)
+    }
+
+    /// Converts the quote `quote_number`, accepted by the client on `date`, into
+    /// a delivery note. The note and the quote keep the number of each other.
+    pub fn accept_quote(
+        &mut self,
+        quote_number: &str,
+        date: NaiveDate,
+    ) -> Result<DeliveryNote, Error> {
+        let idx = match self
+            .quotes
+            .iter()
+            .position(|quote| quote.number == quote_number)
+        {
+            Some(idx) => idx,
+            None => {
+                return Err(Error::NotFound {
+                    kind: "quote",
+                    id: quote_number.to_owned(),
+                })
+            }
+        };
+        // The note may have been saved without the quote being updated
+        let converted = self
+            .delivery_notes
+            .iter()
+            .find(|note| note.quote == quote_number)
+            .map(|note| note.number.clone());
+        let quote = &self.quotes[idx];
+        if let Some(note) = converted.or(quote.is_accepted().then(|| quote.delivery_note.clone())) {
+            return Err(Error::AlreadyConverted {
+                quote: quote_number.to_owned(),
+                note,
+            });
+        }
+        if date > quote.valid_until {
+            return Err(Error::QuoteExpired {
+                quote: quote_number.to_owned(),
+                valid_until: quote.valid_until,
+            });
+        }
+
+        let quote = quote.clone();
+        let note = self.add_delivery_note(
+            quote.sender.clone(),
+            quote.client.clone(),
+            quote.items.clone(),
+            date,
+            quote.number.clone(),
+        )?;
+        let quote = Quote {
+            delivery_note: note.number.clone(),
+            ..quote
+        };
+        self.storage.build().save(&quote)?;
+        self.quotes[idx] = quote;
+        Ok(note)
+    }
+
+    pub fn get_invoices(&self) -> &Vec<Invoice> {
+        &self.invoices
+    }
+
+    /// Creates and saves the invoice of the delivery note `note_number`, each
+    /// item being billed at the VAT rate of its product. A delivery note is
+    /// billed once.
+    pub fn create_invoice(
+        &mut self,
+        note_number: &str,
+        date: NaiveDate,
+        due_date: NaiveDate,
+    ) -> Result<Invoice, Error> {
+        let note = match self
+            .delivery_notes
+            .iter()
+            .find(|note| note.number == note_number)
+        {
+            Some(note) => note,
+            None => {
+                return Err(Error::NotFound {
+                    kind: "delivery note",
+                    id: note_number.to_owned(),
+                })
+            }
+        };
+        if let Some(invoice) = self
+            .invoices
+            .iter()
+            .find(|invoice| invoice.delivery_note == note_number)
+        {
+            return Err(Error::AlreadyInvoiced {
+                note: note_number.to_owned(),
+                invoice: invoice.number.clone(),
+            });
+        }
+        let (number, counter) = self.next_number(&self.invoice_numbering, date, |number| {
+            self.invoices.iter().any(|invoice| invoice.number == number)
+        })?;
+        let invoice = Invoice::from_delivery_note(number, note, date, due_date);
+
+        let storage = self.storage.build();
+        storage.save(&invoice)?;
+        storage.save(&counter)?;
+        self.invoices.push(invoice.clone());
+        Ok(invoice)
+    }
+
+    pub fn output(&self) -> &OutputSettings {
+        &self.output
+    }
+
+    /// Changes the output settings until the engine is dropped, e.g. for one
+    /// command.
+    pub fn set_output(&mut self, output: OutputSettings) {
+        self.output = output;
     }
 
-    pub fn save(&mut self) {
-        for client in &self.clients {
-            self.storage.build().save(client);
+    /// Saves the output settings, which are then used on the next starts too.
+    pub fn save_output(&mut self, output: OutputSettings) -> Result<(), StorageError> {
+        self.storage.build().save(&output)?;
+        self.output = output;
+        Ok(())
+    }
+
+    /// Writes the report in `format` in the output directory and returns the
+    /// path of the file, unless the report is invalid. An existing file is
+    /// only replaced when the settings allow it.
+    pub fn generate_report(&self, report: impl Report, format: Format) -> Result<PathBuf, Error> {
+        let path = self.output.path(&report.name(), format);
+        self.write_report(report, format, &path)?;
+        Ok(path)
+    }
+
+    /// Writes the report in `format` at `path`, which is only replaced when
+    /// the settings allow it.
+    pub fn write_report(
+        &self,
+        report: impl Report,
+        format: Format,
+        path: &Path,
+    ) -> Result<(), Error> {
+        report.validate()?;
+        let bytes = format.render(&report.document()?)?;
+        let path = path.to_owned();
+        let output_error = |source| Error::Output {
+            path: path.clone(),
+            source,
+        };
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(output_error)?;
+        }
+        let mut options = OpenOptions::new();
+        if self.output.overwrite {
+            options.write(true).create(true).truncate(true);
+        } else {
+            options.write(true).create_new(true);
+        }
+        let mut file = match options.open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                return Err(Error::FileExists { path })
+            }
+            Err(e) => return Err(output_error(e)),
+        };
+        file.write_all(&bytes).map_err(output_error)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::dec;
+    use std::{fs, path::Path};
+
+    fn setup_test_engine(folder: &'static str) -> Engine {
+        let path = Path::new(folder);
+        if path.exists() {
+            fs::remove_dir_all(path).unwrap();
+        }
+        fs::create_dir(path).unwrap();
+        Engine::new(StorageType::FileStorage(folder)).unwrap()
+    }
+
+    fn client(id: &str, name: &str) -> Client {
+        Client {
+            id: id.to_string(),
+            name: name.to_string(),
+            address1: "1 place Bellecour".to_string(),
+            postal_code: "69002".to_string(),
+            city: "Lyon".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn items() -> Vec<Item> {
+        vec![Item::new(
+            Product::new("1", "Chaise", "CH-01"),
+            dec!(12.50),
+            dec!(3),
+        )]
+    }
+
+    #[test]
+    fn test_rename_client_removes_old_row() {
+        let mut engine = setup_test_engine("test_engine_rename_client");
+        engine.create_client(client("1", "Alice")).unwrap();
+        engine.create_client(client("2", "Bob")).unwrap();
+
+        assert!(engine.update_client("1", client("3", "Alice")).is_ok());
+        assert!(engine.update_client("3", client("2", "Alice")).is_err());
+        assert!(engine.update_client("4", client("4", "Carol")).is_err());
+        // A change without a new id is saved too
+        assert!(engine.update_client("2", client("2", "Bob Martin")).is_ok());
+
+        let storage = StorageType::FileStorage("test_engine_rename_client").build();
+        let stored = storage.load::<Client>().unwrap();
+        assert_eq!(
+            stored,
+            vec![client("2", "Bob Martin"), client("3", "Alice")]
+        );
+    }
+
+    fn sender() -> Sender {
+        Sender {
+            id: "BU0".to_string(),
+            name: "Alexandre".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_failed_migration_leaves_no_database() {
+        let folder = "test_engine_migrate";
+        let mut engine = setup_test_engine(folder);
+        engine.create_client(client("1", "Alice")).unwrap();
+        fs::write(format!("{}/Product.csv", folder), "description\nChaise\n").unwrap();
+        let path = format!("{}/db.sqlite", folder);
+        let from = StorageType::FileStorage(folder);
+
+        assert!(Engine::migrate(&from, &path).is_err());
+        assert!(!Path::new(&path).exists());
+        assert!(!Path::new(&format!("{}.import", path)).exists());
+
+        fs::write(
+            format!("{}/Product.csv", folder),
+            "id;description;reference\n1;Chaise;CH-01\n",
+        )
+        .unwrap();
+        Engine::migrate(&from, &path).unwrap();
+        let database = SqliteStorage::new(&path);
+        assert_eq!(database.load::<Client>().unwrap().len(), 1);
+        assert_eq!(database.load::<Product>().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_start_with_free_form_quantities() {
+        let folder = "test_engine_legacy_notes";
+        let path = Path::new(folder);
+        if path.exists() {
+            fs::remove_dir_all(path).unwrap();
+        }
+        fs::create_dir(path).unwrap();
+        fs::write(
+            path.join("delivery_note.csv"),
+            "number;date;sender_name;sender_addr1;sender_addr2;sender_postal_code;\
+             sender_city;client_id;client_name;client_address1;client_address2;\
+             client_postal_code;client_city;items\n\
+             BL-2026-00001;2026-03-14;Alexandre;43 rue Courtalon;;10000;Troyes;C1;\
+             Dupont;;;;;1,Sable,SA-01,12,4 sacs\n",
+        )
+        .unwrap();
+
+        let engine = Engine::new(StorageType::FileStorage(folder)).unwrap();
+        let item = &engine.get_delivery_notes()[0].items[0];
+        assert_eq!(item.product().description(), "Sable (4 sacs)");
+        assert_eq!(item.quantity(), dec!(4));
+    }
+
+    #[test]
+    fn test_delivery_note_numbers_are_sequential() {
+        let folder = "test_engine_note_numbers";
+        let mut engine = setup_test_engine(folder);
+        let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+        let first = engine
+            .create_delivery_note(sender(), client("1", "Alice"), &items(), date)
+            .unwrap();
+        let second = engine
+            .create_delivery_note(sender(), client("1", "Alice"), &items(), date)
+            .unwrap();
+        let next_year = engine
+            .create_delivery_note(
+                sender(),
+                client("1", "Alice"),
+                &items(),
+                NaiveDate::from_ymd_opt(2027, 1, 4).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(first.number, "BL-2026-00001");
+        assert_eq!(second.number, "BL-2026-00002");
+        assert_eq!(next_year.number, "BL-2027-00001");
+
+        // Numbering goes on after a restart
+        let mut engine = Engine::new(StorageType::FileStorage(folder)).unwrap();
+        assert_eq!(engine.get_delivery_notes().len(), 3);
+        let third = engine
+            .create_delivery_note(sender(), client("1", "Alice"), &items(), date)
+            .unwrap();
+        assert_eq!(third.number, "BL-2026-00003");
+    }
+
+    #[test]
+    fn test_set_numbering() {
+        let folder = "test_engine_set_numbering";
+        let mut engine = setup_test_engine(folder);
+        let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+        let numbering = Numbering {
+            prefix: "LIV".to_string(),
+            width: 3,
+            ..Numbering::default()
+        };
+        engine.set_numbering(numbering.clone()).unwrap();
+        let invalid = Numbering {
+            prefix: String::new(),
+            ..Numbering::quote()
+        };
+        assert!(engine.set_numbering(invalid).is_err());
+        let unknown = Numbering {
+            id: "receipt".to_string(),
+            ..Numbering::default()
+        };
+        assert!(engine.set_numbering(unknown).is_err());
+
+        // The numbering is kept after a restart
+        let mut engine = Engine::new(StorageType::FileStorage(folder)).unwrap();
+        assert_eq!(
+            engine.get_numberings(),
+            [&numbering, &Numbering::quote(), &Numbering::invoice()]
+        );
+        let note = engine
+            .create_delivery_note(sender(), client("1", "Alice"), &items(), date)
+            .unwrap();
+        assert_eq!(note.number, "LIV-2026-001");
+    }
+
+    #[test]
+    fn test_invoice_delivery_note() {
+        let folder = "test_engine_invoice";
+        let mut engine = setup_test_engine(folder);
+        let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+        let due_date = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        let mut book = Product::new("2", "Livre", "LI-01");
+        *book.vat_rate_mut() = dec!(5.5);
+        let items = [
+            Item::new(Product::new("1", "Chaise", "CH-01"), dec!(12.50), dec!(2)),
+            Item::new(book, dec!(8), dec!(1)),
+        ];
+        let note = engine
+            .create_delivery_note(sender(), client("1", "Alice"), &items, date)
+            .unwrap();
+
+        let invoice = engine.create_invoice(&note.number, date, due_date).unwrap();
+        assert_eq!(invoice.number, "FA-2026-00001");
+        assert_eq!(invoice.delivery_note, note.number);
+        // Each line is billed at the rate of its product
+        assert_eq!(
+            invoice
+                .vat_breakdown()
+                .iter()
+                .map(|vat| (vat.rate, vat.base, vat.amount))
+                .collect::<Vec<_>>(),
+            vec![
+                (dec!(5.5), dec!(8), dec!(0.44)),
+                (dec!(20), dec!(25.00), dec!(5.00))
+            ]
+        );
+        assert_eq!(invoice.total_including_tax(), dec!(38.44));
+
+        match engine.create_invoice(&note.number, date, due_date) {
+            Err(Error::AlreadyInvoiced { invoice, .. }) => assert_eq!(invoice, "FA-2026-00001"),
+            _ => panic!("expected the note to be already invoiced"),
+        }
+        match engine.create_invoice("BL-2026-00099", date, due_date) {
+            Err(Error::NotFound { kind, .. }) => assert_eq!(kind, "delivery note"),
+            _ => panic!("expected a missing delivery note"),
         }
-        for product in &self.products {
-            self.storage.build().save(product);
+
+        let engine = Engine::new(StorageType::FileStorage(folder)).unwrap();
+        assert_eq!(engine.get_invoices(), &vec![invoice]);
+    }
+
+    #[test]
+    fn test_accept_quote() {
+        let folder = "test_engine_quote";
+        let mut engine = setup_test_engine(folder);
+        let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+        let valid_until = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        let items = [Item::new(
+            Product::new("1", "Chaise", "CH-01"),
+            dec!(12.50),
+            dec!(2),
+        )];
+        let quote = engine
+            .create_quote(sender(), client("1", "Alice"), &items, date, valid_until)
+            .unwrap();
+        assert_eq!(quote.number, "DE-2026-00001");
+
+        match engine.accept_quote(&quote.number, NaiveDate::from_ymd_opt(2026, 6, 2).unwrap()) {
+            Err(Error::QuoteExpired { valid_until, .. }) => {
+                assert_eq!(valid_until, quote.valid_until)
+            }
+            _ => panic!("expected the quote to be expired"),
+        }
+        let note = engine.accept_quote(&quote.number, valid_until).unwrap();
+        assert_eq!(note.number, "BL-2026-00001");
+        assert_eq!(note.quote, quote.number);
+        assert_eq!(note.items, quote.items);
+        match engine.accept_quote(&quote.number, valid_until) {
+            Err(Error::AlreadyConverted { note: number, .. }) => assert_eq!(number, note.number),
+            _ => panic!("expected the quote to be already converted"),
+        }
+
+        let invoice = engine
+            .create_invoice(&note.number, valid_until, valid_until)
+            .unwrap();
+        assert_eq!(invoice.delivery_note, note.number);
+
+        let engine = Engine::new(StorageType::FileStorage(folder)).unwrap();
+        assert_eq!(engine.get_quotes()[0].delivery_note, note.number);
+        assert_eq!(engine.get_delivery_notes()[0].quote, quote.number);
+    }
+
+    #[test]
+    fn test_sender_profiles() {
+        let folder = "test_engine_senders";
+        let mut engine = setup_test_engine(folder);
+        let mut profile = Sender {
+            id: "BU1".to_string(),
+            postal_code: "1000".to_string(),
+            siret: "123 456 789 00012".to_string(),
+            ..sender()
+        };
+        match engine.save_sender(profile.clone()) {
+            Err(Error::Invalid { errors, .. }) => assert_eq!(errors[0].field, "postal_code"),
+            _ => panic!("expected the postal code to be refused"),
+        }
+        // The leading zero is kept
+        profile.postal_code = "01000".to_string();
+        engine.save_sender(profile.clone()).unwrap();
+        engine.save_sender(sender()).unwrap();
+        profile.email = "contact@example.com".to_string();
+        engine.save_sender(profile.clone()).unwrap();
+        assert_eq!(engine.get_senders().len(), 2);
+
+        let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+        engine
+            .create_delivery_note(profile.clone(), client("1", "Alice"), &items(), date)
+            .unwrap();
+        engine.remove_sender("BU1").unwrap();
+
+        let engine = Engine::new(StorageType::FileStorage(folder)).unwrap();
+        assert_eq!(engine.get_senders(), &vec![sender()]);
+        // The note keeps its copy of the removed profile
+        assert_eq!(engine.get_delivery_notes()[0].sender, profile);
+    }
+
+    #[test]
+    fn test_generate_report_output() {
+        let folder = "test_engine_reports";
+        let mut engine = setup_test_engine(folder);
+        engine.set_output(OutputSettings {
+            directory: Path::new(folder).join("reports"),
+            ..Default::default()
+        });
+        let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+        let note = engine
+            .create_delivery_note(sender(), client("1", "Alice Martin"), &items(), date)
+            .unwrap();
+        let report = || crate::generators::delivery_note::DeliveryNote::new(note.clone());
+
+        let path = engine.generate_report(report(), Format::Pdf).unwrap();
+        assert_eq!(
+            path,
+            Path::new(folder)
+                .join("reports/bon_livraison_BL-2026-00001_Alice_Martin_2026-05-02.pdf")
+        );
+        assert!(fs::read(&path).unwrap().starts_with(b"%PDF"));
+        match engine.generate_report(report(), Format::Pdf) {
+            Err(Error::FileExists { path: existing }) => assert_eq!(existing, path),
+            other => panic!("expected the file to exist, got {:?}", other),
+        }
+
+        engine.set_output(OutputSettings {
+            overwrite: true,
+            ..engine.output().clone()
+        });
+        assert_eq!(engine.generate_report(report(), Format::Pdf).unwrap(), path);
+
+        let path = engine.generate_report(report(), Format::Csv).unwrap();
+        assert_eq!(path.extension().unwrap(), "csv");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "Référence;Description;Quantité\nCH-01;Chaise;3 pce\n"
+        );
+    }
+
+    #[test]
+    fn test_save_output() {
+        let folder = "test_engine_output";
+        let mut engine = setup_test_engine(folder);
+        let output = OutputSettings {
+            directory: Path::new(folder).join("documents"),
+            pattern: "{number}.{ext}".to_string(),
+            overwrite: true,
+        };
+        engine.save_output(output.clone()).unwrap();
+        // Settings changed for one command are not saved
+        engine.set_output(OutputSettings::default());
+
+        let engine = Engine::new(StorageType::FileStorage(folder)).unwrap();
+        assert_eq!(engine.output(), &output);
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut engine = setup_test_engine("test_engine_validation");
+        let invalid = Client {
+            postal_code: "6900".to_string(),
+            ..client("1", "Alice")
+        };
+        match engine.create_client(invalid) {
+            Err(Error::Invalid { kind, errors, .. }) => {
+                assert_eq!(kind, "client");
+                assert_eq!(errors[0].field, "postal_code");
+            }
+            other => panic!("expected an invalid client, got {:?}", other),
         }
+        assert!(engine.get_clients().is_empty());
+        assert!(engine
+            .create_product(Product::new("", "Chaise", ""))
+            .is_err());
+
+        let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+        let invalid_items = [Item::new(
+            Product::new("1", "Chaise", "CH-01"),
+            dec!(12.50),
+            dec!(0),
+        )];
+        match engine.create_delivery_note(sender(), client("1", "Alice"), &invalid_items, date) {
+            Err(Error::Invalid { errors, .. }) => {
+                assert_eq!(errors[0].field, "items[0].quantity");
+            }
+            other => panic!("expected an invalid item, got {:?}", other),
+        }
+        assert!(engine.get_delivery_notes().is_empty());
+
+        // Notes saved before the validation are checked when printed
+        let note = DeliveryNote {
+            number: "BL-2026-00001".to_string(),
+            date,
+            sender: sender(),
+            client: client("1", ""),
+            items: items(),
+            quote: String::new(),
+        };
+        let report = crate::generators::delivery_note::DeliveryNote::new(note);
+        let path = Path::new("test_engine_validation/note.pdf");
+        assert!(matches!(
+            engine.write_report(report, Format::Pdf, path),
+            Err(Error::Invalid { .. })
+        ));
+        assert!(!path.exists());
     }
-    pub fn generate_report(&self, report: impl Report) {
-        let pdf = report.generate();
-        let mut buffer = BufWriter::new(File::create("report").unwrap());
-        pdf.save(&mut buffer);
+
+    #[test]
+    fn test_remove_client_in_delivery_note() {
+        let mut engine = setup_test_engine("test_engine_remove_noted_client");
+        engine.create_client(client("1", "Alice")).unwrap();
+        engine.create_client(client("2", "Bob")).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+        engine
+            .create_delivery_note(sender(), client("1", "Alice"), &items(), date)
+            .unwrap();
+
+        match engine.remove_client("1") {
+            Err(Error::InUse { notes, .. }) => assert_eq!(notes, vec!["BL-2026-00001"]),
+            _ => panic!("expected the client to be in use"),
+        }
+        assert!(engine.remove_client("2").is_ok());
+    }
+
+    #[test]
+    fn test_remove_product() {
+        let mut engine = setup_test_engine("test_engine_remove_product");
+        engine
+            .create_product(Product::new("1", "Chaise", "CH-01"))
+            .unwrap();
+        engine
+            .create_product(Product::new("2", "Table", "TA-01"))
+            .unwrap();
+
+        assert!(engine.remove_product("1").is_ok());
+        assert!(engine.remove_product("1").is_err());
+        assert_eq!(engine.get_products().len(), 1);
+
+        let storage = StorageType::FileStorage("test_engine_remove_product").build();
+        let stored = storage.load::<Product>().unwrap();
+        assert_eq!(stored, vec![Product::new("2", "Table", "TA-01")]);
+    }
+
+    #[test]
+    fn test_import_products() {
+        let folder = "test_engine_import_products";
+        let mut engine = setup_test_engine(folder);
+        engine
+            .create_product(Product::new("1", "Chaise", "CH-01"))
+            .unwrap();
+
+        let added = engine
+            .import_products(vec![
+                (2, Product::new("1", "Chaise pliante", "CH-01")),
+                (3, Product::new("2", "Table", "TA-01")),
+            ])
+            .unwrap();
+        assert_eq!(added, 1);
+        assert_eq!(engine.get_products()[0].description(), "Chaise pliante");
+
+        let invalid = vec![
+            (2, Product::new("3", "Banc", "BA-01")),
+            (3, Product::new("", "Tabouret", "TB-01")),
+        ];
+        match engine.import_products(invalid) {
+            Err(Error::Storage(StorageError::InvalidValue { field, line, .. })) => {
+                assert_eq!((line, field.as_str()), (Some(3), "id"));
+            }
+            other => panic!("expected an invalid product, got {:?}", other),
+        }
+        assert_eq!(engine.get_products().len(), 2);
+
+        let storage = StorageType::FileStorage(folder).build();
+        assert_eq!(storage.load::<Product>().unwrap().len(), 2);
     }
 }
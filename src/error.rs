@@ -0,0 +1,65 @@
+use std::{io, path::PathBuf};
+
+use thiserror::Error;
+
+use crate::{
+    generators::barcode::BarcodeError, models::validation::FieldError, storage::StorageError,
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("No {kind} with id \"{id}\"")]
+    NotFound { kind: &'static str, id: String },
+    #[error("A {kind} with id \"{id}\" already exists")]
+    DuplicateId { kind: &'static str, id: String },
+    #[error("Cannot remove {kind} \"{id}\": it appears in delivery notes {}", notes.join(", "))]
+    InUse {
+        kind: &'static str,
+        id: String,
+        notes: Vec<String>,
+    },
+    #[error("Invalid {kind} \"{id}\": {}", errors.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    Invalid {
+        kind: &'static str,
+        id: String,
+        errors: Vec<FieldError>,
+    },
+    #[error("{}", match count {
+        0 => "No sender profile is saved, create one in the settings".to_string(),
+        count => format!("Choose one of the {} sender profiles", count),
+    })]
+    SenderNotChosen { count: usize },
+    #[error("Delivery note {note} is already billed by invoice {invoice}")]
+    AlreadyInvoiced { note: String, invoice: String },
+    #[error("Quote {quote} was only valid until {valid_until}")]
+    QuoteExpired {
+        quote: String,
+        valid_until: chrono::NaiveDate,
+    },
+    #[error("Quote {quote} was already converted into delivery note {note}")]
+    AlreadyConverted { quote: String, note: String },
+    #[error("Missing asset {}: {source}", path.display())]
+    MissingAsset {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Invalid asset {}: {message}", path.display())]
+    InvalidAsset { path: PathBuf, message: String },
+    #[error("Cannot write {}: {source}", path.display())]
+    Output {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("{} already exists", path.display())]
+    FileExists { path: PathBuf },
+    #[error("Cannot listen on {address}: {message}")]
+    Listen { address: String, message: String },
+    #[error(transparent)]
+    Barcode(#[from] BarcodeError),
+    #[error("PDF error: {0}")]
+    Pdf(#[from] printpdf::Error),
+}
@@ -0,0 +1,270 @@
+//! Code 128, using code set B for text and code set C for runs of digits.
+
+use super::{expand, BarcodeError, Symbol};
+
+/// Widths of the bars and spaces of each symbol value. 103 to 105 are the
+/// start symbols of code sets A, B and C.
+const PATTERNS: [[u8; 6]; 106] = [
+    [2, 1, 2, 2, 2, 2],
+    [2, 2, 2, 1, 2, 2],
+    [2, 2, 2, 2, 2, 1],
+    [1, 2, 1, 2, 2, 3],
+    [1, 2, 1, 3, 2, 2],
+    [1, 3, 1, 2, 2, 2],
+    [1, 2, 2, 2, 1, 3],
+    [1, 2, 2, 3, 1, 2],
+    [1, 3, 2, 2, 1, 2],
+    [2, 2, 1, 2, 1, 3],
+    [2, 2, 1, 3, 1, 2],
+    [2, 3, 1, 2, 1, 2],
+    [1, 1, 2, 2, 3, 2],
+    [1, 2, 2, 1, 3, 2],
+    [1, 2, 2, 2, 3, 1],
+    [1, 1, 3, 2, 2, 2],
+    [1, 2, 3, 1, 2, 2],
+    [1, 2, 3, 2, 2, 1],
+    [2, 2, 3, 2, 1, 1],
+    [2, 2, 1, 1, 3, 2],
+    [2, 2, 1, 2, 3, 1],
+    [2, 1, 3, 2, 1, 2],
+    [2, 2, 3, 1, 1, 2],
+    [3, 1, 2, 1, 3, 1],
+    [3, 1, 1, 2, 2, 2],
+    [3, 2, 1, 1, 2, 2],
+    [3, 2, 1, 2, 2, 1],
+    [3, 1, 2, 2, 1, 2],
+    [3, 2, 2, 1, 1, 2],
+    [3, 2, 2, 2, 1, 1],
+    [2, 1, 2, 1, 2, 3],
+    [2, 1, 2, 3, 2, 1],
+    [2, 3, 2, 1, 2, 1],
+    [1, 1, 1, 3, 2, 3],
+    [1, 3, 1, 1, 2, 3],
+    [1, 3, 1, 3, 2, 1],
+    [1, 1, 2, 3, 1, 3],
+    [1, 3, 2, 1, 1, 3],
+    [1, 3, 2, 3, 1, 1],
+    [2, 1, 1, 3, 1, 3],
+    [2, 3, 1, 1, 1, 3],
+    [2, 3, 1, 3, 1, 1],
+    [1, 1, 2, 1, 3, 3],
+    [1, 1, 2, 3, 3, 1],
+    [1, 3, 2, 1, 3, 1],
+    [1, 1, 3, 1, 2, 3],
+    [1, 1, 3, 3, 2, 1],
+    [1, 3, 3, 1, 2, 1],
+    [3, 1, 3, 1, 2, 1],
+    [2, 1, 1, 3, 3, 1],
+    [2, 3, 1, 1, 3, 1],
+    [2, 1, 3, 1, 1, 3],
+    [2, 1, 3, 3, 1, 1],
+    [2, 1, 3, 1, 3, 1],
+    [3, 1, 1, 1, 2, 3],
+    [3, 1, 1, 3, 2, 1],
+    [3, 3, 1, 1, 2, 1],
+    [3, 1, 2, 1, 1, 3],
+    [3, 1, 2, 3, 1, 1],
+    [3, 3, 2, 1, 1, 1],
+    [3, 1, 4, 1, 1, 1],
+    [2, 2, 1, 4, 1, 1],
+    [4, 3, 1, 1, 1, 1],
+    [1, 1, 1, 2, 2, 4],
+    [1, 1, 1, 4, 2, 2],
+    [1, 2, 1, 1, 2, 4],
+    [1, 2, 1, 4, 2, 1],
+    [1, 4, 1, 1, 2, 2],
+    [1, 4, 1, 2, 2, 1],
+    [1, 1, 2, 2, 1, 4],
+    [1, 1, 2, 4, 1, 2],
+    [1, 2, 2, 1, 1, 4],
+    [1, 2, 2, 4, 1, 1],
+    [1, 4, 2, 1, 1, 2],
+    [1, 4, 2, 2, 1, 1],
+    [2, 4, 1, 2, 1, 1],
+    [2, 2, 1, 1, 1, 4],
+    [4, 1, 3, 1, 1, 1],
+    [2, 4, 1, 1, 1, 2],
+    [1, 3, 4, 1, 1, 1],
+    [1, 1, 1, 2, 4, 2],
+    [1, 2, 1, 1, 4, 2],
+    [1, 2, 1, 2, 4, 1],
+    [1, 1, 4, 2, 1, 2],
+    [1, 2, 4, 1, 1, 2],
+    [1, 2, 4, 2, 1, 1],
+    [4, 1, 1, 2, 1, 2],
+    [4, 2, 1, 1, 1, 2],
+    [4, 2, 1, 2, 1, 1],
+    [2, 1, 2, 1, 4, 1],
+    [2, 1, 4, 1, 2, 1],
+    [4, 1, 2, 1, 2, 1],
+    [1, 1, 1, 1, 4, 3],
+    [1, 1, 1, 3, 4, 1],
+    [1, 3, 1, 1, 4, 1],
+    [1, 1, 4, 1, 1, 3],
+    [1, 1, 4, 3, 1, 1],
+    [4, 1, 1, 1, 1, 3],
+    [4, 1, 1, 3, 1, 1],
+    [1, 1, 3, 1, 4, 1],
+    [1, 1, 4, 1, 3, 1],
+    [3, 1, 1, 1, 4, 1],
+    [4, 1, 1, 1, 3, 1],
+    [2, 1, 1, 4, 1, 2],
+    [2, 1, 1, 2, 1, 4],
+    [2, 1, 1, 2, 3, 2],
+];
+const STOP: [u8; 7] = [2, 3, 3, 1, 1, 1, 2];
+
+const START_B: u8 = 104;
+const START_C: u8 = 105;
+/// Switches to code set C from code set B.
+const CODE_C: u8 = 99;
+/// Switches to code set B from code set C.
+const CODE_B: u8 = 100;
+
+/// Runs of digits at least this long are written in code set C, two digits
+/// per symbol.
+const MIN_DIGIT_RUN: usize = 4;
+
+fn digit_run(chars: &[char]) -> usize {
+    return chars.iter().take_while(|c| c.is_ascii_digit()).count();
+}
+
+/// The symbol values of `value`, start and check symbols included.
+fn values(value: &str) -> Result<Vec<u8>, BarcodeError> {
+    let chars = value.chars().collect::<Vec<char>>();
+    let mut values = Vec::new();
+    let mut in_c = false;
+    let mut idx = 0;
+    while idx < chars.len() {
+        let run = digit_run(&chars[idx..]);
+        // Digits alone are worth code set C even when they are few
+        let whole = idx == 0 && run == chars.len() && run.is_multiple_of(2);
+        if !in_c && (run >= MIN_DIGIT_RUN || whole) {
+            values.push(if idx == 0 { START_C } else { CODE_C });
+            in_c = true;
+        }
+        if in_c {
+            if run >= 2 {
+                let pair = chars[idx..idx + 2].iter().collect::<String>();
+                values.push(pair.parse().unwrap());
+                idx += 2;
+                continue;
+            }
+            values.push(CODE_B);
+            in_c = false;
+        }
+        if idx == 0 {
+            values.push(START_B);
+        }
+        let c = chars[idx];
+        if !(' '..='~').contains(&c) {
+            return Err(BarcodeError::UnsupportedCharacter(c));
+        }
+        values.push(c as u8 - b' ');
+        idx += 1;
+    }
+    if values.is_empty() {
+        values.push(START_B);
+    }
+    let check = values
+        .iter()
+        .enumerate()
+        .map(|(position, value)| position.max(1) * *value as usize)
+        .sum::<usize>()
+        % 103;
+    values.push(check as u8);
+    return Ok(values);
+}
+
+/// Encodes printable ASCII text.
+pub fn encode(value: &str) -> Result<Symbol, BarcodeError> {
+    let mut modules = Vec::new();
+    for value in values(value)? {
+        expand(&PATTERNS[value as usize], &mut modules);
+    }
+    expand(&STOP, &mut modules);
+    return Ok(Symbol::linear(modules));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Widths of the runs of modules of the same color.
+    fn runs(modules: &[bool]) -> Vec<u8> {
+        let mut runs = Vec::new();
+        let mut idx = 0;
+        while idx < modules.len() {
+            let start = idx;
+            while idx < modules.len() && modules[idx] == modules[start] {
+                idx += 1;
+            }
+            runs.push((idx - start) as u8);
+        }
+        return runs;
+    }
+
+    /// Reads the symbol back, checking the check symbol.
+    fn decode(symbol: &Symbol) -> String {
+        let runs = runs(&symbol.modules);
+        assert!(symbol.modules[0] && symbol.modules[symbol.width - 1]);
+        assert_eq!(runs[runs.len() - 7..], STOP);
+        let values = runs[..runs.len() - 7]
+            .chunks(6)
+            .map(|widths| {
+                return PATTERNS
+                    .iter()
+                    .position(|pattern| pattern == widths)
+                    .expect("unknown pattern") as u8;
+            })
+            .collect::<Vec<u8>>();
+        let (check, values) = values.split_last().unwrap();
+        let sum = values
+            .iter()
+            .enumerate()
+            .map(|(position, value)| position.max(1) * *value as usize)
+            .sum::<usize>();
+        assert_eq!(sum % 103, *check as usize);
+
+        let mut text = String::new();
+        let mut in_c = values[0] == START_C;
+        for value in &values[1..] {
+            match (in_c, *value) {
+                (false, CODE_C) => in_c = true,
+                (true, CODE_B) => in_c = false,
+                (true, pair) => text.push_str(&format!("{:02}", pair)),
+                (false, value) => text.push((value + b' ') as char),
+            }
+        }
+        return text;
+    }
+
+    #[test]
+    fn test_patterns() {
+        for (value, pattern) in PATTERNS.iter().enumerate() {
+            assert_eq!(pattern.iter().sum::<u8>(), 11, "{}", value);
+            // The bars take an even number of modules, the spaces an odd one
+            assert_eq!((pattern[0] + pattern[2] + pattern[4]) % 2, 0, "{}", value);
+            assert!(!PATTERNS[..value].contains(pattern), "{}", value);
+        }
+        assert_eq!(STOP.iter().sum::<u8>(), 13);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for value in ["BL-2026-00001", "123456", "12345", "A1234B", "x", "", "Ré"] {
+            match encode(value) {
+                Ok(symbol) => assert_eq!(decode(&symbol), value),
+                Err(e) => assert_eq!(value, "Ré", "{}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_code_sets() {
+        // 2 symbols in code set C instead of 4 in code set B
+        assert_eq!(values("1234").unwrap(), vec![START_C, 12, 34, 82]);
+        assert_eq!(values("A").unwrap(), vec![START_B, 33, 34]);
+        assert_eq!(encode("1234").unwrap().width, 11 * 4 + 13);
+    }
+}
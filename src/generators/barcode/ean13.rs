@@ -0,0 +1,136 @@
+//! EAN-13, the barcode printed on retail products.
+
+use super::{BarcodeError, Symbol};
+
+/// Left hand digits with odd parity. The right hand digits are their
+/// complement, the left hand digits with even parity their mirror image.
+const L_CODES: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011, 0b0110001, 0b0101111, 0b0111011,
+    0b0110111, 0b0001011,
+];
+/// Parity of the six left hand digits, one bit per digit, set for even
+/// parity, given by the first digit.
+const PARITIES: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101, 0b010110,
+    0b011010,
+];
+
+/// The check digit of the 12 first digits.
+pub fn check_digit(digits: &[u8]) -> u8 {
+    let sum = digits
+        .iter()
+        .enumerate()
+        .map(|(idx, digit)| *digit as u32 * if idx % 2 == 0 { 1 } else { 3 })
+        .sum::<u32>();
+    return ((10 - sum % 10) % 10) as u8;
+}
+
+fn push_bits(bits: u8, count: u32, modules: &mut Vec<bool>) {
+    for idx in (0..count).rev() {
+        modules.push(bits >> idx & 1 == 1);
+    }
+}
+
+/// Encodes 12 digits, or 13 digits whose last one is the check digit.
+pub fn encode(value: &str) -> Result<Symbol, BarcodeError> {
+    let mut digits = value
+        .chars()
+        .map(|c| c.to_digit(10).map(|digit| digit as u8))
+        .collect::<Option<Vec<u8>>>()
+        .filter(|digits| digits.len() == 12 || digits.len() == 13)
+        .ok_or_else(|| BarcodeError::InvalidEan(value.to_string()))?;
+    let expected = check_digit(&digits[..12]);
+    match digits.get(12) {
+        Some(check) if *check != expected => {
+            return Err(BarcodeError::WrongCheckDigit {
+                value: value.to_string(),
+                expected,
+            })
+        }
+        Some(_) => {}
+        None => digits.push(expected),
+    }
+
+    let mut modules = Vec::with_capacity(95);
+    push_bits(0b101, 3, &mut modules);
+    let parity = PARITIES[digits[0] as usize];
+    for (idx, digit) in digits[1..7].iter().enumerate() {
+        let code = L_CODES[*digit as usize];
+        if parity >> (5 - idx) & 1 == 1 {
+            // Complement, then read backwards
+            push_bits((!code & 0x7f).reverse_bits() >> 1, 7, &mut modules);
+        } else {
+            push_bits(code, 7, &mut modules);
+        }
+    }
+    push_bits(0b01010, 5, &mut modules);
+    for digit in &digits[7..] {
+        push_bits(!L_CODES[*digit as usize] & 0x7f, 7, &mut modules);
+    }
+    push_bits(0b101, 3, &mut modules);
+    return Ok(Symbol::linear(modules));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(modules: &[bool]) -> u8 {
+        return modules.iter().fold(0, |bits, dark| bits << 1 | *dark as u8);
+    }
+
+    /// Reads the 13 digits back from the modules.
+    fn decode(symbol: &Symbol) -> String {
+        let modules = &symbol.modules;
+        assert_eq!(modules.len(), 95);
+        assert_eq!(bits(&modules[..3]), 0b101);
+        assert_eq!(bits(&modules[45..50]), 0b01010);
+        assert_eq!(bits(&modules[92..]), 0b101);
+        let mut parity = 0;
+        let mut digits = Vec::new();
+        for chunk in modules[3..45].chunks(7) {
+            let code = bits(chunk);
+            let (digit, even) = match L_CODES.iter().position(|l| *l == code) {
+                Some(digit) => (digit, false),
+                None => {
+                    let mirrored = (!code & 0x7f).reverse_bits() >> 1;
+                    (L_CODES.iter().position(|l| *l == mirrored).unwrap(), true)
+                }
+            };
+            parity = parity << 1 | even as u8;
+            digits.push(digit as u8);
+        }
+        for chunk in modules[50..92].chunks(7) {
+            let code = !bits(chunk) & 0x7f;
+            digits.push(L_CODES.iter().position(|l| *l == code).unwrap() as u8);
+        }
+        let first = PARITIES.iter().position(|p| *p == parity).unwrap() as u8;
+        digits.insert(0, first);
+        assert_eq!(check_digit(&digits[..12]), digits[12]);
+        return digits.iter().map(|digit| digit.to_string()).collect();
+    }
+
+    #[test]
+    fn test_check_digit() {
+        assert_eq!(check_digit(&[4, 0, 0, 6, 3, 8, 1, 3, 3, 3, 9, 3]), 1);
+        assert_eq!(check_digit(&[5, 9, 0, 1, 2, 3, 4, 1, 2, 3, 4, 5]), 7);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(decode(&encode("4006381333931").unwrap()), "4006381333931");
+        assert_eq!(decode(&encode("590123412345").unwrap()), "5901234123457");
+        // The first digit only changes the parity of the left hand digits
+        assert_eq!(decode(&encode("0000000000000").unwrap()), "0000000000000");
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(matches!(
+            encode("4006381333932"),
+            Err(BarcodeError::WrongCheckDigit { expected: 1, .. })
+        ));
+        assert!(matches!(encode("CH-01"), Err(BarcodeError::InvalidEan(_))));
+        assert!(matches!(encode("12345"), Err(BarcodeError::InvalidEan(_))));
+    }
+}
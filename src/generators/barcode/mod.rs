@@ -0,0 +1,110 @@
+//! Barcode encoders, giving the modules of a symbol for any output format.
+
+use serde::Deserialize;
+use thiserror::Error;
+
+pub mod code128;
+pub mod ean13;
+pub mod qr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Symbology {
+    Code128,
+    Ean13,
+    Qr,
+}
+
+#[derive(Debug, Error)]
+pub enum BarcodeError {
+    #[error("Code 128 cannot encode {0:?}")]
+    UnsupportedCharacter(char),
+    #[error("EAN-13 needs 12 or 13 digits, got \"{0}\"")]
+    InvalidEan(String),
+    #[error("Wrong EAN-13 check digit in \"{value}\", expected {expected}")]
+    WrongCheckDigit { value: String, expected: u8 },
+    #[error("{0} bytes do not fit in a QR code")]
+    TooLong(usize),
+}
+
+/// The modules of a symbol, dark when `true`, row by row. Linear symbols
+/// have a single row. The quiet zone around the symbol is not included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub width: usize,
+    pub height: usize,
+    pub modules: Vec<bool>,
+}
+
+impl Symbol {
+    pub fn linear(modules: Vec<bool>) -> Self {
+        return Symbol {
+            width: modules.len(),
+            height: 1,
+            modules,
+        };
+    }
+
+    /// Width and height of the symbol drawn with modules `module` wide. Linear
+    /// symbols take `height`, two-dimensional ones have square modules.
+    pub fn size(&self, module: f32, height: f32) -> (f32, f32) {
+        let width = self.width as f32 * module;
+        return match self.height {
+            1 => (width, height),
+            rows => (width, rows as f32 * module),
+        };
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        return self.modules[y * self.width + x];
+    }
+
+    /// The runs of dark modules of each row, as `(row, first column, length)`,
+    /// so that a renderer draws one rectangle per run.
+    pub fn dark_runs(&self) -> Vec<(usize, usize, usize)> {
+        let mut runs = Vec::new();
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                if !self.is_dark(x, y) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < self.width && self.is_dark(x, y) {
+                    x += 1;
+                }
+                runs.push((y, start, x - start));
+            }
+        }
+        return runs;
+    }
+}
+
+/// Widths of the bars and spaces of a linear pattern, starting with a bar.
+fn expand(widths: &[u8], modules: &mut Vec<bool>) {
+    for (idx, width) in widths.iter().enumerate() {
+        let dark = idx % 2 == 0;
+        modules.extend(std::iter::repeat_n(dark, *width as usize));
+    }
+}
+
+pub fn encode(symbology: Symbology, value: &str) -> Result<Symbol, BarcodeError> {
+    return match symbology {
+        Symbology::Code128 => code128::encode(value),
+        Symbology::Ean13 => ean13::encode(value),
+        Symbology::Qr => qr::encode(value.as_bytes()),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dark_runs() {
+        let symbol = Symbol::linear(vec![true, true, false, true, false, false, true]);
+        assert_eq!(symbol.dark_runs(), vec![(0, 0, 2), (0, 3, 1), (0, 6, 1)]);
+        assert_eq!(symbol.size(0.5, 10.0), (3.5, 10.0));
+    }
+}
@@ -0,0 +1,658 @@
+//! QR codes in byte mode with error correction level M, versions 1 to 10,
+//! which hold up to 213 bytes.
+
+use super::{BarcodeError, Symbol};
+
+/// Error correction of the versions: codewords of error correction per block,
+/// then the blocks of each group with their count of data codewords.
+struct VersionInfo {
+    ec_codewords: usize,
+    groups: &'static [(usize, usize)],
+}
+
+const VERSIONS: [VersionInfo; 10] = [
+    VersionInfo {
+        ec_codewords: 10,
+        groups: &[(1, 16)],
+    },
+    VersionInfo {
+        ec_codewords: 16,
+        groups: &[(1, 28)],
+    },
+    VersionInfo {
+        ec_codewords: 26,
+        groups: &[(1, 44)],
+    },
+    VersionInfo {
+        ec_codewords: 18,
+        groups: &[(2, 32)],
+    },
+    VersionInfo {
+        ec_codewords: 24,
+        groups: &[(2, 43)],
+    },
+    VersionInfo {
+        ec_codewords: 16,
+        groups: &[(4, 27)],
+    },
+    VersionInfo {
+        ec_codewords: 18,
+        groups: &[(4, 31)],
+    },
+    VersionInfo {
+        ec_codewords: 22,
+        groups: &[(2, 38), (2, 39)],
+    },
+    VersionInfo {
+        ec_codewords: 22,
+        groups: &[(3, 36), (2, 37)],
+    },
+    VersionInfo {
+        ec_codewords: 26,
+        groups: &[(4, 43), (1, 44)],
+    },
+];
+
+/// Centers of the alignment patterns, on both axes.
+const ALIGNMENTS: [&[usize]; 10] = [
+    &[],
+    &[6, 18],
+    &[6, 22],
+    &[6, 26],
+    &[6, 30],
+    &[6, 34],
+    &[6, 22, 38],
+    &[6, 24, 42],
+    &[6, 26, 46],
+    &[6, 28, 50],
+];
+
+/// Format bits of error correction level M.
+const LEVEL_M: u32 = 0b00;
+const MODE_BYTE: u32 = 0b0100;
+const PAD_BYTES: [u8; 2] = [0xec, 0x11];
+
+impl VersionInfo {
+    fn data_codewords(&self) -> usize {
+        return self.groups.iter().map(|(count, size)| count * size).sum();
+    }
+
+    /// Data codewords of each block.
+    fn block_sizes(&self) -> Vec<usize> {
+        return self
+            .groups
+            .iter()
+            .flat_map(|(count, size)| std::iter::repeat_n(*size, *count))
+            .collect();
+    }
+}
+
+fn count_bits(version: usize) -> usize {
+    return if version < 10 { 8 } else { 16 };
+}
+
+/// Multiplication in GF(256) modulo x^8 + x^4 + x^3 + x^2 + 1.
+fn gf_multiply(a: u8, b: u8) -> u8 {
+    let mut result: u16 = 0;
+    for idx in (0..8).rev() {
+        result = (result << 1) ^ ((result >> 7) * 0x11d);
+        result ^= ((b >> idx) & 1) as u16 * a as u16;
+    }
+    return result as u8;
+}
+
+/// Coefficients of the Reed-Solomon generator polynomial of `degree`, highest
+/// power first, the leading 1 left out.
+fn generator(degree: usize) -> Vec<u8> {
+    let mut coefficients = vec![0; degree];
+    coefficients[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for idx in 0..degree {
+            coefficients[idx] = gf_multiply(coefficients[idx], root);
+            if idx + 1 < degree {
+                coefficients[idx] ^= coefficients[idx + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    return coefficients;
+}
+
+/// The error correction codewords of `data`.
+fn reed_solomon(data: &[u8], degree: usize) -> Vec<u8> {
+    let generator = generator(degree);
+    let mut remainder = vec![0; degree];
+    for byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (value, coefficient) in remainder.iter_mut().zip(&generator) {
+            *value ^= gf_multiply(*coefficient, factor);
+        }
+    }
+    return remainder;
+}
+
+struct Bits(Vec<bool>);
+
+impl Bits {
+    fn push(&mut self, value: u32, count: usize) {
+        for idx in (0..count).rev() {
+            self.0.push(value >> idx & 1 == 1);
+        }
+    }
+}
+
+/// The data and error correction codewords, interleaved.
+fn codewords(data: &[u8], version: usize) -> Vec<u8> {
+    let info = &VERSIONS[version - 1];
+    let capacity = info.data_codewords() * 8;
+    let mut bits = Bits(Vec::new());
+    bits.push(MODE_BYTE, 4);
+    bits.push(data.len() as u32, count_bits(version));
+    for byte in data {
+        bits.push(*byte as u32, 8);
+    }
+    let terminator = (capacity - bits.0.len()).min(4);
+    bits.push(0, terminator);
+    bits.push(0, (8 - bits.0.len() % 8) % 8);
+    let mut bytes = bits
+        .0
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |value, bit| value << 1 | *bit as u8))
+        .collect::<Vec<u8>>();
+    let mut pad = PAD_BYTES.iter().cycle();
+    while bytes.len() < info.data_codewords() {
+        bytes.push(*pad.next().unwrap());
+    }
+
+    let mut blocks = Vec::new();
+    let mut rest = bytes.as_slice();
+    for size in info.block_sizes() {
+        let (block, next) = rest.split_at(size);
+        blocks.push((block, reed_solomon(block, info.ec_codewords)));
+        rest = next;
+    }
+    let mut interleaved = Vec::new();
+    for idx in 0..*info.block_sizes().iter().max().unwrap() {
+        for (block, _) in &blocks {
+            if let Some(byte) = block.get(idx) {
+                interleaved.push(*byte);
+            }
+        }
+    }
+    for idx in 0..info.ec_codewords {
+        for (_, ec) in &blocks {
+            interleaved.push(ec[idx]);
+        }
+    }
+    return interleaved;
+}
+
+/// The smallest version that holds `length` bytes.
+fn version_for(length: usize) -> Option<usize> {
+    return (1..=VERSIONS.len()).find(|version| {
+        let bits = 4 + count_bits(*version) + length * 8;
+        return bits <= VERSIONS[version - 1].data_codewords() * 8;
+    });
+}
+
+/// The 15 format bits of `mask`, with their BCH error correction.
+fn format_bits(mask: u32) -> u32 {
+    let data = LEVEL_M << 3 | mask;
+    let mut remainder = data;
+    for _ in 0..10 {
+        remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+    }
+    return (data << 10 | remainder) ^ 0x5412;
+}
+
+/// The 18 version bits, with their BCH error correction.
+fn version_bits(version: usize) -> u32 {
+    let version = version as u32;
+    let mut remainder = version;
+    for _ in 0..12 {
+        remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1f25);
+    }
+    return version << 12 | remainder;
+}
+
+fn masked(mask: u32, x: usize, y: usize) -> bool {
+    return match mask {
+        0 => (x + y).is_multiple_of(2),
+        1 => y.is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        3 => (x + y).is_multiple_of(3),
+        4 => (x / 3 + y / 2).is_multiple_of(2),
+        5 => x * y % 2 + x * y % 3 == 0,
+        6 => (x * y % 2 + x * y % 3).is_multiple_of(2),
+        _ => ((x + y) % 2 + x * y % 3).is_multiple_of(2),
+    };
+}
+
+/// A symbol being built, with the modules of the function patterns, which
+/// the data and the mask leave alone.
+struct Grid {
+    version: usize,
+    size: usize,
+    modules: Vec<bool>,
+    function: Vec<bool>,
+}
+
+impl Grid {
+    fn new(version: usize) -> Self {
+        let size = version * 4 + 17;
+        let mut grid = Grid {
+            version,
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+        grid.draw_function_patterns();
+        return grid;
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_finder(&mut self, center_x: isize, center_y: isize) {
+        for dy in -4..=4isize {
+            for dx in -4..=4isize {
+                let (x, y) = (center_x + dx, center_y + dy);
+                if x < 0 || y < 0 || x >= self.size as isize || y >= self.size as isize {
+                    continue;
+                }
+                // The rings 2 and 4 modules away from the center are light, the
+                // latter being the separator
+                let distance = dx.abs().max(dy.abs());
+                self.set_function(x as usize, y as usize, distance != 2 && distance != 4);
+            }
+        }
+    }
+
+    fn draw_function_patterns(&mut self) {
+        let size = self.size;
+        for idx in 0..size {
+            self.set_function(6, idx, idx % 2 == 0);
+            self.set_function(idx, 6, idx % 2 == 0);
+        }
+        let last = size as isize - 4;
+        self.draw_finder(3, 3);
+        self.draw_finder(last, 3);
+        self.draw_finder(3, last);
+
+        let positions = ALIGNMENTS[self.version - 1];
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                let last = positions.len() - 1;
+                // The corners already hold the finder patterns
+                if (i == 0 || i == last) && (j == 0 || j == last) && i + j != 2 * last {
+                    continue;
+                }
+                for dy in -2..=2isize {
+                    for dx in -2..=2isize {
+                        let distance = dx.abs().max(dy.abs());
+                        let (mx, my) = ((x as isize + dx) as usize, (y as isize + dy) as usize);
+                        self.set_function(mx, my, distance != 1);
+                    }
+                }
+            }
+        }
+
+        // Reserved until the mask is chosen
+        self.draw_format(0);
+        if self.version >= 7 {
+            let bits = version_bits(self.version);
+            for idx in 0..18 {
+                let dark = bits >> idx & 1 == 1;
+                let (a, b) = (size - 11 + idx % 3, idx / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    fn draw_format(&mut self, mask: u32) {
+        let bits = format_bits(mask);
+        let bit = |idx: usize| bits >> idx & 1 == 1;
+        let size = self.size;
+        for idx in 0..6 {
+            self.set_function(8, idx, bit(idx));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for idx in 9..15 {
+            self.set_function(14 - idx, 8, bit(idx));
+        }
+        for idx in 0..8 {
+            self.set_function(size - 1 - idx, 8, bit(idx));
+        }
+        for idx in 8..15 {
+            self.set_function(8, size - 15 + idx, bit(idx));
+        }
+        // Always dark
+        self.set_function(8, size - 8, true);
+    }
+
+    /// The data modules in placement order: two columns at a time from the
+    /// right, going up then down, skipping the vertical timing pattern.
+    fn data_positions(&self) -> Vec<(usize, usize)> {
+        let size = self.size;
+        let mut positions = Vec::new();
+        let mut right = size - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..size {
+                let y = if upward {
+                    size - 1 - vertical
+                } else {
+                    vertical
+                };
+                for x in [right, right - 1] {
+                    if !self.function[y * size + x] {
+                        positions.push((x, y));
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+        return positions;
+    }
+
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        for (idx, (x, y)) in self.data_positions().into_iter().enumerate() {
+            // The remainder bits are light
+            let dark = codewords
+                .get(idx / 8)
+                .is_some_and(|byte| byte >> (7 - idx % 8) & 1 == 1);
+            self.modules[y * self.size + x] = dark;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let idx = y * self.size + x;
+                if !self.function[idx] && masked(mask, x, y) {
+                    self.modules[idx] = !self.modules[idx];
+                }
+            }
+        }
+    }
+
+    /// How hard the symbol is to read, the lower the better.
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let dark = |x: usize, y: usize| self.modules[y * size + x];
+        let mut penalty = 0;
+        for horizontal in [true, false] {
+            for line in 0..size {
+                let colors = (0..size)
+                    .map(|idx| match horizontal {
+                        true => dark(idx, line),
+                        false => dark(line, idx),
+                    })
+                    .collect::<Vec<bool>>();
+                // Runs of five modules or more of the same color
+                let mut run = 1;
+                for idx in 1..=size {
+                    if idx < size && colors[idx] == colors[idx - 1] {
+                        run += 1;
+                        continue;
+                    }
+                    if run >= 5 {
+                        penalty += run - 2;
+                    }
+                    run = 1;
+                }
+                // Patterns looking like a finder
+                for window in colors.windows(11) {
+                    let finder = [true, false, true, true, true, false, true];
+                    let light = [false; 4];
+                    if (window[..7] == finder && window[7..] == light)
+                        || (window[..4] == light && window[4..] == finder)
+                    {
+                        penalty += 40;
+                    }
+                }
+            }
+        }
+        // Blocks of 2 by 2 modules of the same color
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = dark(x, y);
+                if dark(x + 1, y) == color && dark(x, y + 1) == color && dark(x + 1, y + 1) == color
+                {
+                    penalty += 3;
+                }
+            }
+        }
+        // Balance of dark and light modules
+        let dark_count = self.modules.iter().filter(|dark| **dark).count();
+        let total = size * size;
+        let deviation = (dark_count * 20).abs_diff(total * 10);
+        penalty += deviation.div_ceil(total).saturating_sub(1) * 10;
+        return penalty;
+    }
+}
+
+/// Encodes `data` in the smallest version that holds it, with the mask that
+/// makes it easiest to read.
+pub fn encode(data: &[u8]) -> Result<Symbol, BarcodeError> {
+    let version = version_for(data.len()).ok_or(BarcodeError::TooLong(data.len()))?;
+    let mut grid = Grid::new(version);
+    grid.draw_codewords(&codewords(data, version));
+    let best = (0..8)
+        .min_by_key(|mask| {
+            let mut candidate = Grid {
+                version,
+                size: grid.size,
+                modules: grid.modules.clone(),
+                function: grid.function.clone(),
+            };
+            candidate.apply_mask(*mask);
+            candidate.draw_format(*mask);
+            return candidate.penalty();
+        })
+        .unwrap();
+    grid.apply_mask(best);
+    grid.draw_format(best);
+    return Ok(Symbol {
+        width: grid.size,
+        height: grid.size,
+        modules: grid.modules,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Evaluates the polynomial of `codewords`, highest power first, at `x`.
+    fn evaluate(codewords: &[u8], x: u8) -> u8 {
+        return codewords
+            .iter()
+            .fold(0, |value, coefficient| gf_multiply(value, x) ^ coefficient);
+    }
+
+    /// Reads the data back from the modules, checking the format bits and the
+    /// error correction of every block.
+    fn decode(symbol: &Symbol) -> Vec<u8> {
+        let size = symbol.width;
+        let version = (size - 17) / 4;
+        let dark = |x: usize, y: usize| symbol.is_dark(x, y);
+        // Finder pattern in the upper left corner
+        for idx in 0..7 {
+            assert!(dark(idx, 0) && dark(0, idx) && dark(idx, 6) && dark(6, idx));
+        }
+        assert!(!dark(7, 0) && dark(2, 2) && dark(4, 4) && !dark(1, 1));
+
+        let mut format = 0;
+        for idx in (9..15).rev() {
+            format = format << 1 | dark(14 - idx, 8) as u32;
+        }
+        format = format << 1 | dark(7, 8) as u32;
+        format = format << 1 | dark(8, 8) as u32;
+        format = format << 1 | dark(8, 7) as u32;
+        for idx in (0..6).rev() {
+            format = format << 1 | dark(8, idx) as u32;
+        }
+        let format = format ^ 0x5412;
+        let (level, mask) = (format >> 13, format >> 10 & 0b111);
+        assert_eq!(level, LEVEL_M);
+        assert_eq!(format_bits(mask) ^ 0x5412, format);
+
+        let grid = Grid::new(version);
+        let mut bits = Vec::new();
+        for (x, y) in grid.data_positions() {
+            bits.push(dark(x, y) ^ masked(mask, x, y));
+        }
+        let codewords = bits
+            .chunks_exact(8)
+            .map(|byte| byte.iter().fold(0, |value, bit| value << 1 | *bit as u8))
+            .collect::<Vec<u8>>();
+
+        let info = &VERSIONS[version - 1];
+        let sizes = info.block_sizes();
+        let mut blocks = vec![Vec::new(); sizes.len()];
+        let mut next = codewords.iter();
+        for idx in 0..*sizes.iter().max().unwrap() {
+            for (block, size) in blocks.iter_mut().zip(&sizes) {
+                if idx < *size {
+                    block.push(*next.next().unwrap());
+                }
+            }
+        }
+        for _ in 0..info.ec_codewords {
+            for block in blocks.iter_mut() {
+                block.push(*next.next().unwrap());
+            }
+        }
+        // A block without error is a multiple of the generator polynomial,
+        // whose roots are the powers of 2
+        let mut data = Vec::new();
+        for (block, size) in blocks.iter().zip(&sizes) {
+            let mut root = 1;
+            for _ in 0..info.ec_codewords {
+                assert_eq!(evaluate(block, root), 0);
+                root = gf_multiply(root, 2);
+            }
+            data.extend_from_slice(&block[..*size]);
+        }
+
+        let bit = |idx: usize| (data[idx / 8] >> (7 - idx % 8) & 1) as usize;
+        let read = |start: usize, count: usize| {
+            return (start..start + count).fold(0, |value, idx| value << 1 | bit(idx));
+        };
+        assert_eq!(read(0, 4), MODE_BYTE as usize);
+        let length = read(4, count_bits(version));
+        let start = 4 + count_bits(version);
+        return (0..length)
+            .map(|idx| read(start + idx * 8, 8) as u8)
+            .collect();
+    }
+
+    #[test]
+    fn test_gf_multiply() {
+        assert_eq!(gf_multiply(0x80, 0x02), 0x1d);
+        assert_eq!(gf_multiply(0x53, 0x01), 0x53);
+        assert_eq!(gf_multiply(0x02, 0x8e), 0x01);
+    }
+
+    #[test]
+    fn test_reference_codewords() {
+        // Symbol 1-M of "01234567" in annex I of ISO/IEC 18004: its data
+        // codewords, in numeric mode, then its error correction codewords
+        let data = [
+            0x10, 0x20, 0x0c, 0x56, 0x61, 0x80, 0xec, 0x11, 0xec, 0x11, 0xec, 0x11, 0xec, 0x11,
+            0xec, 0x11,
+        ];
+        assert_eq!(
+            reed_solomon(&data, 10),
+            [0xa5, 0x24, 0xd4, 0xc1, 0xed, 0x36, 0xc7, 0x87, 0x2c, 0x55]
+        );
+
+        // "BL" in byte mode: 0100, the length 00000010, 01000010 01001100,
+        // the terminator 0000, then the pad codewords
+        let codewords = codewords(b"BL", 1);
+        assert_eq!(
+            codewords[..16],
+            [
+                0x40, 0x24, 0x24, 0xc0, 0xec, 0x11, 0xec, 0x11, 0xec, 0x11, 0xec, 0x11, 0xec, 0x11,
+                0xec, 0x11
+            ]
+        );
+        assert_eq!(codewords.len(), 26);
+    }
+
+    #[test]
+    fn test_first_codeword_placement() {
+        // The first codeword fills the two right columns of the lower right
+        // corner upward, its most significant bit in the corner
+        let positions = Grid::new(1).data_positions();
+        assert_eq!(
+            positions[..8],
+            [
+                (20, 20),
+                (19, 20),
+                (20, 19),
+                (19, 19),
+                (20, 18),
+                (19, 18),
+                (20, 17),
+                (19, 17)
+            ]
+        );
+        assert_eq!(positions.len(), 26 * 8);
+    }
+
+    #[test]
+    fn test_bch_codes() {
+        // Values of the tables of the standard
+        assert_eq!(format_bits(0), 0b101010000010010);
+        assert_eq!(version_bits(7), 0b000111110010010100);
+    }
+
+    #[test]
+    fn test_versions() {
+        for (idx, info) in VERSIONS.iter().enumerate() {
+            let size = (idx + 1) * 4 + 17;
+            let grid = Grid::new(idx + 1);
+            let blocks = info.block_sizes().len();
+            let codewords = info.data_codewords() + blocks * info.ec_codewords;
+            // Every data module holds a bit of a codeword or a remainder bit
+            let remainder = grid.data_positions().len() - codewords * 8;
+            assert!(remainder < 8, "version {}", idx + 1);
+            assert_eq!(grid.size, size);
+        }
+        assert_eq!(version_for(14), Some(1));
+        assert_eq!(version_for(15), Some(2));
+        assert_eq!(version_for(213), Some(10));
+        assert_eq!(version_for(214), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for data in [
+            "BL-2026-00001".as_bytes().to_vec(),
+            "https://example.com/bl/BL-2026-00001?client=C1"
+                .as_bytes()
+                .to_vec(),
+            "Bon de livraison n° 42, 3 articles".repeat(5).into_bytes(),
+            (0..=212).map(|byte| byte as u8).collect(),
+        ] {
+            let symbol = encode(&data).unwrap();
+            assert_eq!(decode(&symbol), data);
+        }
+        assert!(matches!(encode(&[0; 214]), Err(BarcodeError::TooLong(214))));
+    }
+}
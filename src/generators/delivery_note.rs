@@ -1,202 +1,139 @@
-use crate::models::client::Client;
-use crate::models::item::Item;
-use crate::Sender;
-use image_crate::codecs::png::PngDecoder;
-use printpdf::*;
-use std::fs::File;
+use std::collections::HashMap;
 
-use super::Report;
+use rust_decimal::Decimal;
+
+use crate::error::Error;
+use crate::i18n::Catalog;
+use crate::models::{self, unit::Unit, validation::check};
+
+use super::{
+    document::{Document, ReportData},
+    format_quantity,
+    output::ReportName,
+    party_values,
+    template::Template,
+    Report,
+};
 
 pub struct DeliveryNote {
-    sender: Sender,
-    client: Client,
-    items: Vec<Item>,
-    logo_path: Option<String>,
+    note: models::delivery_note::DeliveryNote,
 }
 
 impl DeliveryNote {
-    pub fn new(sender: Sender, client: Client, items: &[Item], logo_path: Option<String>) -> Self {
-        return Self {
-            sender,
-            client,
-            items: items.to_vec(),
-            logo_path,
-        };
+    pub fn new(note: models::delivery_note::DeliveryNote) -> Self {
+        return Self { note };
+    }
+
+    /// Fails if the catalog of the language of the client cannot be read.
+    fn data(&self) -> Result<ReportData, Error> {
+        let note = &self.note;
+        let language = note.client.language;
+        let catalog = Catalog::load(language)?;
+        let values = [
+            ("number", note.number.clone()),
+            ("date", language.format_date(note.date)),
+        ];
+        let rows = note
+            .items
+            .iter()
+            .map(|item| {
+                HashMap::from([
+                    ("id".to_string(), item.product().id().clone()),
+                    ("reference".to_string(), item.product().reference().clone()),
+                    (
+                        "description".to_string(),
+                        item.product().description().clone(),
+                    ),
+                    ("price".to_string(), language.format_amount(item.price())),
+                    (
+                        "quantity".to_string(),
+                        format_quantity(&catalog, item.quantity(), *item.product().unit()),
+                    ),
+                ])
+            })
+            .collect();
+        // Quantities of different units cannot be added, so there is a total per unit
+        let totals = Unit::ALL
+            .into_iter()
+            .filter_map(|unit| {
+                let items = note
+                    .items
+                    .iter()
+                    .filter(|item| *item.product().unit() == unit);
+                let quantities = items.map(|item| item.quantity()).collect::<Vec<Decimal>>();
+                if quantities.is_empty() {
+                    return None;
+                }
+                let total = format_quantity(&catalog, quantities.into_iter().sum(), unit);
+                return Some(HashMap::from([("total_quantity".to_string(), total)]));
+            })
+            .collect();
+        return Ok(ReportData {
+            title: catalog.get("delivery-note"),
+            values: values
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .chain(party_values(&note.sender, &note.client, &catalog))
+                .collect(),
+            rows,
+            lists: HashMap::from([("totals".to_string(), totals)]),
+            logo_path: note.sender.logo_path(),
+            language,
+            messages: catalog.templates(),
+        });
     }
 }
 
 impl Report for DeliveryNote {
-    fn generate(&self) -> PdfDocumentReference {
-        let (doc, page1, layer1) =
-            PdfDocument::new("Bon de Livraison", Mm(210.0), Mm(297.0), "Layer 1");
-
-        let current_layer = doc.get_page(page1).get_layer(layer1);
-
-        // Ajouter des polices
-        let font_bold = doc
-            .add_external_font(File::open("assets/fonts/Helvetica-Bold.ttf").unwrap())
-            .unwrap();
-        let font = doc
-            .add_external_font(File::open("assets/fonts/Helvetica.ttf").unwrap())
-            .unwrap();
-
-        // Ajouter le logo
-        if let Some(logo_path) = &self.logo_path {
-            let mut image_file = File::open(logo_path).unwrap();
-            let image = Image::try_from(PngDecoder::new(&mut image_file).unwrap()).unwrap();
-
-            // translate x, translate y, rotate, scale x, scale y
-            // by default, an image is optimized to 300 DPI (if scale is None)
-            // rotations and translations are always in relation to the lower left corner
-            image.add_to_layer(
-                current_layer.clone(),
-                ImageTransform {
-                    translate_x: Some(Mm(10.)),
-                    translate_y: Some(Mm(250.)),
-                    rotate: None,
-                    scale_x: None,
-                    scale_y: None,
-                    dpi: None,
-                },
-            );
-        }
-        // Ajouter le titre
-        current_layer.use_text("Bon de Livraison", 24.0, Mm(110.0), Mm(280.0), &font_bold);
-        // Ajouter les informations de l'émetteur
-        current_layer.use_text(
-            format!("{}", self.sender.name),
-            12.0,
-            Mm(10.0),
-            Mm(240.0),
-            &font,
-        );
-        current_layer.use_text(
-            format!("{}", self.sender.addr1),
-            12.0,
-            Mm(10.0),
-            Mm(235.0),
-            &font,
-        );
-        current_layer.use_text(
-            format!("{}", self.sender.addr2),
-            12.0,
-            Mm(10.0),
-            Mm(230.0),
-            &font,
-        );
-        current_layer.use_text(
-            format!("{} {}", self.sender.postal_code, self.sender.city),
-            12.0,
-            Mm(10.0),
-            Mm(225.0),
-            &font,
-        );
+    fn document(&self) -> Result<Document, Error> {
+        return Ok(Document::new(Template::delivery_note()?, &self.data()?));
+    }
 
-        // Ajouter les informations du client à droite
-        current_layer.use_text(
-            format!("Pour: {}", self.client.name),
-            12.0,
-            Mm(120.0),
-            Mm(230.0),
-            &font,
-        );
-        current_layer.use_text(
-            format!("{}", self.client.address1),
-            12.0,
-            Mm(120.0),
-            Mm(225.0),
-            &font,
-        );
-        current_layer.use_text(
-            format!("{}", self.client.address2),
-            12.0,
-            Mm(120.0),
-            Mm(220.0),
-            &font,
-        );
-        current_layer.use_text(
-            format!("{} {}", self.client.postal_code, self.client.city),
-            12.0,
-            Mm(120.0),
-            Mm(215.0),
-            &font,
-        );
+    fn name(&self) -> ReportName {
+        return ReportName {
+            kind: "bon_livraison",
+            number: self.note.number.clone(),
+            client: self.note.client.name.clone(),
+            date: self.note.date,
+        };
+    }
 
-        // Ajouter une ligne de séparation
-        current_layer.add_line(Line {
-            points: vec![
-                (Point::new(Mm(10.0), Mm(200.0)), false),
-                (Point::new(Mm(200.0), Mm(200.0)), false),
-            ],
-            is_closed: false,
-        });
+    fn validate(&self) -> Result<(), Error> {
+        return check("delivery note", &self.note.number, &self.note);
+    }
+}
 
-        // Ajouter un tableau pour les objets achetés
-        let mut y_position = 195.0;
-        current_layer.use_text("Référence", 12.0, Mm(10.0), Mm(y_position), &font_bold);
-        current_layer.use_text("Description", 12.0, Mm(50.0), Mm(y_position), &font_bold);
-        current_layer.use_text("Quantité", 12.0, Mm(150.0), Mm(y_position), &font_bold);
-        // Ajouter des lignes de séparation
-        current_layer.add_line(Line {
-            points: vec![
-                (Point::new(Mm(10.0), Mm(y_position - 2.0)), false),
-                (Point::new(Mm(200.0), Mm(y_position - 2.0)), false),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{item::Item, product::Product};
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+
+    #[test]
+    fn test_totals() {
+        let mut sand = Product::new("2", "Sable", "SA-01");
+        *sand.unit_mut() = Unit::Kilogram;
+        let chair = Product::new("1", "Chaise", "CH-01");
+        let note = DeliveryNote::new(models::delivery_note::DeliveryNote {
+            number: "BL-2026-00001".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            sender: Default::default(),
+            client: Default::default(),
+            items: vec![
+                Item::new(sand.clone(), dec!(0.40), dec!(2.5)),
+                Item::new(chair, dec!(12.50), dec!(3)),
+                Item::new(sand, dec!(0.40), dec!(0.75)),
             ],
-            is_closed: false,
-        });
-
-        y_position -= 10.0;
-
-        for item in &self.items {
-            current_layer.use_text(
-                item.product().reference(),
-                12.0,
-                Mm(10.0),
-                Mm(y_position),
-                &font,
-            );
-            current_layer.use_text(
-                item.product().description(),
-                12.0,
-                Mm(50.0),
-                Mm(y_position),
-                &font,
-            );
-            current_layer.use_text(
-                item.quantity().to_string(),
-                12.0,
-                Mm(150.0),
-                Mm(y_position),
-                &font,
-            );
-
-            // Ajouter des lignes de séparation
-            current_layer.add_line(Line {
-                points: vec![
-                    (Point::new(Mm(10.0), Mm(y_position - 2.0)), false),
-                    (Point::new(Mm(200.0), Mm(y_position - 2.0)), false),
-                ],
-                is_closed: false,
-            });
-
-            y_position -= 10.0;
-        }
-
-        current_layer.add_rect(Rect {
-            ll: Point::new(Mm(10.0), Mm(y_position - 20.)),
-            ur: Point::new(Mm(100.0), Mm(y_position)),
-            mode: path::PaintMode::Stroke,
-            winding: path::WindingOrder::EvenOdd,
-        });
-        current_layer.use_text("Nom :", 12.0, Mm(15.0), Mm(y_position - 5.), &font);
-        y_position -= 25.;
-        current_layer.add_rect(Rect {
-            ll: Point::new(Mm(10.0), Mm(y_position - 20.)),
-            ur: Point::new(Mm(100.0), Mm(y_position)),
-            mode: path::PaintMode::Stroke,
-            winding: path::WindingOrder::EvenOdd,
+            quote: String::new(),
         });
-        current_layer.use_text("Visa :", 12.0, Mm(15.0), Mm(y_position - 5.), &font);
-        return doc;
+        let data = note.data().unwrap();
+        assert_eq!(data.rows[0]["quantity"], "2,5 kg");
+        assert_eq!(data.rows[0]["price"], "0,40");
+        let totals = data.lists["totals"]
+            .iter()
+            .map(|total| total["total_quantity"].as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(totals, vec!["3 pce", "3,25 kg"]);
     }
 }
@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+
+use super::{
+    barcode::{self, Symbology},
+    template::{Address, Align, Block as TemplateBlock, Template},
+};
+use crate::i18n::Language;
+
+/// The content of a report, laid out by a template.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReportData {
+    /// Title of the document.
+    pub title: String,
+    /// Values written in the text blocks. The sender and recipient blocks use
+    /// the `name`, `address1`, `address2`, `postal_code` and `city` values
+    /// prefixed by `sender.` and `client.`.
+    pub values: HashMap<String, String>,
+    /// Rows of the table, the values of each row being indexed by column field.
+    pub rows: Vec<HashMap<String, String>>,
+    /// Entries of the list blocks, indexed by list name.
+    pub lists: HashMap<String, Vec<HashMap<String, String>>>,
+    pub logo_path: Option<String>,
+    pub language: Language,
+    /// Messages of the catalog of `language`, written `{t.id}` in the texts.
+    /// Their variables are values of the report.
+    pub messages: HashMap<String, String>,
+}
+
+/// Replaces every `{name}` in `text` by its value. Unknown names are kept.
+pub fn fill(text: &str, values: &HashMap<String, String>) -> String {
+    let mut filled = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            let value = values.get(&rest[1..end])?;
+            Some((value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                filled.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    return filled;
+}
+
+/// Replaces the messages `{t.id}` of `text`, then the values.
+fn localize(text: &str, data: &ReportData, values: &HashMap<String, String>) -> String {
+    return fill(&fill(text, &data.messages), values);
+}
+
+/// Whether `text` shows the page number, which only the formats split into
+/// pages can fill.
+pub fn has_page_number(text: &str) -> bool {
+    return text.contains("{page}") || text.contains("{pages}");
+}
+
+/// A piece of text written with a single style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    pub bold: bool,
+}
+
+/// Where a block goes on a page, in millimeters from the lower left corner,
+/// and the size of its text in points. Only the formats split into pages use
+/// it.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Placement {
+    pub x: f32,
+    pub y: f32,
+    pub size: f32,
+    pub align: Align,
+    pub line_height: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Content {
+    /// A PNG image, such as the logo of the sender.
+    Image(String),
+    Heading(String),
+    /// One run for each line.
+    Lines(Vec<TextRun>),
+    /// A horizontal separator.
+    Rule,
+    /// A barcode, `module` being the width of its narrowest bar and `height`
+    /// the height of a linear symbol, in millimeters.
+    Barcode {
+        symbology: Symbology,
+        value: String,
+        module: f32,
+        height: f32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub content: Content,
+    pub placement: Placement,
+}
+
+impl Block {
+    /// How far below its `y` the text of the block goes.
+    pub fn depth(&self) -> f32 {
+        return match &self.content {
+            Content::Lines(lines) => {
+                self.placement.line_height * lines.len().saturating_sub(1) as f32
+            }
+            // The block fails to render when its value cannot be encoded
+            Content::Barcode {
+                symbology,
+                value,
+                module,
+                height,
+            } => barcode::encode(*symbology, value)
+                .map(|symbol| symbol.size(*module, *height).1)
+                .unwrap_or_default(),
+            Content::Image(_) | Content::Heading(_) | Content::Rule => 0.0,
+        };
+    }
+
+    /// The same block, `top - y` millimeters from the bottom of the page.
+    pub fn below(&self, top: f32) -> Block {
+        let mut block = self.clone();
+        block.placement.y = top - block.placement.y;
+        return block;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableColumn {
+    pub header: String,
+    pub align: Align,
+    /// Symbology of the cells drawn as barcodes.
+    pub barcode: Option<Symbology>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub columns: Vec<TableColumn>,
+    /// The cells of each row, one for each column.
+    pub rows: Vec<Vec<String>>,
+}
+
+/// A part of a document, for the formats that are not split into pages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Part<'a> {
+    Block(Block),
+    Table(&'a Table),
+    Signatures(&'a [String]),
+}
+
+/// The blocks sorted from the top of the page, with neither empty lines nor
+/// page numbers.
+fn reading_order(blocks: &[Block], from_top: bool) -> Vec<Block> {
+    let mut blocks = blocks
+        .iter()
+        .filter_map(|block| {
+            let mut block = block.clone();
+            if let Content::Lines(lines) = &mut block.content {
+                lines.retain(|line| !line.text.trim().is_empty() && !has_page_number(&line.text));
+                if lines.is_empty() {
+                    return None;
+                }
+            }
+            return Some(block);
+        })
+        .collect::<Vec<Block>>();
+    blocks.sort_by(|a, b| {
+        let (a, b) = (&a.placement, &b.placement);
+        let by_height = match from_top {
+            true => b.y.total_cmp(&a.y),
+            false => a.y.total_cmp(&b.y),
+        };
+        return by_height.then(a.x.total_cmp(&b.x));
+    });
+    return blocks;
+}
+
+/// A report independent of its output format: blocks of text, a table and
+/// signature boxes. The text of the blocks may still hold `{page}` and
+/// `{pages}`, which the formats split into pages fill.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    pub title: String,
+    pub language: Language,
+    /// The template the document was built from, which gives the page, the
+    /// fonts and the geometry of the table and of the signature boxes.
+    pub layout: Template,
+    /// Blocks of the first page, or of the start of the document.
+    pub header: Vec<Block>,
+    /// Blocks of the pages following the first one.
+    pub continuation: Vec<Block>,
+    /// Blocks of every page, or of the end of the document.
+    pub footer: Vec<Block>,
+    pub table: Option<Table>,
+    /// Blocks following the table. Their `y` is the distance below the end
+    /// of the table.
+    pub summary: Vec<Block>,
+    /// Labels of the signature boxes.
+    pub signatures: Vec<String>,
+}
+
+impl Document {
+    /// Fills the blocks of `layout` with `data`.
+    pub fn new(layout: Template, data: &ReportData) -> Self {
+        let blocks = |blocks: &[TemplateBlock]| {
+            return blocks
+                .iter()
+                .filter_map(|block| resolve(block, data))
+                .collect::<Vec<Block>>();
+        };
+        let table = layout.table.as_ref().map(|table| Table {
+            columns: table
+                .columns
+                .iter()
+                .map(|column| TableColumn {
+                    header: localize(&column.header, data, &data.values),
+                    align: column.align,
+                    barcode: column.barcode,
+                })
+                .collect(),
+            rows: data
+                .rows
+                .iter()
+                .map(|row| {
+                    table
+                        .columns
+                        .iter()
+                        .map(|column| row.get(&column.field).cloned().unwrap_or_default())
+                        .collect()
+                })
+                .collect(),
+        });
+        return Document {
+            title: data.title.clone(),
+            language: data.language,
+            header: blocks(&layout.header),
+            continuation: blocks(&layout.continuation),
+            footer: blocks(&layout.footer),
+            table,
+            summary: blocks(&layout.summary),
+            signatures: layout
+                .signatures
+                .as_ref()
+                .map(|signatures| {
+                    return signatures
+                        .labels
+                        .iter()
+                        .map(|label| localize(label, data, &data.values))
+                        .collect();
+                })
+                .unwrap_or_default(),
+            layout,
+        };
+    }
+
+    /// The parts of the document in reading order: header, table, summary
+    /// and signatures.
+    pub fn parts(&self) -> Vec<Part<'_>> {
+        let mut parts = reading_order(&self.header, true)
+            .into_iter()
+            .map(Part::Block)
+            .collect::<Vec<Part>>();
+        if let Some(table) = &self.table {
+            parts.push(Part::Table(table));
+        }
+        // The summary is placed below the table
+        parts.extend(
+            reading_order(&self.summary, false)
+                .into_iter()
+                .map(Part::Block),
+        );
+        if !self.signatures.is_empty() {
+            parts.push(Part::Signatures(&self.signatures));
+        }
+        return parts;
+    }
+
+    /// The footer blocks in reading order, to write once at the end.
+    pub fn footer_parts(&self) -> Vec<Part<'_>> {
+        return reading_order(&self.footer, true)
+            .into_iter()
+            .map(Part::Block)
+            .collect();
+    }
+}
+
+fn lines(texts: Vec<String>, bold: bool) -> Content {
+    return Content::Lines(
+        texts
+            .into_iter()
+            .map(|text| TextRun { text, bold })
+            .collect(),
+    );
+}
+
+fn address_lines(address: &Address, data: &ReportData, prefix: &str) -> Content {
+    let value = |name: &str| {
+        return data
+            .values
+            .get(&format!("{}.{}", prefix, name))
+            .cloned()
+            .unwrap_or_default();
+    };
+    let texts = vec![
+        format!(
+            "{}{}",
+            localize(&address.label, data, &data.values),
+            value("name")
+        ),
+        value("address1"),
+        value("address2"),
+        format!("{} {}", value("postal_code"), value("city")),
+    ];
+    return lines(texts, false);
+}
+
+/// The content of a template block, none for a logo when the report has no
+/// logo.
+fn resolve(block: &TemplateBlock, data: &ReportData) -> Option<Block> {
+    let values = &data.values;
+    let at = |x: f32, y: f32, size: f32| Placement {
+        x,
+        y,
+        size,
+        ..Default::default()
+    };
+    let block = match block {
+        TemplateBlock::Logo { x, y } => Block {
+            content: Content::Image(data.logo_path.clone()?),
+            placement: at(*x, *y, 0.0),
+        },
+        TemplateBlock::Title { x, y, size, text } => Block {
+            content: Content::Heading(localize(text, data, values)),
+            placement: at(*x, *y, *size),
+        },
+        TemplateBlock::Sender(address) | TemplateBlock::Recipient(address) => {
+            let prefix = match block {
+                TemplateBlock::Sender(_) => "sender",
+                _ => "client",
+            };
+            Block {
+                content: address_lines(address, data, prefix),
+                placement: Placement {
+                    line_height: address.line_height,
+                    ..at(address.x, address.y, address.size)
+                },
+            }
+        }
+        TemplateBlock::Text {
+            x,
+            y,
+            size,
+            bold,
+            align,
+            line_height,
+            lines: texts,
+        } => Block {
+            content: lines(
+                texts
+                    .iter()
+                    .map(|text| localize(text, data, values))
+                    .collect(),
+                *bold,
+            ),
+            placement: Placement {
+                x: *x,
+                y: *y,
+                size: *size,
+                align: *align,
+                line_height: *line_height,
+            },
+        },
+        TemplateBlock::List {
+            x,
+            y,
+            size,
+            bold,
+            align,
+            line_height,
+            list,
+            line,
+        } => {
+            let entries = data.lists.get(list).map(Vec::as_slice).unwrap_or(&[]);
+            let texts = entries
+                .iter()
+                .map(|entry| {
+                    let mut values = values.clone();
+                    values.extend(entry.clone());
+                    localize(line, data, &values)
+                })
+                .collect();
+            Block {
+                content: lines(texts, *bold),
+                placement: Placement {
+                    x: *x,
+                    y: *y,
+                    size: *size,
+                    align: *align,
+                    line_height: *line_height,
+                },
+            }
+        }
+        TemplateBlock::Line { y } => Block {
+            content: Content::Rule,
+            placement: at(0.0, *y, 0.0),
+        },
+        TemplateBlock::Barcode {
+            x,
+            y,
+            symbology,
+            value,
+            module,
+            height,
+        } => Block {
+            content: Content::Barcode {
+                symbology: *symbology,
+                value: localize(value, data, values),
+                module: *module,
+                height: *height,
+            },
+            placement: at(*x, *y, 0.0),
+        },
+    };
+    return Some(block);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Catalog;
+
+    #[test]
+    fn test_fill() {
+        let values = HashMap::from([
+            ("number".to_string(), "BL-2026-00001".to_string()),
+            ("page".to_string(), "2".to_string()),
+        ]);
+        assert_eq!(fill("N° {number}", &values), "N° BL-2026-00001");
+        assert_eq!(fill("page {page} / {pages}", &values), "page 2 / {pages}");
+        assert_eq!(fill("{ {page}} {", &values), "{ 2} {");
+    }
+
+    #[test]
+    fn test_new_document() {
+        let data = ReportData {
+            title: "Bon de Livraison".to_string(),
+            values: HashMap::from([
+                ("number".to_string(), "BL-2026-00001".to_string()),
+                ("client.name".to_string(), "Dupont".to_string()),
+            ]),
+            rows: vec![HashMap::from([
+                ("description".to_string(), "Chaise".to_string()),
+                ("quantity".to_string(), "3".to_string()),
+            ])],
+            messages: Catalog::load(Language::French).unwrap().templates(),
+            ..Default::default()
+        };
+        let document = Document::new(Template::delivery_note().unwrap(), &data);
+        // Without a logo, the logo block is left out
+        assert!(document
+            .header
+            .iter()
+            .all(|block| !matches!(block.content, Content::Image(_))));
+        assert!(document.header.iter().any(|block| match &block.content {
+            Content::Lines(lines) => lines[0].text.ends_with("Dupont"),
+            _ => false,
+        }));
+        let table = document.table.unwrap();
+        assert_eq!(table.columns[1].header, "Description");
+        assert_eq!(table.rows, vec![vec!["", "Chaise", "3"]]);
+        assert!(document.header.iter().any(|block| {
+            return block.content
+                == Content::Barcode {
+                    symbology: Symbology::Code128,
+                    value: "BL-2026-00001".to_string(),
+                    module: 0.3,
+                    height: 10.0,
+                };
+        }));
+        assert_eq!(document.signatures.len(), 2);
+    }
+
+    #[test]
+    fn test_block_below() {
+        let block = Block {
+            content: Content::Rule,
+            placement: Placement {
+                y: 5.0,
+                ..Default::default()
+            },
+        };
+        assert_eq!(block.below(100.0).placement.y, 95.0);
+    }
+
+    #[test]
+    fn test_parts() {
+        let data = ReportData {
+            title: "Bon de Livraison".to_string(),
+            values: ["sender.legal", "sender.contact", "sender.footer"]
+                .iter()
+                .map(|name| (name.to_string(), String::new()))
+                .collect(),
+            messages: Catalog::load(Language::French).unwrap().templates(),
+            ..Default::default()
+        };
+        let document = Document::new(Template::delivery_note().unwrap(), &data);
+        let parts = document.parts();
+        match &parts[0] {
+            Part::Block(block) => assert_eq!(
+                block.content,
+                Content::Heading("Bon de Livraison".to_string())
+            ),
+            other => panic!("expected the title first, got {:?}", other),
+        }
+        assert!(parts.iter().any(|part| matches!(part, Part::Table(_))));
+        assert!(matches!(parts.last(), Some(Part::Signatures(_))));
+        // Neither the page number nor the empty footer lines are kept
+        assert!(document.footer_parts().is_empty());
+    }
+}
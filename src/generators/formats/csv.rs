@@ -0,0 +1,24 @@
+use crate::{error::Error, generators::document::Document, storage::csv::write_record};
+
+/// Semicolons, as the amounts use a decimal comma.
+const DELIMITER: char = ';';
+
+/// Writes the rows of the table of the document, after a line of headers.
+/// The other blocks are left out.
+pub fn render(document: &Document) -> Result<Vec<u8>, Error> {
+    let mut records = Vec::new();
+    if let Some(table) = &document.table {
+        let headers = table
+            .columns
+            .iter()
+            .map(|column| column.header.clone())
+            .collect::<Vec<String>>();
+        records.push(write_record(&headers, DELIMITER));
+        for row in &table.rows {
+            records.push(write_record(row, DELIMITER));
+        }
+    }
+    let mut csv = records.join("\n");
+    csv.push('\n');
+    return Ok(csv.into_bytes());
+}
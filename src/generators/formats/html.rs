@@ -0,0 +1,231 @@
+use std::{fs, path::Path};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::{
+    error::Error,
+    generators::{
+        barcode::{self, Symbol},
+        document::{Content, Document, Part, Table},
+        template::Align,
+    },
+};
+
+const STYLE: &str =
+    "body { font-family: Helvetica, Arial, sans-serif; max-width: 190mm; margin: 10mm auto; }
+p { margin: 0 0 1em; }
+.right { text-align: right; }
+table { width: 100%; border-collapse: collapse; margin-bottom: 1em; }
+th, td { border-bottom: 1px solid #000; padding: 0.3em; vertical-align: top; }
+th { text-align: left; }
+.signatures { display: flex; flex-direction: column; gap: 5mm; }
+.signature { border: 1px solid #000; width: 80mm; height: 20mm; padding: 2mm; }
+.barcode { display: block; }
+footer { font-size: 0.7em; }";
+
+/// Escapes the characters that have a meaning in HTML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    return escaped;
+}
+
+fn class(align: Align) -> &'static str {
+    return match align {
+        Align::Left => "",
+        Align::Right => " class=\"right\"",
+    };
+}
+
+/// The PNG at `path` in a data URL, so that the page does not depend on
+/// other files.
+fn image(path: &str) -> Result<String, Error> {
+    let bytes = fs::read(path).map_err(|source| Error::MissingAsset {
+        path: Path::new(path).to_owned(),
+        source,
+    })?;
+    return Ok(format!(
+        "<img src=\"data:image/png;base64,{}\" alt=\"\">",
+        STANDARD.encode(bytes)
+    ));
+}
+
+/// The symbol as an inline SVG image, one rectangle for each run of dark
+/// modules, its size in millimeters.
+fn svg(symbol: &Symbol, module: f32, height: f32) -> String {
+    let (width, height) = symbol.size(module, height);
+    let rects = symbol
+        .dark_runs()
+        .into_iter()
+        .map(|(row, start, length)| {
+            return format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\"/>",
+                start, row, length
+            );
+        })
+        .collect::<String>();
+    // Les modules d'un code linéaire sont étirés sur toute la hauteur
+    return format!(
+        "<svg class=\"barcode\" xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" width=\"{}mm\" height=\"{}mm\" preserveAspectRatio=\"none\" shape-rendering=\"crispEdges\">{}</svg>",
+        symbol.width, symbol.height, width, height, rects
+    );
+}
+
+/// Module of the barcodes of the table, in millimeters.
+const CELL_MODULE: f32 = 0.33;
+/// Height of the linear barcodes of the table, in millimeters.
+const CELL_HEIGHT: f32 = 8.0;
+
+fn table(table: &Table) -> String {
+    let mut html = String::from("<table>\n<thead><tr>");
+    for column in &table.columns {
+        html.push_str(&format!(
+            "<th{}>{}</th>",
+            class(column.align),
+            escape(&column.header)
+        ));
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for row in &table.rows {
+        html.push_str("<tr>");
+        for (cell, column) in row.iter().zip(&table.columns) {
+            // Les valeurs qui ne peuvent pas être encodées restent en texte
+            let symbol = column
+                .barcode
+                .and_then(|symbology| barcode::encode(symbology, cell).ok());
+            let mut cell = escape(cell).replace('\n', "<br>");
+            if let Some(symbol) = symbol {
+                cell = format!("{}{}", svg(&symbol, CELL_MODULE, CELL_HEIGHT), cell);
+            }
+            html.push_str(&format!("<td{}>{}</td>", class(column.align), cell));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>");
+    return html;
+}
+
+fn part(part: Part) -> Result<String, Error> {
+    let html = match part {
+        Part::Block(block) => match &block.content {
+            Content::Image(path) => image(path)?,
+            Content::Heading(text) => format!("<h1>{}</h1>", escape(text)),
+            Content::Lines(runs) => {
+                let lines = runs
+                    .iter()
+                    .map(|run| match run.bold {
+                        true => format!("<strong>{}</strong>", escape(&run.text)),
+                        false => escape(&run.text),
+                    })
+                    .collect::<Vec<String>>();
+                format!(
+                    "<p{}>{}</p>",
+                    class(block.placement.align),
+                    lines.join("<br>\n")
+                )
+            }
+            Content::Rule => "<hr>".to_string(),
+            Content::Barcode {
+                symbology,
+                value,
+                module,
+                height,
+            } => {
+                let symbol = barcode::encode(*symbology, value)?;
+                format!("<p>{}</p>", svg(&symbol, *module, *height))
+            }
+        },
+        Part::Table(content) => table(content),
+        Part::Signatures(labels) => {
+            let boxes = labels
+                .iter()
+                .map(|label| format!("<div class=\"signature\">{}</div>", escape(label)))
+                .collect::<Vec<String>>();
+            format!("<div class=\"signatures\">\n{}\n</div>", boxes.join("\n"))
+        }
+    };
+    return Ok(html);
+}
+
+/// Writes the document as a standalone HTML page, the logo included.
+pub fn render(document: &Document) -> Result<Vec<u8>, Error> {
+    let mut body = Vec::new();
+    for content in document.parts() {
+        body.push(part(content)?);
+    }
+    let footer = document.footer_parts();
+    if !footer.is_empty() {
+        body.push("<footer>".to_string());
+        for content in footer {
+            body.push(part(content)?);
+        }
+        body.push("</footer>".to_string());
+    }
+    let html = format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        document.language.code(),
+        escape(&document.title),
+        STYLE,
+        body.join("\n")
+    );
+    return Ok(html.into_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{barcode::Symbology, document::TableColumn};
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("Dupont & Fils <\"SARL\">"),
+            "Dupont &amp; Fils &lt;&quot;SARL&quot;&gt;"
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let content = Table {
+            columns: vec![TableColumn {
+                header: "Qté".to_string(),
+                align: Align::Right,
+                barcode: None,
+            }],
+            rows: vec![vec!["<3>".to_string()]],
+        };
+        let html = table(&content);
+        assert!(html.contains("<th class=\"right\">Qté</th>"), "{}", html);
+        assert!(
+            html.contains("<td class=\"right\">&lt;3&gt;</td>"),
+            "{}",
+            html
+        );
+    }
+
+    #[test]
+    fn test_barcode_cells() {
+        let content = Table {
+            columns: vec![TableColumn {
+                header: "Référence".to_string(),
+                align: Align::Left,
+                barcode: Some(Symbology::Ean13),
+            }],
+            rows: vec![vec!["4006381333931".to_string()], vec!["REF-1".to_string()]],
+        };
+        let html = table(&content);
+        // 30 bars, the value being written under them
+        assert_eq!(html.matches("<rect ").count(), 30, "{}", html);
+        assert!(html.contains("</svg>4006381333931</td>"), "{}", html);
+        assert!(html.contains("<td>REF-1</td>"), "{}", html);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use crate::error::Error;
+
+use super::document::Document;
+
+pub mod csv;
+pub mod html;
+pub mod pdf;
+pub mod plain;
+
+/// The file formats a report can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Pdf,
+    Html,
+    /// Plain text for thermal printers.
+    Text,
+    /// The line items only.
+    Csv,
+}
+
+impl Format {
+    pub const ALL: [Format; 4] = [Format::Pdf, Format::Html, Format::Text, Format::Csv];
+
+    pub fn extension(self) -> &'static str {
+        return match self {
+            Format::Pdf => "pdf",
+            Format::Html => "html",
+            Format::Text => "txt",
+            Format::Csv => "csv",
+        };
+    }
+
+    /// The id of the message naming the format in the interface.
+    pub fn message(self) -> &'static str {
+        return match self {
+            Format::Pdf => "format-pdf",
+            Format::Html => "format-html",
+            Format::Text => "format-text",
+            Format::Csv => "format-csv",
+        };
+    }
+
+    /// The media type of the files, for HTTP responses.
+    pub fn media_type(self) -> &'static str {
+        return match self {
+            Format::Pdf => "application/pdf",
+            Format::Html => "text/html; charset=utf-8",
+            Format::Text => "text/plain; charset=utf-8",
+            Format::Csv => "text/csv; charset=utf-8",
+        };
+    }
+
+    /// The format whose extension is `extension`, whatever its case.
+    pub fn from_extension(extension: &str) -> Option<Format> {
+        return Format::ALL
+            .into_iter()
+            .find(|format| format.extension().eq_ignore_ascii_case(extension));
+    }
+
+    pub fn render(self, document: &Document) -> Result<Vec<u8>, Error> {
+        return match self {
+            Format::Pdf => pdf::render(document),
+            Format::Html => html::render(document),
+            Format::Text => plain::render(document),
+            Format::Csv => csv::render(document),
+        };
+    }
+}
+
+/// Written as its extension, which is how it is chosen on the command line
+/// and in the API.
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.extension());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::{
+        generators::{document::ReportData, template::Template},
+        i18n::{Catalog, Language},
+    };
+
+    fn document() -> Document {
+        let data = ReportData {
+            title: "Bon de Livraison".to_string(),
+            values: HashMap::from([
+                ("number".to_string(), "BL-2026-00001".to_string()),
+                ("client.name".to_string(), "Dupont & Fils".to_string()),
+            ]),
+            rows: vec![HashMap::from([
+                ("reference".to_string(), "CH-01".to_string()),
+                ("description".to_string(), "Chaise; pliante".to_string()),
+                ("quantity".to_string(), "3".to_string()),
+            ])],
+            messages: Catalog::load(Language::French).unwrap().templates(),
+            ..Default::default()
+        };
+        return Document::new(Template::delivery_note().unwrap(), &data);
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let document = document();
+        for format in Format::ALL {
+            let bytes = format.render(&document).unwrap();
+            assert!(!bytes.is_empty(), "{}", format);
+        }
+
+        let html = String::from_utf8(Format::Html.render(&document).unwrap()).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Pour: Dupont &amp; Fils"), "{}", html);
+
+        let text = String::from_utf8(Format::Text.render(&document).unwrap()).unwrap();
+        assert!(text.contains("N° BL-2026-00001"), "{}", text);
+        assert!(!text.contains("{page}"), "{}", text);
+        assert!(text
+            .lines()
+            .all(|line| line.chars().count() <= plain::WIDTH));
+
+        let csv = String::from_utf8(Format::Csv.render(&document).unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "Référence;Description;Quantité\nCH-01;\"Chaise; pliante\";3\n"
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        let catalog = Catalog::load(Language::English).unwrap();
+        for format in Format::ALL {
+            assert_eq!(Format::from_extension(&format.to_string()), Some(format));
+        }
+        assert_eq!(catalog.get(Format::Text.message()), "Text");
+    }
+}
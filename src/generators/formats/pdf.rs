@@ -0,0 +1,550 @@
+use std::{collections::HashMap, ops::Range};
+
+use printpdf::*;
+
+use crate::{
+    error::Error,
+    generators::{
+        barcode::{self, Symbol},
+        document::{fill, Block, Content, Document},
+        load_font, load_png,
+        template::{Align, Column, Signatures, Table},
+        text::FontMetrics,
+    },
+};
+
+/// Espace laissé sous les blocs du récapitulatif.
+const SUMMARY_MARGIN: f32 = 5.0;
+/// Module le plus large des codes-barres du tableau, pour qu'une valeur
+/// courte ne remplisse pas toute sa colonne.
+const MAX_CELL_MODULE: f32 = 0.5;
+/// Taille de la valeur écrite sous les codes-barres du tableau.
+const CELL_VALUE_SIZE: f32 = 6.0;
+
+/// Répartit les lignes, selon leurs hauteurs, sur les pages. Le récapitulatif
+/// et les cadres de signature prennent `closing_height` sous les lignes de la
+/// dernière page, qui n'a aucune ligne quand ils ne tiennent pas après le
+/// tableau.
+fn paginate(
+    table: &Table,
+    margin_bottom: f32,
+    row_heights: &[f32],
+    closing_height: f32,
+) -> Vec<Range<usize>> {
+    let mut pages = Vec::new();
+    let mut start = 0;
+    // Ligne de base du premier texte de la ligne suivante
+    let mut y_position = table.top - table.row_height;
+    for (idx, height) in row_heights.iter().enumerate() {
+        // Une ligne plus haute qu'une page est dessinée quand même, seule sur sa page
+        if y_position - (height - table.row_height) < margin_bottom && idx > start {
+            pages.push(start..idx);
+            start = idx;
+            y_position = table.continuation_top - table.row_height;
+        }
+        y_position -= height;
+    }
+    pages.push(start..row_heights.len());
+    if closing_height > 0.0 && y_position - closing_height < margin_bottom {
+        pages.push(row_heights.len()..row_heights.len());
+    }
+    return pages;
+}
+
+/// Hauteur d'une ligne dont les cellules ont été coupées en `lines`.
+fn row_height(table: &Table, cells: &[Vec<String>]) -> f32 {
+    let line_count = cells.iter().map(|lines| lines.len()).max().unwrap_or(1);
+    return table.row_height + table.line_height * (line_count.max(1) - 1) as f32;
+}
+
+/// Début d'un texte pour qu'il soit aligné sur `x`.
+fn aligned_x(x: f32, align: Align, text: &str, size: f32, metrics: &FontMetrics) -> f32 {
+    return match align {
+        Align::Left => x,
+        Align::Right => x - metrics.text_width(text, size),
+    };
+}
+
+fn column_x(column: &Column, text: &str, size: f32, metrics: &FontMetrics) -> f32 {
+    return match column.align {
+        Align::Left => column.x,
+        Align::Right => aligned_x(column.x + column.width, Align::Right, text, size, metrics),
+    };
+}
+
+struct Fonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    regular_metrics: FontMetrics,
+    bold_metrics: FontMetrics,
+}
+
+impl Fonts {
+    fn get(&self, bold: bool) -> (&IndirectFontRef, &FontMetrics) {
+        return match bold {
+            true => (&self.bold, &self.bold_metrics),
+            false => (&self.regular, &self.regular_metrics),
+        };
+    }
+}
+
+/// Dessine un document sur les pages de sa mise en page.
+struct PdfRenderer<'a> {
+    document: &'a Document,
+    fonts: Fonts,
+}
+
+impl PdfRenderer<'_> {
+    /// Place prise par les blocs du récapitulatif sous le tableau.
+    fn summary_height(&self) -> f32 {
+        let blocks = &self.document.summary;
+        if blocks.is_empty() {
+            return 0.0;
+        }
+        let depth = blocks
+            .iter()
+            .map(|block| block.placement.y + block.depth())
+            .fold(0.0, f32::max);
+        return depth + SUMMARY_MARGIN;
+    }
+
+    fn add_separator(&self, layer: &PdfLayerReference, y: f32) {
+        let page = &self.document.layout.page;
+        layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(page.margin_left), Mm(y)), false),
+                (Point::new(Mm(page.width - page.margin_right), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    /// Dessine `symbol`, son coin supérieur gauche en `x`, `top`, avec un
+    /// rectangle plein pour chaque suite de modules foncés.
+    fn add_symbol(
+        &self,
+        layer: &PdfLayerReference,
+        symbol: &Symbol,
+        (x, top): (f32, f32),
+        module: f32,
+        height: f32,
+    ) {
+        let row_height = symbol.size(module, height).1 / symbol.height as f32;
+        for (row, start, length) in symbol.dark_runs() {
+            let y = top - row_height * row as f32;
+            let left = x + module * start as f32;
+            layer.add_rect(Rect {
+                ll: Point::new(Mm(left), Mm(y - row_height)),
+                ur: Point::new(Mm(left + module * length as f32), Mm(y)),
+                mode: path::PaintMode::Fill,
+                winding: path::WindingOrder::NonZero,
+            });
+        }
+    }
+
+    /// Dessine le code-barres d'une cellule du tableau au-dessus de sa valeur,
+    /// dans une ligne dont la première ligne de base est `y`.
+    fn add_cell_symbol(
+        &self,
+        layer: &PdfLayerReference,
+        symbol: &Symbol,
+        (column, geometry): (&Column, &Table),
+        y: f32,
+        value: &str,
+    ) {
+        // Entre le texte de la valeur et le séparateur de la ligne précédente
+        let top = y + geometry.row_height - 3.0;
+        let height = geometry.row_height - 4.5;
+        let module = (column.width / symbol.width as f32)
+            .min(height / symbol.height as f32)
+            .min(MAX_CELL_MODULE);
+        self.add_symbol(layer, symbol, (column.x, top), module, height);
+        layer.use_text(
+            value,
+            CELL_VALUE_SIZE,
+            Mm(column.x),
+            Mm(y - 1.0),
+            &self.fonts.regular,
+        );
+    }
+
+    fn add_block(
+        &self,
+        layer: &PdfLayerReference,
+        block: &Block,
+        values: &HashMap<String, String>,
+    ) -> Result<(), Error> {
+        let placement = &block.placement;
+        match &block.content {
+            Content::Image(path) => {
+                let image = load_png(path)?;
+                image.add_to_layer(
+                    layer.clone(),
+                    ImageTransform {
+                        translate_x: Some(Mm(placement.x)),
+                        translate_y: Some(Mm(placement.y)),
+                        ..Default::default()
+                    },
+                );
+            }
+            Content::Heading(text) => {
+                layer.use_text(
+                    fill(text, values),
+                    placement.size,
+                    Mm(placement.x),
+                    Mm(placement.y),
+                    &self.fonts.bold,
+                );
+            }
+            Content::Lines(lines) => {
+                for (idx, run) in lines.iter().enumerate() {
+                    let (font, metrics) = self.fonts.get(run.bold);
+                    let text = fill(&run.text, values);
+                    let x = aligned_x(placement.x, placement.align, &text, placement.size, metrics);
+                    let y = placement.y - placement.line_height * idx as f32;
+                    layer.use_text(text, placement.size, Mm(x), Mm(y), font);
+                }
+            }
+            Content::Rule => self.add_separator(layer, placement.y),
+            Content::Barcode {
+                symbology,
+                value,
+                module,
+                height,
+            } => {
+                let symbol = barcode::encode(*symbology, value)?;
+                self.add_symbol(layer, &symbol, (placement.x, placement.y), *module, *height);
+            }
+        }
+        return Ok(());
+    }
+
+    fn add_signatures(
+        &self,
+        layer: &PdfLayerReference,
+        signatures: &Signatures,
+        mut y_position: f32,
+    ) {
+        for label in &self.document.signatures {
+            layer.add_rect(Rect {
+                ll: Point::new(Mm(signatures.x), Mm(y_position - signatures.height)),
+                ur: Point::new(Mm(signatures.x + signatures.width), Mm(y_position)),
+                mode: path::PaintMode::Stroke,
+                winding: path::WindingOrder::EvenOdd,
+            });
+            layer.use_text(
+                label.clone(),
+                signatures.size,
+                Mm(signatures.x + 5.),
+                Mm(y_position - 5.),
+                &self.fonts.regular,
+            );
+            y_position -= signatures.height + signatures.gap;
+        }
+    }
+
+    fn draw(
+        &self,
+        doc: &PdfDocumentReference,
+        mut current_layer: PdfLayerReference,
+    ) -> Result<(), Error> {
+        let document = self.document;
+        let layout = &document.layout;
+        let table = match (&layout.table, &document.table) {
+            (Some(geometry), Some(table)) => Some((geometry, table)),
+            _ => None,
+        };
+
+        // Les valeurs des colonnes de codes-barres qui ne peuvent pas être
+        // encodées sont écrites en texte
+        let symbols = match table {
+            Some((_, table)) => table
+                .rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .zip(&table.columns)
+                        .map(|(text, column)| {
+                            column
+                                .barcode
+                                .and_then(|symbology| barcode::encode(symbology, text).ok())
+                        })
+                        .collect::<Vec<Option<Symbol>>>()
+                })
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+
+        // Découper les cellules du tableau en lignes
+        let rows = match table {
+            Some((geometry, table)) => table
+                .rows
+                .iter()
+                .zip(&symbols)
+                .map(|(row, symbols)| {
+                    row.iter()
+                        .zip(&geometry.columns)
+                        .zip(symbols)
+                        .map(|((text, column), symbol)| match symbol {
+                            Some(_) => vec![text.clone()],
+                            None => {
+                                self.fonts
+                                    .regular_metrics
+                                    .wrap(text, geometry.size, column.width)
+                            }
+                        })
+                        .collect::<Vec<Vec<String>>>()
+                })
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+        let heights = match table {
+            Some((geometry, _)) => rows
+                .iter()
+                .map(|cells| row_height(geometry, cells))
+                .collect(),
+            None => Vec::new(),
+        };
+        let summary_height = self.summary_height();
+        let signatures = layout
+            .signatures
+            .as_ref()
+            .filter(|_| !document.signatures.is_empty());
+        let closing_height = summary_height
+            + signatures
+                .map(|signatures| signatures.total_height())
+                .unwrap_or_default();
+        let pages = match table {
+            Some((geometry, _)) => paginate(
+                geometry,
+                layout.page.margin_bottom,
+                &heights,
+                closing_height,
+            ),
+            None => vec![Range::default()],
+        };
+
+        let page_count = pages.len();
+        let mut values = HashMap::from([("pages".to_string(), page_count.to_string())]);
+        for (page_idx, page_rows) in pages.into_iter().enumerate() {
+            values.insert("page".to_string(), (page_idx + 1).to_string());
+            let blocks = if page_idx == 0 {
+                &document.header
+            } else {
+                let (page, layer) =
+                    doc.add_page(Mm(layout.page.width), Mm(layout.page.height), "Layer 1");
+                current_layer = doc.get_page(page).get_layer(layer);
+                &document.continuation
+            };
+            for block in blocks.iter().chain(&document.footer) {
+                self.add_block(&current_layer, block, &values)?;
+            }
+
+            // Ajouter un tableau pour les objets
+            let mut y_position = layout.page.height;
+            if let Some((geometry, table)) = table {
+                y_position = match page_idx {
+                    0 => geometry.top,
+                    _ => geometry.continuation_top,
+                };
+                if page_idx == 0 || !page_rows.is_empty() {
+                    for (column, header) in geometry.columns.iter().zip(&table.columns) {
+                        let x = column_x(
+                            column,
+                            &header.header,
+                            geometry.size,
+                            &self.fonts.bold_metrics,
+                        );
+                        current_layer.use_text(
+                            header.header.clone(),
+                            geometry.size,
+                            Mm(x),
+                            Mm(y_position),
+                            &self.fonts.bold,
+                        );
+                    }
+                    // Ajouter des lignes de séparation
+                    self.add_separator(&current_layer, y_position - 2.0);
+                    y_position -= geometry.row_height;
+                }
+
+                let page_symbols = &symbols[page_rows.clone()];
+                for ((cells, height), symbols) in rows[page_rows.clone()]
+                    .iter()
+                    .zip(&heights[page_rows])
+                    .zip(page_symbols)
+                {
+                    for ((lines, column), symbol) in
+                        cells.iter().zip(&geometry.columns).zip(symbols)
+                    {
+                        if let Some(symbol) = symbol {
+                            self.add_cell_symbol(
+                                &current_layer,
+                                symbol,
+                                (column, geometry),
+                                y_position,
+                                &lines[0],
+                            );
+                            continue;
+                        }
+                        for (line_idx, line) in lines.iter().enumerate() {
+                            let x =
+                                column_x(column, line, geometry.size, &self.fonts.regular_metrics);
+                            current_layer.use_text(
+                                line.clone(),
+                                geometry.size,
+                                Mm(x),
+                                Mm(y_position - geometry.line_height * line_idx as f32),
+                                &self.fonts.regular,
+                            );
+                        }
+                    }
+
+                    // Ajouter des lignes de séparation
+                    self.add_separator(
+                        &current_layer,
+                        y_position - (height - geometry.row_height) - 2.0,
+                    );
+
+                    y_position -= height;
+                }
+            }
+
+            if page_idx + 1 == page_count {
+                for block in &document.summary {
+                    self.add_block(&current_layer, &block.below(y_position), &values)?;
+                }
+                y_position -= summary_height;
+                if let Some(signatures) = signatures {
+                    self.add_signatures(&current_layer, signatures, y_position);
+                }
+            }
+        }
+        return Ok(());
+    }
+}
+
+/// Dessine le document sur les pages de sa mise en page, le tableau
+/// continuant sur autant de pages que nécessaire.
+pub fn render(document: &Document) -> Result<Vec<u8>, Error> {
+    let layout = &document.layout;
+    let (doc, page1, layer1) = PdfDocument::new(
+        document.title.clone(),
+        Mm(layout.page.width),
+        Mm(layout.page.height),
+        "Layer 1",
+    );
+    let renderer = PdfRenderer {
+        document,
+        fonts: Fonts {
+            regular: load_font(&doc, &layout.fonts.regular)?,
+            bold: load_font(&doc, &layout.fonts.bold)?,
+            regular_metrics: FontMetrics::load(&layout.fonts.regular)?,
+            bold_metrics: FontMetrics::load(&layout.fonts.bold)?,
+        },
+    };
+    renderer.draw(&doc, doc.get_page(page1).get_layer(layer1))?;
+    return Ok(doc.save_to_bytes()?);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{barcode::BarcodeError, document::ReportData, template::Template};
+
+    fn table() -> Table {
+        return Template::delivery_note().unwrap().table.unwrap();
+    }
+
+    fn paginate_lines(count: usize) -> Vec<Range<usize>> {
+        let table = table();
+        return paginate(&table, 20.0, &vec![table.row_height; count], 45.0);
+    }
+
+    #[test]
+    fn test_paginate_single_page() {
+        assert_eq!(paginate_lines(0), vec![0..0]);
+        assert_eq!(paginate_lines(12), vec![0..12]);
+    }
+
+    #[test]
+    fn test_paginate_signatures_on_last_page() {
+        // Les lignes tiennent sur la première page, mais pas les cadres de signature
+        assert_eq!(paginate_lines(15), vec![0..15, 15..15]);
+        assert_eq!(paginate_lines(17), vec![0..17, 17..17]);
+        assert_eq!(paginate_lines(18), vec![0..17, 17..18]);
+    }
+
+    #[test]
+    fn test_paginate_many_items() {
+        assert_eq!(paginate_lines(60), vec![0..17, 17..42, 42..60]);
+    }
+
+    #[test]
+    fn test_paginate_wrapped_rows() {
+        let table = table();
+        // Les lignes de 3 lignes font 20 mm de haut, 8 tiennent sur la première page
+        let mut heights = vec![20.0; 9];
+        heights.push(table.row_height);
+        assert_eq!(paginate(&table, 20.0, &heights, 45.0), vec![0..8, 8..10]);
+
+        // Une ligne plus haute qu'une page reste seule sur sa page
+        assert_eq!(
+            paginate(&table, 20.0, &[400.0, table.row_height], 45.0),
+            vec![0..1, 1..2]
+        );
+
+        // Sans signatures, les dernières lignes peuvent descendre jusqu'à la marge
+        assert_eq!(
+            paginate(&table, 20.0, &[table.row_height; 17], 0.0),
+            vec![0..17]
+        );
+    }
+
+    #[test]
+    fn test_row_height() {
+        let table = table();
+        let line = |count: usize| vec!["x".to_string(); count];
+        assert_eq!(row_height(&table, &[line(1), line(1), line(1)]), 10.0);
+        assert_eq!(row_height(&table, &[line(2), line(4), line(1)]), 25.0);
+    }
+
+    #[test]
+    fn test_generate_pages() {
+        let data = ReportData {
+            title: "Bon de Livraison".to_string(),
+            rows: vec![HashMap::from([("description".to_string(), "Chaise".to_string())]); 30],
+            ..Default::default()
+        };
+        let document = Document::new(Template::delivery_note().unwrap(), &data);
+        let bytes = render(&document).unwrap();
+        // "/Type/Pages" est l'arbre des pages, pas une page
+        let pages = bytes
+            .windows(11)
+            .filter(|w| w.starts_with(b"/Type/Page") && w[10] != b's')
+            .count();
+        assert_eq!(pages, 2);
+    }
+
+    #[test]
+    fn test_barcodes() {
+        let data = |number: &str| ReportData {
+            title: "Bon de Livraison".to_string(),
+            values: HashMap::from([("number".to_string(), number.to_string())]),
+            rows: vec![
+                HashMap::from([("reference".to_string(), "4006381333931".to_string())]),
+                HashMap::from([("reference".to_string(), "REF-1".to_string())]),
+            ],
+            ..Default::default()
+        };
+        let document = Document::new(Template::delivery_note().unwrap(), &data("BL-2026-00001"));
+        assert!(render(&document).is_ok());
+
+        // Le numéro de l'en-tête ne peut pas être écrit en Code 128
+        let document = Document::new(Template::delivery_note().unwrap(), &data("BL-2026-é"));
+        assert!(matches!(
+            render(&document),
+            Err(Error::Barcode(BarcodeError::UnsupportedCharacter('é')))
+        ));
+    }
+}
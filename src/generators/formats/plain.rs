@@ -0,0 +1,194 @@
+use crate::{
+    error::Error,
+    generators::{
+        document::{Content, Document, Part, Table},
+        template::Align,
+    },
+};
+
+/// Characters per line of a 80 mm thermal printer.
+pub const WIDTH: usize = 42;
+/// Lines left blank to sign in.
+const SIGNATURE_LINES: usize = 3;
+
+fn char_count(text: &str) -> usize {
+    return text.chars().count();
+}
+
+/// Splits `text` into lines of at most `width` characters, breaking between
+/// words when possible.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word = word.to_string();
+            if !line.is_empty() && char_count(&line) + 1 + char_count(&word) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            while char_count(&word) > width {
+                let rest = word.split_off(word.char_indices().nth(width).unwrap().0);
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word);
+                word = rest;
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+    return lines;
+}
+
+fn aligned(text: &str, align: Align) -> String {
+    return match align {
+        Align::Left => text.to_string(),
+        Align::Right => format!("{:>width$}", text, width = WIDTH),
+    };
+}
+
+fn centered(text: &str) -> String {
+    let padding = WIDTH.saturating_sub(char_count(text)) / 2;
+    return format!("{}{}", " ".repeat(padding), text);
+}
+
+fn rule() -> String {
+    return "-".repeat(WIDTH);
+}
+
+/// The text of the left aligned cells, wrapped, followed by the right aligned
+/// cells on one line, each padded to the width of its column.
+fn table_row(table: &Table, cells: &[String], widths: &[usize]) -> Vec<String> {
+    let columns = table.columns.iter().zip(cells).zip(widths);
+    let left = columns
+        .clone()
+        .filter(|((column, _), _)| column.align == Align::Left)
+        .map(|((_, cell), _)| cell.as_str())
+        .filter(|cell| !cell.is_empty())
+        .collect::<Vec<&str>>();
+    let right = columns
+        .filter(|((column, _), _)| column.align == Align::Right)
+        .map(|((_, cell), width)| format!("{:>width$}", cell, width = width))
+        .collect::<Vec<String>>();
+    let mut lines = wrap(&left.join(" "), WIDTH);
+    if !right.is_empty() {
+        lines.push(aligned(&right.join(" "), Align::Right));
+    }
+    return lines;
+}
+
+fn table_lines(table: &Table) -> Vec<String> {
+    let headers = table
+        .columns
+        .iter()
+        .map(|column| column.header.clone())
+        .collect::<Vec<String>>();
+    let widths = (0..table.columns.len())
+        .map(|idx| {
+            return table
+                .rows
+                .iter()
+                .chain([&headers])
+                .map(|row| char_count(&row[idx]))
+                .max()
+                .unwrap_or_default();
+        })
+        .collect::<Vec<usize>>();
+    let mut lines = table_row(table, &headers, &widths);
+    lines.push(rule());
+    for row in &table.rows {
+        lines.extend(table_row(table, row, &widths));
+    }
+    lines.push(rule());
+    return lines;
+}
+
+/// Writes the document as plain text for thermal printers, `WIDTH`
+/// characters wide.
+pub fn render(document: &Document) -> Result<Vec<u8>, Error> {
+    let mut lines = Vec::new();
+    for part in document.parts().into_iter().chain(document.footer_parts()) {
+        match part {
+            Part::Block(block) => match &block.content {
+                // An image cannot be printed as text
+                Content::Image(_) => continue,
+                Content::Heading(text) => {
+                    lines.extend(wrap(text, WIDTH).iter().map(|line| centered(line)))
+                }
+                Content::Lines(runs) => {
+                    for run in runs {
+                        let wrapped = wrap(&run.text, WIDTH);
+                        lines.extend(
+                            wrapped
+                                .iter()
+                                .map(|line| aligned(line, block.placement.align)),
+                        );
+                    }
+                }
+                Content::Rule => lines.push(rule()),
+                // A thermal printer in text mode cannot draw bars
+                Content::Barcode { value, .. } => lines.push(value.clone()),
+            },
+            Part::Table(table) => lines.extend(table_lines(table)),
+            Part::Signatures(labels) => {
+                for label in labels {
+                    lines.push(label.clone());
+                    lines.extend(vec![String::new(); SIGNATURE_LINES]);
+                    lines.push("_".repeat(WIDTH));
+                }
+            }
+        }
+        lines.push(String::new());
+    }
+    let mut text = lines.join("\n");
+    text.truncate(text.trim_end().len());
+    text.push('\n');
+    return Ok(text.into_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::document::TableColumn;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(
+            wrap("une chaise pliante", 10),
+            vec!["une chaise", "pliante"]
+        );
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("", 4), Vec::<String>::new());
+        assert_eq!(wrap("a\nb", 4), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_table_lines() {
+        let column = |header: &str, align| TableColumn {
+            header: header.to_string(),
+            align,
+            barcode: None,
+        };
+        let table = Table {
+            columns: vec![
+                column("Description", Align::Left),
+                column("Qté", Align::Right),
+                column("Total HT", Align::Right),
+            ],
+            rows: vec![vec![
+                "Chaise".to_string(),
+                "3".to_string(),
+                "37,50".to_string(),
+            ]],
+        };
+        let lines = table_lines(&table);
+        assert_eq!(lines[0], "Description");
+        assert_eq!(lines[1], format!("{:>42}", "Qté Total HT"));
+        assert_eq!(lines[4], format!("{:>42}", "  3    37,50"));
+        assert!(lines.iter().all(|line| char_count(line) <= WIDTH));
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use crate::error::Error;
+use crate::i18n::Catalog;
+use crate::models;
+
+use super::{
+    document::{Document, ReportData},
+    format_quantity,
+    output::ReportName,
+    party_values,
+    template::Template,
+    Report,
+};
+
+pub struct Invoice {
+    invoice: models::invoice::Invoice,
+}
+
+impl Invoice {
+    pub fn new(invoice: models::invoice::Invoice) -> Self {
+        return Self { invoice };
+    }
+
+    /// Fails if the catalog of the language of the client cannot be read.
+    fn data(&self) -> Result<ReportData, Error> {
+        let invoice = &self.invoice;
+        let language = invoice.client.language;
+        let catalog = Catalog::load(language)?;
+        let format_amount = |amount| language.format_amount(amount);
+        let format_decimal = |value| language.format_decimal(value);
+        let values = [
+            ("number", invoice.number.clone()),
+            ("date", language.format_date(invoice.date)),
+            ("due_date", language.format_date(invoice.due_date)),
+            ("delivery_note", invoice.delivery_note.clone()),
+            (
+                "total_excluding_tax",
+                format_amount(invoice.total_excluding_tax()),
+            ),
+            ("total_vat", format_amount(invoice.total_vat())),
+            (
+                "total_including_tax",
+                format_amount(invoice.total_including_tax()),
+            ),
+        ];
+        let rows = invoice
+            .lines
+            .iter()
+            .map(|line| {
+                HashMap::from([
+                    ("id".to_string(), line.product.id().clone()),
+                    ("reference".to_string(), line.product.reference().clone()),
+                    (
+                        "description".to_string(),
+                        line.product.description().clone(),
+                    ),
+                    (
+                        "quantity".to_string(),
+                        format_quantity(&catalog, line.quantity, *line.product.unit()),
+                    ),
+                    ("unit_price".to_string(), format_amount(line.unit_price)),
+                    (
+                        "vat_rate".to_string(),
+                        format!("{} %", format_decimal(line.vat_rate)),
+                    ),
+                    ("total".to_string(), format_amount(line.total())),
+                ])
+            })
+            .collect();
+        let vat = invoice
+            .vat_breakdown()
+            .into_iter()
+            .map(|vat| {
+                HashMap::from([
+                    ("rate".to_string(), format_decimal(vat.rate)),
+                    ("base".to_string(), format_amount(vat.base)),
+                    ("amount".to_string(), format_amount(vat.amount)),
+                ])
+            })
+            .collect();
+        return Ok(ReportData {
+            title: catalog.get("invoice"),
+            values: values
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .chain(party_values(&invoice.sender, &invoice.client, &catalog))
+                .collect(),
+            rows,
+            lists: HashMap::from([("vat".to_string(), vat)]),
+            logo_path: invoice.sender.logo_path(),
+            language,
+            messages: catalog.templates(),
+        });
+    }
+}
+
+impl Report for Invoice {
+    fn document(&self) -> Result<Document, Error> {
+        return Ok(Document::new(Template::invoice()?, &self.data()?));
+    }
+
+    fn name(&self) -> ReportName {
+        return ReportName {
+            kind: "facture",
+            number: self.invoice.number.clone(),
+            client: self.invoice.client.name.clone(),
+            date: self.invoice.date,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::formats::Format;
+    use crate::models::{invoice::InvoiceLine, product::Product};
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+
+    #[test]
+    fn test_data() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 20).unwrap();
+        let line = |vat_rate| InvoiceLine {
+            product: Product::new("1", "Chaise", "CH-01"),
+            unit_price: dec!(12.5),
+            quantity: dec!(3),
+            vat_rate,
+        };
+        let invoice = Invoice::new(models::invoice::Invoice {
+            number: "FA-2026-00001".to_string(),
+            date,
+            due_date: date,
+            delivery_note: "BL-2026-00001".to_string(),
+            sender: Default::default(),
+            client: Default::default(),
+            lines: vec![line(dec!(20)), line(dec!(5.5))],
+        });
+        let data = invoice.data().unwrap();
+        assert_eq!(data.values["total_excluding_tax"], "75,00");
+        assert_eq!(data.values["total_including_tax"], "84,56");
+        assert_eq!(data.rows[1]["vat_rate"], "5,5 %");
+        assert_eq!(data.lists["vat"].len(), 2);
+        assert_eq!(data.lists["vat"][0]["amount"], "2,06");
+        assert!(Format::Pdf.render(&invoice.document().unwrap()).is_ok());
+    }
+}
@@ -1,7 +1,161 @@
-use printpdf::PdfDocumentReference;
+use std::{fs::File, path::Path};
 
+use rust_decimal::Decimal;
+
+use crate::{
+    error::Error,
+    i18n::Catalog,
+    models::{client::Client, sender::Sender, unit::Unit},
+};
+use document::Document;
+use output::ReportName;
+use printpdf::{
+    image_crate::codecs::png::PngDecoder, Image, IndirectFontRef, PdfDocumentReference,
+};
+
+pub mod barcode;
 pub mod delivery_note;
+pub mod document;
+pub mod formats;
+pub mod invoice;
+pub mod output;
+pub mod quote;
+pub mod template;
+pub mod text;
+
+/// Joins the parts that are not empty.
+fn join_filled(parts: &[String]) -> String {
+    return parts
+        .iter()
+        .filter(|part| !part.is_empty())
+        .cloned()
+        .collect::<Vec<String>>()
+        .join(" – ");
+}
+
+/// The values of the sender and of the client of a document, prefixed by
+/// `sender.` and `client.`, labelled with the messages of `catalog`.
+pub fn party_values(sender: &Sender, client: &Client, catalog: &Catalog) -> Vec<(String, String)> {
+    let labelled = |id: &str, name: &str, value: &String| {
+        if value.is_empty() {
+            return String::new();
+        }
+        return catalog.format(id, &[(name, value.clone())]);
+    };
+    let values = [
+        ("sender.name", sender.name.clone()),
+        ("sender.address1", sender.addr1.clone()),
+        ("sender.address2", sender.addr2.clone()),
+        ("sender.postal_code", sender.postal_code.clone()),
+        ("sender.city", sender.city.clone()),
+        ("sender.siret", sender.siret.clone()),
+        ("sender.vat_number", sender.vat_number.clone()),
+        ("sender.phone", sender.phone.clone()),
+        ("sender.email", sender.email.clone()),
+        ("sender.footer", sender.footer.clone()),
+        (
+            "sender.legal",
+            join_filled(&[
+                sender.name.clone(),
+                labelled("sender-siret", "siret", &sender.siret),
+                labelled("sender-vat-number", "vat_number", &sender.vat_number),
+            ]),
+        ),
+        (
+            "sender.contact",
+            join_filled(&[
+                labelled("sender-phone", "phone", &sender.phone),
+                sender.email.clone(),
+            ]),
+        ),
+        ("client.id", client.id.clone()),
+        ("client.name", client.name.clone()),
+        ("client.address1", client.address1.clone()),
+        ("client.address2", client.address2.clone()),
+        ("client.postal_code", client.postal_code.clone()),
+        ("client.city", client.city.clone()),
+    ];
+    return values
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+}
+
+/// A quantity followed by the symbol of its unit in the language of
+/// `catalog`, e.g. `2,5 kg`.
+pub fn format_quantity(catalog: &Catalog, quantity: Decimal, unit: Unit) -> String {
+    let quantity = catalog.language.format_decimal(quantity);
+    return format!("{} {}", quantity, catalog.get(unit.message()));
+}
 
 pub trait Report {
-    fn generate(&self) -> PdfDocumentReference;
+    /// The content of the report, independent of the output format.
+    fn document(&self) -> Result<Document, Error>;
+    /// What the file of the report is named after.
+    fn name(&self) -> ReportName;
+    /// Fails when a value printed on the report breaks a rule, which is
+    /// checked before the report is written.
+    fn validate(&self) -> Result<(), Error> {
+        return Ok(());
+    }
+}
+
+/// Embeds the TTF font found at `path` in the document.
+pub fn load_font(doc: &PdfDocumentReference, path: &str) -> Result<IndirectFontRef, Error> {
+    let file = File::open(path).map_err(|source| Error::MissingAsset {
+        path: path.into(),
+        source,
+    })?;
+    Ok(doc.add_external_font(file)?)
+}
+
+/// Reads the PNG image found at `path`.
+pub fn load_png(path: &str) -> Result<Image, Error> {
+    let invalid = |message: String| Error::InvalidAsset {
+        path: Path::new(path).to_owned(),
+        message,
+    };
+    let mut file = File::open(path).map_err(|source| Error::MissingAsset {
+        path: path.into(),
+        source,
+    })?;
+    let decoder = PngDecoder::new(&mut file).map_err(|e| invalid(e.to_string()))?;
+    Image::try_from(decoder).map_err(|e| invalid(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+
+    #[test]
+    fn test_party_values() {
+        let sender = Sender {
+            name: "Consitainer".to_string(),
+            siret: "123 456 789 00012".to_string(),
+            email: "contact@example.com".to_string(),
+            ..Default::default()
+        };
+        let catalog = Catalog::load(Language::French).unwrap();
+        let values = party_values(&sender, &Client::default(), &catalog)
+            .into_iter()
+            .collect::<std::collections::HashMap<_, _>>();
+        assert_eq!(
+            values["sender.legal"],
+            "Consitainer – SIRET 123 456 789 00012"
+        );
+        assert_eq!(values["sender.contact"], "contact@example.com");
+
+        let sender = Sender {
+            vat_number: "FR12345678901".to_string(),
+            phone: "03 25 00 00 00".to_string(),
+            ..sender
+        };
+        let catalog = Catalog::load(Language::German).unwrap();
+        let values = party_values(&sender, &Client::default(), &catalog)
+            .into_iter()
+            .collect::<std::collections::HashMap<_, _>>();
+        assert!(values["sender.legal"].ends_with("USt-IdNr. FR12345678901"));
+        assert!(values["sender.contact"].starts_with("Tel. 03 25 00 00 00"));
+    }
 }
@@ -0,0 +1,140 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::{
+    models::fields::{take, take_or_default},
+    storage::{Savable, StorageError},
+};
+
+use super::{document::fill, formats::Format};
+
+pub const DEFAULT_DIRECTORY: &str = "reports";
+pub const DEFAULT_PATTERN: &str = "{type}_{number}_{client}_{date}.{ext}";
+
+/// What a report file is named after.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportName {
+    /// Kind of document, such as `bon_livraison`.
+    pub kind: &'static str,
+    pub number: String,
+    pub client: String,
+    pub date: NaiveDate,
+}
+
+/// Where the reports are written and how their files are named.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputSettings {
+    pub directory: PathBuf,
+    /// File name, where `{type}`, `{number}`, `{client}` and `{date}` are
+    /// replaced by the values of the report, and `{ext}` by the extension of
+    /// its format.
+    pub pattern: String,
+    /// Whether an existing file may be replaced.
+    pub overwrite: bool,
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        return Self {
+            directory: PathBuf::from(DEFAULT_DIRECTORY),
+            pattern: DEFAULT_PATTERN.to_string(),
+            overwrite: false,
+        };
+    }
+}
+
+/// Saved as a single row, so that the settings chosen in the interface are
+/// kept from one start to the next.
+impl Savable for OutputSettings {
+    fn savable_name() -> String {
+        return "output_settings".to_string();
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        return vec![
+            ("id".to_string(), "default".to_string()),
+            (
+                "directory".to_string(),
+                self.directory.display().to_string(),
+            ),
+            ("pattern".to_string(), self.pattern.clone()),
+            ("overwrite".to_string(), self.overwrite.to_string()),
+        ];
+    }
+
+    fn from_fields(mut fields: Vec<(String, String)>) -> Result<Self, StorageError> {
+        let overwrite = take_or_default(&mut fields, "overwrite");
+        return Ok(Self {
+            directory: PathBuf::from(take(&mut fields, "directory")?),
+            pattern: take(&mut fields, "pattern")?,
+            overwrite: overwrite.parse().map_err(|_| {
+                StorageError::invalid_value(
+                    "overwrite",
+                    &overwrite,
+                    "expected true or false".to_string(),
+                )
+            })?,
+        });
+    }
+}
+
+/// Keeps letters, digits, `-` and `.`, so that a value cannot add a folder
+/// or a character some file systems refuse.
+fn sanitize(value: &str) -> String {
+    return value
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '.' {
+                return c;
+            }
+            return '_';
+        })
+        .collect();
+}
+
+impl OutputSettings {
+    pub fn file_name(&self, name: &ReportName, format: Format) -> String {
+        let values = HashMap::from([
+            ("type".to_string(), sanitize(name.kind)),
+            ("number".to_string(), sanitize(&name.number)),
+            ("client".to_string(), sanitize(&name.client)),
+            ("date".to_string(), name.date.format("%Y-%m-%d").to_string()),
+            ("ext".to_string(), format.extension().to_string()),
+        ]);
+        return fill(&self.pattern, &values);
+    }
+
+    pub fn path(&self, name: &ReportName, format: Format) -> PathBuf {
+        return self.directory.join(self.file_name(name, format));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        let name = ReportName {
+            kind: "facture",
+            number: "FA-2026-00001".to_string(),
+            client: "Dupont / Fils & Cie".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 3, 20).unwrap(),
+        };
+        let settings = OutputSettings::default();
+        assert_eq!(
+            settings.path(&name, Format::Pdf),
+            PathBuf::from("reports/facture_FA-2026-00001_Dupont___Fils___Cie_2026-03-20.pdf")
+        );
+        let settings = OutputSettings {
+            pattern: "{number}.{ext}".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.file_name(&name, Format::Html),
+            "FA-2026-00001.html"
+        );
+    }
+}
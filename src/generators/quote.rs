@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::error::Error;
+use crate::i18n::Catalog;
+use crate::models::{self, amount::round_amount, validation::check};
+
+use super::{
+    document::{Document, ReportData},
+    format_quantity,
+    output::ReportName,
+    party_values,
+    template::Template,
+    Report,
+};
+
+pub struct Quote {
+    quote: models::quote::Quote,
+}
+
+impl Quote {
+    pub fn new(quote: models::quote::Quote) -> Self {
+        return Self { quote };
+    }
+
+    /// Fails if the catalog of the language of the client cannot be read.
+    fn data(&self) -> Result<ReportData, Error> {
+        let quote = &self.quote;
+        let language = quote.client.language;
+        let catalog = Catalog::load(language)?;
+        let mut rows = Vec::new();
+        let mut total = Decimal::ZERO;
+        for item in &quote.items {
+            let line_total = round_amount(item.price() * item.quantity());
+            total += line_total;
+            rows.push(HashMap::from([
+                ("id".to_string(), item.product().id().clone()),
+                ("reference".to_string(), item.product().reference().clone()),
+                (
+                    "description".to_string(),
+                    item.product().description().clone(),
+                ),
+                (
+                    "quantity".to_string(),
+                    format_quantity(&catalog, item.quantity(), *item.product().unit()),
+                ),
+                (
+                    "unit_price".to_string(),
+                    language.format_amount(item.price()),
+                ),
+                ("total".to_string(), language.format_amount(line_total)),
+            ]));
+        }
+        let values = [
+            ("number", quote.number.clone()),
+            ("date", language.format_date(quote.date)),
+            ("valid_until", language.format_date(quote.valid_until)),
+            ("total_excluding_tax", language.format_amount(total)),
+        ];
+        return Ok(ReportData {
+            title: catalog.get("quote"),
+            values: values
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .chain(party_values(&quote.sender, &quote.client, &catalog))
+                .collect(),
+            rows,
+            lists: HashMap::new(),
+            logo_path: quote.sender.logo_path(),
+            language,
+            messages: catalog.templates(),
+        });
+    }
+}
+
+impl Report for Quote {
+    fn document(&self) -> Result<Document, Error> {
+        return Ok(Document::new(Template::quote()?, &self.data()?));
+    }
+
+    fn name(&self) -> ReportName {
+        return ReportName {
+            kind: "devis",
+            number: self.quote.number.clone(),
+            client: self.quote.client.name.clone(),
+            date: self.quote.date,
+        };
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        return check("quote", &self.quote.number, &self.quote);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::formats::Format;
+    use crate::models::{item::Item, product::Product};
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+
+    fn quote() -> Quote {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let item = |price: Decimal, quantity: Decimal| {
+            Item::new(Product::new("1", "Chaise", "CH-01"), price, quantity)
+        };
+        return Quote::new(models::quote::Quote {
+            number: "DE-2026-00001".to_string(),
+            date,
+            valid_until: NaiveDate::from_ymd_opt(2026, 3, 31).unwrap(),
+            sender: Default::default(),
+            client: Default::default(),
+            items: vec![item(dec!(12.50), dec!(3)), item(dec!(0.333), dec!(2))],
+            delivery_note: String::new(),
+        });
+    }
+
+    #[test]
+    fn test_data() {
+        let quote = quote();
+        let data = quote.data().unwrap();
+        assert_eq!(data.values["valid_until"], "31/03/2026");
+        assert_eq!(data.rows[0]["quantity"], "3 pce");
+        assert_eq!(data.rows[0]["total"], "37,50");
+        assert_eq!(data.rows[1]["total"], "0,67");
+        assert_eq!(data.values["total_excluding_tax"], "38,17");
+        assert!(Format::Pdf.render(&quote.document().unwrap()).is_ok());
+    }
+}
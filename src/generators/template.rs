@@ -0,0 +1,310 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+use super::barcode::Symbology;
+use crate::error::Error;
+
+/// Where the layouts are read from, so they can be changed without recompiling.
+pub const DELIVERY_NOTE_TEMPLATE: &str = "templates/delivery_note.toml";
+pub const INVOICE_TEMPLATE: &str = "templates/invoice.toml";
+pub const QUOTE_TEMPLATE: &str = "templates/quote.toml";
+const DEFAULT_DELIVERY_NOTE: &str = include_str!("../../templates/delivery_note.toml");
+const DEFAULT_INVOICE: &str = include_str!("../../templates/invoice.toml");
+const DEFAULT_QUOTE: &str = include_str!("../../templates/quote.toml");
+
+/// The layout of a report, read from a TOML file. Positions and sizes are in
+/// millimeters from the lower left corner of the page, font sizes in points.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    pub page: Page,
+    pub fonts: Fonts,
+    /// Blocks drawn on the first page.
+    #[serde(default)]
+    pub header: Vec<Block>,
+    /// Blocks drawn on the pages following the first one.
+    #[serde(default)]
+    pub continuation: Vec<Block>,
+    /// Blocks drawn on every page.
+    #[serde(default)]
+    pub footer: Vec<Block>,
+    pub table: Option<Table>,
+    /// Blocks drawn after the table on the last page, such as totals. Their
+    /// `y` is the distance below the end of the table.
+    #[serde(default)]
+    pub summary: Vec<Block>,
+    /// Boxes drawn after the summary, on the last page.
+    pub signatures: Option<Signatures>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Page {
+    pub width: f32,
+    pub height: f32,
+    pub margin_left: f32,
+    pub margin_right: f32,
+    pub margin_bottom: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Fonts {
+    pub regular: String,
+    pub bold: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Block {
+    /// The logo of the report, when it has one.
+    Logo {
+        x: f32,
+        y: f32,
+    },
+    Title {
+        x: f32,
+        y: f32,
+        size: f32,
+        text: String,
+    },
+    Sender(Address),
+    Recipient(Address),
+    /// Free text. `{name}` is replaced by the value `name` of the report.
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        #[serde(default)]
+        bold: bool,
+        #[serde(default)]
+        align: Align,
+        #[serde(default = "default_line_height")]
+        line_height: f32,
+        lines: Vec<String>,
+    },
+    /// One line for each entry of the list `list` of the report, `{name}`
+    /// being replaced by the value `name` of the entry or of the report.
+    List {
+        x: f32,
+        y: f32,
+        size: f32,
+        #[serde(default)]
+        bold: bool,
+        #[serde(default)]
+        align: Align,
+        #[serde(default = "default_line_height")]
+        line_height: f32,
+        list: String,
+        line: String,
+    },
+    /// A horizontal line between the margins.
+    Line {
+        y: f32,
+    },
+    /// A barcode of `value`, where `{name}` is replaced by the value `name`
+    /// of the report. `y` is the top of the symbol and `module` the width of
+    /// its narrowest bar. A QR code is square, its `height` is not used.
+    Barcode {
+        x: f32,
+        y: f32,
+        symbology: Symbology,
+        value: String,
+        #[serde(default = "default_module")]
+        module: f32,
+        #[serde(default = "default_barcode_height")]
+        height: f32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Address {
+    pub x: f32,
+    pub y: f32,
+    pub size: f32,
+    #[serde(default = "default_line_height")]
+    pub line_height: f32,
+    /// Written before the name.
+    #[serde(default)]
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Table {
+    /// Height of the column header on the first page.
+    pub top: f32,
+    /// Height of the column header on the following pages.
+    pub continuation_top: f32,
+    pub size: f32,
+    /// Height of a row holding a single line of text.
+    pub row_height: f32,
+    /// Height added to a row for each extra line of wrapped text.
+    pub line_height: f32,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Column {
+    pub header: String,
+    /// The value of the row shown in the column.
+    pub field: String,
+    pub x: f32,
+    pub width: f32,
+    #[serde(default)]
+    pub align: Align,
+    /// Draws the values as barcodes, those that cannot be encoded being
+    /// written as text.
+    #[serde(default)]
+    pub barcode: Option<Symbology>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Signatures {
+    pub x: f32,
+    pub width: f32,
+    pub height: f32,
+    pub gap: f32,
+    pub size: f32,
+    pub labels: Vec<String>,
+}
+
+impl Signatures {
+    /// Room taken by all the boxes.
+    pub fn total_height(&self) -> f32 {
+        let count = self.labels.len() as f32;
+        return count * self.height + (count - 1.0).max(0.0) * self.gap;
+    }
+}
+
+fn default_line_height() -> f32 {
+    return 5.0;
+}
+
+fn default_module() -> f32 {
+    return 0.33;
+}
+
+fn default_barcode_height() -> f32 {
+    return 15.0;
+}
+
+impl Template {
+    /// Reads a template from TOML. `path` is only used in errors.
+    pub fn parse(content: &str, path: &Path) -> Result<Self, Error> {
+        return toml::from_str(content).map_err(|e| Error::InvalidAsset {
+            path: path.to_owned(),
+            message: e.to_string(),
+        });
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path).map_err(|source| Error::MissingAsset {
+            path: path.to_owned(),
+            source,
+        })?;
+        return Self::parse(&content, path);
+    }
+
+    /// The layout from the file at `path`, or `default` when the file does
+    /// not exist.
+    fn load_or(path: &str, default: &str) -> Result<Self, Error> {
+        let path = Path::new(path);
+        if path.exists() {
+            return Self::load(path);
+        }
+        return Self::parse(default, path);
+    }
+
+    /// The delivery note layout from `DELIVERY_NOTE_TEMPLATE`, or the layout
+    /// shipped with the application when the file does not exist.
+    pub fn delivery_note() -> Result<Self, Error> {
+        return Self::load_or(DELIVERY_NOTE_TEMPLATE, DEFAULT_DELIVERY_NOTE);
+    }
+
+    /// The invoice layout from `INVOICE_TEMPLATE`, or the layout shipped with
+    /// the application when the file does not exist.
+    pub fn invoice() -> Result<Self, Error> {
+        return Self::load_or(INVOICE_TEMPLATE, DEFAULT_INVOICE);
+    }
+
+    /// The quote layout from `QUOTE_TEMPLATE`, or the layout shipped with the
+    /// application when the file does not exist.
+    pub fn quote() -> Result<Self, Error> {
+        return Self::load_or(QUOTE_TEMPLATE, DEFAULT_QUOTE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_delivery_note() {
+        let template = Template::parse(DEFAULT_DELIVERY_NOTE, Path::new("default")).unwrap();
+        assert_eq!(template.page.width, 210.0);
+        assert_eq!(template.header.len(), 7);
+        assert_eq!(
+            template.header[1],
+            Block::Title {
+                x: 110.0,
+                y: 280.0,
+                size: 24.0,
+                text: "{t.delivery-note}".to_string(),
+            }
+        );
+        let table = template.table.unwrap();
+        assert_eq!(table.columns.len(), 3);
+        assert_eq!(table.columns[1].field, "description");
+        assert_eq!(table.columns[0].barcode, Some(Symbology::Ean13));
+        assert!(template.header.iter().any(|block| matches!(
+            block,
+            Block::Barcode {
+                symbology: Symbology::Code128,
+                ..
+            }
+        )));
+        assert_eq!(template.signatures.unwrap().total_height(), 45.0);
+    }
+
+    #[test]
+    fn test_default_invoice() {
+        let template = Template::parse(DEFAULT_INVOICE, Path::new("default")).unwrap();
+        let table = template.table.unwrap();
+        assert_eq!(table.columns.last().unwrap().field, "total");
+        assert_eq!(table.columns.last().unwrap().align, Align::Right);
+        assert!(!template.summary.is_empty());
+        assert_eq!(template.signatures, None);
+    }
+
+    #[test]
+    fn test_default_quote() {
+        let template = Template::parse(DEFAULT_QUOTE, Path::new("default")).unwrap();
+        assert_eq!(template.table.unwrap().columns.len(), 5);
+        assert_eq!(template.signatures.unwrap().labels.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        let content = DEFAULT_DELIVERY_NOTE.replace("type = \"line\"", "type = \"circle\"");
+        match Template::parse(&content, Path::new("layout.toml")) {
+            Err(Error::InvalidAsset { path, message }) => {
+                assert_eq!(path, Path::new("layout.toml"));
+                assert!(message.contains("circle"), "{}", message);
+            }
+            other => panic!("expected an invalid template, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::fs;
+
+use ttf_parser::Face;
+
+use crate::error::Error;
+
+const MM_PER_POINT: f32 = 25.4 / 72.0;
+
+/// Glyph metrics of a TTF font, used to measure and wrap text before drawing it.
+pub struct FontMetrics {
+    data: Vec<u8>,
+}
+
+impl FontMetrics {
+    /// Reads the metrics of the TTF font found at `path`.
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let data = fs::read(path).map_err(|source| Error::MissingAsset {
+            path: path.into(),
+            source,
+        })?;
+        Face::parse(&data, 0).map_err(|e| Error::InvalidAsset {
+            path: path.into(),
+            message: e.to_string(),
+        })?;
+        return Ok(Self { data });
+    }
+
+    fn face(&self) -> Face<'_> {
+        return Face::parse(&self.data, 0).expect("the font is parsed when loaded");
+    }
+
+    /// Width of `text` in millimeters, at `font_size` points.
+    pub fn text_width(&self, text: &str, font_size: f32) -> f32 {
+        return measure(&self.face(), text, font_size);
+    }
+
+    /// Splits `text` into lines no wider than `max_width` millimeters. Lines
+    /// break between words, and inside words longer than a whole line. Line
+    /// breaks already in the text are kept.
+    pub fn wrap(&self, text: &str, font_size: f32, max_width: f32) -> Vec<String> {
+        let face = self.face();
+        let mut lines = Vec::new();
+        for paragraph in text.lines() {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                let candidate = if line.is_empty() {
+                    word.to_string()
+                } else {
+                    format!("{} {}", line, word)
+                };
+                if measure(&face, &candidate, font_size) <= max_width {
+                    line = candidate;
+                    continue;
+                }
+                if !line.is_empty() {
+                    lines.push(line);
+                }
+                line = String::new();
+                for c in word.chars() {
+                    line.push(c);
+                    if line.chars().count() > 1 && measure(&face, &line, font_size) > max_width {
+                        line.pop();
+                        lines.push(line);
+                        line = c.to_string();
+                    }
+                }
+            }
+            lines.push(line);
+        }
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        return lines;
+    }
+}
+
+fn measure(face: &Face, text: &str, font_size: f32) -> f32 {
+    let units: u32 = text
+        .chars()
+        .map(|c| {
+            let glyph = face.glyph_index(c).unwrap_or_default();
+            face.glyph_hor_advance(glyph).unwrap_or_default() as u32
+        })
+        .sum();
+    return units as f32 / face.units_per_em() as f32 * font_size * MM_PER_POINT;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics() -> FontMetrics {
+        return FontMetrics::load("assets/fonts/Helvetica.ttf").unwrap();
+    }
+
+    #[test]
+    fn test_text_width() {
+        let metrics = metrics();
+        assert_eq!(metrics.text_width("", 12.0), 0.0);
+        let width = metrics.text_width("Chaise", 12.0);
+        assert!(width > 10.0 && width < 20.0, "{}", width);
+        assert!((metrics.text_width("Chaise", 24.0) - 2.0 * width).abs() < 0.001);
+        assert!(metrics.text_width("WWW", 12.0) > metrics.text_width("iii", 12.0));
+    }
+
+    #[test]
+    fn test_wrap_between_words() {
+        let metrics = metrics();
+        let text = "Table de jardin en chêne massif avec rallonges et six chaises assorties";
+        let lines = metrics.wrap(text, 12.0, 50.0);
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(metrics.text_width(line, 12.0) <= 50.0, "{}", line);
+        }
+        assert_eq!(lines.join(" "), text);
+    }
+
+    #[test]
+    fn test_wrap_long_word_and_line_breaks() {
+        let metrics = metrics();
+        assert_eq!(metrics.wrap("", 12.0, 50.0), vec![String::new()]);
+        assert_eq!(
+            metrics.wrap("Table\nChaise", 12.0, 50.0),
+            vec!["Table", "Chaise"]
+        );
+
+        let lines = metrics.wrap("ABCDEFGHIJKLMNOPQRSTUVWXYZ", 12.0, 20.0);
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(metrics.text_width(line, 12.0) <= 20.0, "{}", line);
+        }
+        assert_eq!(lines.concat(), "ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+    }
+}
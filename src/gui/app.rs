@@ -1,110 +1,487 @@
+use chrono::{Days, Local};
 use eframe::{egui, App, Frame};
 use egui::{CentralPanel, TopBottomPanel};
-use std::sync::{Arc, Mutex};
+use rust_decimal::Decimal;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
     engine::Engine,
-    generators::delivery_note::DeliveryNote,
-    models::{client::Client, item::Item, product::Product},
-    Sender,
+    error::Error,
+    generators::{
+        delivery_note::DeliveryNote, formats::Format, invoice::Invoice, output::OutputSettings,
+        quote::Quote,
+    },
+    i18n::{Catalog, Language},
+    models::{
+        client::Client,
+        counter::Numbering,
+        country::Country,
+        item::Item,
+        product::{Product, VAT_RATES},
+        sender::Sender,
+        unit::Unit,
+        validation::{error_of, field_errors, FieldError},
+    },
 };
 
+use super::table::{Action, Table};
+
+/// Délai de paiement des factures, en jours.
+const PAYMENT_DAYS: u64 = 30;
+/// Durée de validité des devis, en jours.
+const QUOTE_VALIDITY_DAYS: u64 = 30;
+
+/// Ouvre `folder` dans le gestionnaire de fichiers du système.
+fn open_folder(folder: &Path) -> std::io::Result<()> {
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    Command::new(program).arg(folder).spawn()?;
+    return Ok(());
+}
+
+/// Choix du format des documents générés.
+fn show_format(ui: &mut egui::Ui, catalog: &Catalog, format: &mut Format) {
+    ui.horizontal(|ui| {
+        ui.label(format!("{}:", catalog.get("format")));
+        egui::ComboBox::from_id_source("format")
+            .selected_text(catalog.get(format.message()))
+            .show_ui(ui, |ui| {
+                for value in Format::ALL {
+                    ui.selectable_value(format, value, catalog.get(value.message()));
+                }
+            });
+    });
+}
+
+/// Choix de la langue des documents d'un client.
+pub(super) fn show_language(ui: &mut egui::Ui, id: &str, language: &mut Language) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(language.name())
+        .show_ui(ui, |ui| {
+            for value in Language::ALL {
+                ui.selectable_value(language, value, value.name());
+            }
+        });
+}
+
+/// Choix du pays de l'adresse d'un client.
+pub(super) fn show_country(ui: &mut egui::Ui, id: &str, country: &mut Country) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(country.name())
+        .show_ui(ui, |ui| {
+            for value in Country::ALL {
+                ui.selectable_value(country, value, value.name());
+            }
+        });
+}
+
+/// Choix de l'unité dans laquelle un produit est compté.
+pub(super) fn show_unit(ui: &mut egui::Ui, catalog: &Catalog, id: &str, unit: &mut Unit) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(catalog.get(unit.message()))
+        .show_ui(ui, |ui| {
+            for value in Unit::ALL {
+                ui.selectable_value(unit, value, catalog.get(value.message()));
+            }
+        });
+}
+
+/// Choix du taux de TVA auquel un produit est facturé.
+pub(super) fn show_vat_rate(ui: &mut egui::Ui, catalog: &Catalog, id: &str, rate: &mut Decimal) {
+    let text = |rate: Decimal| format!("{} %", catalog.language.format_decimal(rate));
+    egui::ComboBox::from_id_source(id)
+        .selected_text(text(*rate))
+        .show_ui(ui, |ui| {
+            for value in VAT_RATES {
+                ui.selectable_value(rate, value, text(value));
+            }
+        });
+}
+
+/// Libellé d'un champ d'un modèle, nommé comme il est stocké, tel que `postal_code`.
+fn field_label(catalog: &Catalog, field: &str) -> String {
+    return catalog.get(&format!("field-{}", field.replace('_', "-")));
+}
+
+/// Affiche en rouge l'erreur de `field`, sous le champ qu'elle concerne.
+pub(super) fn show_field_error(
+    ui: &mut egui::Ui,
+    catalog: &Catalog,
+    errors: &[FieldError],
+    field: &str,
+) {
+    if let Some(error) = error_of(errors, field) {
+        ui.colored_label(egui::Color32::RED, error.message(catalog));
+    }
+}
+
+/// Sépare les erreurs des champs, affichées sous chacun d'eux, de l'erreur
+/// affichée sous le formulaire quand l'entité est refusée pour une autre raison.
+pub(super) fn form_errors(error: Error) -> (Vec<FieldError>, Option<String>) {
+    let errors = field_errors(&error);
+    if errors.is_empty() {
+        return (errors, Some(error.to_string()));
+    }
+    return (errors, None);
+}
+
+/// Erreur d'un formulaire sans champ pour l'afficher, où chaque champ refusé
+/// est nommé avec son libellé.
+fn error_text(catalog: &Catalog, error: Error) -> String {
+    return match form_errors(error) {
+        (_, Some(other)) => other,
+        (errors, None) => errors
+            .iter()
+            .map(|error| {
+                format!(
+                    "{} : {}",
+                    field_label(catalog, &error.field),
+                    error.message(catalog)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", "),
+    };
+}
+
+/// Produit choisi pour un document, avec son prix et sa quantité tels qu'ils
+/// sont saisis.
+#[derive(Default)]
+struct ItemInput {
+    product: Product,
+    price: String,
+    quantity: String,
+}
+
+/// Lit le prix et la quantité de chaque produit choisi, ou renvoie les
+/// erreurs de tous leurs champs.
+fn parse_items(inputs: &[ItemInput]) -> Result<Vec<Item>, Error> {
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, input) in inputs.iter().enumerate() {
+        match Item::parse(input.product.clone(), &input.price, &input.quantity) {
+            Ok(item) => items.push(item),
+            Err(item_errors) => errors.extend(item_errors.into_iter().map(|error| FieldError {
+                field: format!("items[{}].{}", idx, error.field),
+                problem: error.problem,
+            })),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(Error::Invalid {
+            kind: "item",
+            id: String::new(),
+            errors,
+        });
+    }
+    return Ok(items);
+}
+
+/// Erreurs des champs et état à afficher après une génération.
+fn report_outcome(
+    result: Result<PathBuf, Error>,
+) -> (Vec<FieldError>, Option<Result<PathBuf, String>>) {
+    return match result {
+        Ok(path) => (Vec::new(), Some(Ok(path))),
+        Err(error) => {
+            let (errors, other) = form_errors(error);
+            (errors, other.map(Err))
+        }
+    };
+}
+
+/// Affiche le fichier écrit par la dernière génération, ou son erreur. Un
+/// dossier qui ne peut pas être ouvert devient l'erreur affichée.
+fn show_report_status(
+    ui: &mut egui::Ui,
+    catalog: &Catalog,
+    status: &mut Option<Result<PathBuf, String>>,
+) {
+    match status {
+        Some(Ok(path)) => {
+            let mut error = None;
+            ui.horizontal(|ui| {
+                let path_arg = ("path", path.display().to_string());
+                ui.label(catalog.format("file-written", &[path_arg]));
+                if ui.button(catalog.get("open-folder")).clicked() {
+                    let folder = path.parent().unwrap_or(Path::new("."));
+                    if let Err(e) = open_folder(folder) {
+                        let args = [
+                            ("folder", folder.display().to_string()),
+                            ("error", e.to_string()),
+                        ];
+                        error = Some(catalog.format("open-folder-failed", &args));
+                    }
+                }
+            });
+            if let Some(error) = error {
+                *status = Some(Err(error));
+            }
+        }
+        Some(Err(error)) => {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+        None => {}
+    }
+}
+
 #[derive(PartialEq)]
 enum Tab {
     CreateProduct,
     CreateClient,
+    Clients,
+    Products,
     GenerateDeliveryNote,
+    DeliveryNotes,
+    Invoices,
+    Quotes,
+    Settings,
 }
 pub struct MyApp {
     tab: Tab,
     engine: Arc<Mutex<Engine>>,
     client: Arc<Mutex<Client>>,
     product: Arc<Mutex<Product>>,
+    /// Erreurs des champs du client refusé à la dernière création.
+    client_errors: Vec<FieldError>,
+    /// Erreur de la dernière création de client, hors erreurs des champs.
+    client_error: Option<String>,
+    /// Erreurs des champs du produit refusé à la dernière création.
+    product_errors: Vec<FieldError>,
+    /// Erreur de la dernière création de produit, hors erreurs des champs.
+    product_error: Option<String>,
+    clients: Table<Client>,
+    products: Table<Product>,
+    selected_sender: Sender,
     selected_client: Client,
-    selected_items: Vec<Item>,
+    selected_items: Vec<ItemInput>,
+    /// Erreurs du client et des produits choisis, refusés à la dernière génération.
+    report_errors: Vec<FieldError>,
+    /// Format des documents générés.
+    format: Format,
+    /// Fichier écrit par la dernière génération, ou son erreur.
+    report_status: Option<Result<PathBuf, String>>,
+    /// Réglages de sortie en cours d'édition dans les paramètres.
+    output: OutputSettings,
+    /// Numérotations en cours d'édition dans les paramètres.
+    numberings: Vec<Numbering>,
+    /// Profil émetteur en cours d'édition dans les paramètres.
+    sender: Sender,
+    settings_error: Option<String>,
+    /// Messages de l'interface, dans la langue choisie.
+    catalog: Catalog,
+    /// Erreur du dernier changement de langue.
+    language_error: Option<String>,
 }
 
 impl MyApp {
-    pub fn new(engine: Arc<Mutex<Engine>>) -> Self {
+    pub fn new(engine: Arc<Mutex<Engine>>, catalog: Catalog) -> Self {
+        let output = engine.lock().unwrap().output().clone();
+        let numberings = engine
+            .lock()
+            .unwrap()
+            .get_numberings()
+            .map(Clone::clone)
+            .to_vec();
         return Self {
             tab: Tab::GenerateDeliveryNote,
             engine,
             client: Arc::new(Mutex::new(Client::default())),
             product: Arc::new(Mutex::new(Product::default())),
+            client_errors: Vec::new(),
+            client_error: None,
+            product_errors: Vec::new(),
+            product_error: None,
+            clients: Table::default(),
+            products: Table::default(),
+            selected_sender: Sender::default(),
             selected_client: Client::default(),
             selected_items: Vec::new(),
+            report_errors: Vec::new(),
+            format: Format::default(),
+            report_status: None,
+            output,
+            numberings,
+            sender: Sender::default(),
+            settings_error: None,
+            catalog,
+            language_error: None,
         };
     }
     fn show_create_client(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Créer un Client");
+        let catalog = &self.catalog;
+        let label = |id: &str| format!("{}:", catalog.get(id));
+        ui.heading(catalog.get("create-client"));
 
         let mut client = self.client.lock().unwrap();
 
         ui.horizontal(|ui| {
-            ui.label("ID:");
+            ui.label(label("field-id"));
             ui.text_edit_singleline(&mut client.id);
         });
+        show_field_error(ui, catalog, &self.client_errors, "id");
         ui.horizontal(|ui| {
-            ui.label("Nom:");
+            ui.label(label("field-name"));
             ui.text_edit_singleline(&mut client.name);
         });
+        show_field_error(ui, catalog, &self.client_errors, "name");
         ui.horizontal(|ui| {
-            ui.label("Adresse 1:");
+            ui.label(label("field-address1"));
             ui.text_edit_singleline(&mut client.address1);
         });
+        show_field_error(ui, catalog, &self.client_errors, "address1");
         ui.horizontal(|ui| {
-            ui.label("Adresse 2:");
+            ui.label(label("field-address2"));
             ui.text_edit_singleline(&mut client.address2);
         });
+        show_field_error(ui, catalog, &self.client_errors, "address2");
         ui.horizontal(|ui| {
-            ui.label("Code Postal:");
+            ui.label(label("field-postal-code"));
             ui.text_edit_singleline(&mut client.postal_code);
         });
+        show_field_error(ui, catalog, &self.client_errors, "postal_code");
         ui.horizontal(|ui| {
-            ui.label("Ville:");
+            ui.label(label("field-city"));
             ui.text_edit_singleline(&mut client.city);
         });
+        show_field_error(ui, catalog, &self.client_errors, "city");
+        ui.horizontal(|ui| {
+            ui.label(label("field-country"));
+            show_country(ui, "client_country", &mut client.country);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-language"));
+            show_language(ui, "client_language", &mut client.language);
+        });
 
-        if ui.button("Sauvegarder Client").clicked() {
+        if ui.button(catalog.get("save-client")).clicked() {
             let mut engine = self.engine.lock().unwrap();
-            engine.add_client(client.clone());
-            *client = Client::default();
+            // Un client invalide, ou dont l'ID existe déjà, est refusé
+            match engine.create_client(client.clone()) {
+                Ok(()) => {
+                    *client = Client::default();
+                    self.client_errors.clear();
+                    self.client_error = None;
+                }
+                Err(e) => (self.client_errors, self.client_error) = form_errors(e),
+            }
+        }
+        if let Some(error) = &self.client_error {
+            ui.colored_label(egui::Color32::RED, error);
         }
     }
+    fn show_clients(&mut self, ui: &mut egui::Ui) {
+        let catalog = &self.catalog;
+        ui.heading(catalog.get("clients"));
+
+        let mut engine = self.engine.lock().unwrap();
+        let result = match self
+            .clients
+            .show(ui, catalog, "clients", engine.get_clients())
+        {
+            Some(Action::Update(id, client)) => engine.update_client(&id, client),
+            Some(Action::Remove(id)) => engine.remove_client(&id).map(|_| ()),
+            None => return,
+        };
+        self.clients.done(result);
+    }
     fn show_create_product(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Créer un Produit");
+        let catalog = &self.catalog;
+        let label = |id: &str| format!("{}:", catalog.get(id));
+        ui.heading(catalog.get("create-product"));
 
         let mut product = self.product.lock().unwrap();
 
         ui.horizontal(|ui| {
-            ui.label("ID:");
+            ui.label(label("field-id"));
             ui.text_edit_singleline(product.id_mut());
         });
+        show_field_error(ui, catalog, &self.product_errors, "id");
         ui.horizontal(|ui| {
-            ui.label("Description:");
+            ui.label(label("field-description"));
             ui.text_edit_singleline(product.description_mut());
         });
         ui.horizontal(|ui| {
-            ui.label("Reference:");
+            ui.label(label("field-reference"));
             ui.text_edit_singleline(product.reference_mut());
         });
+        ui.horizontal(|ui| {
+            ui.label(label("field-unit"));
+            show_unit(ui, catalog, "product_unit", product.unit_mut());
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-vat-rate"));
+            show_vat_rate(ui, catalog, "product_vat_rate", product.vat_rate_mut());
+        });
+        show_field_error(ui, catalog, &self.product_errors, "vat_rate");
 
-        if ui.button("Sauvegarder un produit").clicked() {
+        if ui.button(catalog.get("save-product")).clicked() {
             let mut engine = self.engine.lock().unwrap();
-            engine.add_product(product.clone());
-            *product = Product::default();
+            // Un produit invalide, ou dont l'ID existe déjà, est refusé
+            match engine.create_product(product.clone()) {
+                Ok(()) => {
+                    *product = Product::default();
+                    self.product_errors.clear();
+                    self.product_error = None;
+                }
+                Err(e) => (self.product_errors, self.product_error) = form_errors(e),
+            }
+        }
+        if let Some(error) = &self.product_error {
+            ui.colored_label(egui::Color32::RED, error);
         }
     }
+    fn show_products(&mut self, ui: &mut egui::Ui) {
+        let catalog = &self.catalog;
+        ui.heading(catalog.get("products"));
+
+        let mut engine = self.engine.lock().unwrap();
+        let result = match self
+            .products
+            .show(ui, catalog, "products", engine.get_products())
+        {
+            Some(Action::Update(id, product)) => engine.update_product(&id, product),
+            Some(Action::Remove(id)) => engine.remove_product(&id).map(|_| ()),
+            None => return,
+        };
+        self.products.done(result);
+    }
     fn show_generate_report(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Créer un Rapport");
+        let catalog = &self.catalog;
+        let label = |id: &str| format!("{}:", catalog.get(id));
+        ui.heading(catalog.get("create-report"));
 
         let mut engine = self.engine.lock().unwrap();
+        let senders = engine.get_senders().clone();
         let clients = engine.get_clients();
         let products = engine.get_products();
 
         ui.vertical(|ui| {
-            ui.label("Selectionner un client:");
-            egui::ComboBox::from_id_source(format!("client"))
+            ui.label(label("sender"));
+            egui::ComboBox::from_id_source("sender")
+                .selected_text(self.selected_sender.name.clone())
+                .show_ui(ui, |ui| {
+                    for sender in senders.iter() {
+                        ui.selectable_value(
+                            &mut self.selected_sender,
+                            sender.clone(),
+                            format!("{} ({})", sender.name, sender.id),
+                        );
+                    }
+                });
+        });
+        ui.vertical(|ui| {
+            ui.label(label("select-client"));
+            egui::ComboBox::from_id_source("client")
                 .selected_text(self.selected_client.name.clone())
                 .show_ui(ui, |ui| {
                     for client in clients.iter() {
@@ -116,53 +493,373 @@ impl MyApp {
                     }
                 });
         });
-        ui.label("Ajouter des produits:");
+        for error in &self.report_errors {
+            if let Some(field) = error.field.strip_prefix("client.") {
+                let text = format!(
+                    "{}: {}",
+                    field_label(catalog, field),
+                    error.message(catalog)
+                );
+                ui.colored_label(egui::Color32::RED, text);
+            }
+        }
+        ui.label(label("add-products"));
         for (idx, item) in self.selected_items.iter_mut().enumerate() {
             ui.horizontal(|ui| {
                 ui.vertical(|ui| {
-                    ui.label("Selectionner un produit:");
+                    ui.label(label("select-product"));
                     egui::ComboBox::from_id_source(format!("item_{}", idx))
-                        .selected_text(item.product().description())
+                        .selected_text(item.product.description())
                         .show_ui(ui, |ui| {
                             for product in products.iter() {
                                 ui.selectable_value(
-                                    item.product_mut(),
+                                    &mut item.product,
                                     product.clone(),
                                     product.description(),
                                 );
                             }
                         });
+                    let field = format!("items[{}].product", idx);
+                    show_field_error(ui, catalog, &self.report_errors, &field);
                 });
                 ui.vertical(|ui| {
-                    ui.label("Selectionner une quantité:");
-                    ui.text_edit_singleline(item.quantity_mut());
+                    ui.label(label("select-quantity"));
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut item.quantity);
+                        ui.label(catalog.get(item.product.unit().message()));
+                    });
+                    let field = format!("items[{}].quantity", idx);
+                    show_field_error(ui, catalog, &self.report_errors, &field);
+                });
+                ui.vertical(|ui| {
+                    ui.label(label("unit-price"));
+                    ui.text_edit_singleline(&mut item.price);
+                    let field = format!("items[{}].price", idx);
+                    show_field_error(ui, catalog, &self.report_errors, &field);
                 })
             });
         }
         ui.horizontal(|ui| {
             if ui.button("+").clicked() {
-                self.selected_items.push(Item::default());
+                self.selected_items.push(ItemInput::default());
+            }
+            if ui.button("-").clicked() && self.selected_items.len() > 1 {
+                self.selected_items.pop();
             }
-            if ui.button("-").clicked() {
-                if self.selected_items.len() > 1 {
-                    self.selected_items.pop();
+        });
+        show_field_error(ui, catalog, &self.report_errors, "items");
+        let today = Local::now().date_naive();
+        show_format(ui, catalog, &mut self.format);
+        let sender_missing = self.selected_sender.id.is_empty();
+        if sender_missing {
+            ui.label(catalog.get("choose-sender"));
+        }
+        // Un client ou des produits invalides sont signalés sous leurs champs
+        if ui.button(catalog.get("generate-delivery-note")).clicked() && !sender_missing {
+            let result = parse_items(&self.selected_items)
+                .and_then(|items| {
+                    engine.create_delivery_note(
+                        self.selected_sender.clone(),
+                        self.selected_client.clone(),
+                        &items,
+                        today,
+                    )
+                })
+                .and_then(|note| engine.generate_report(DeliveryNote::new(note), self.format));
+            (self.report_errors, self.report_status) = report_outcome(result);
+        }
+        if ui.button(catalog.get("generate-quote")).clicked() && !sender_missing {
+            let result = parse_items(&self.selected_items)
+                .and_then(|items| {
+                    engine.create_quote(
+                        self.selected_sender.clone(),
+                        self.selected_client.clone(),
+                        &items,
+                        today,
+                        today + Days::new(QUOTE_VALIDITY_DAYS),
+                    )
+                })
+                .and_then(|quote| engine.generate_report(Quote::new(quote), self.format));
+            (self.report_errors, self.report_status) = report_outcome(result);
+        }
+        show_report_status(ui, catalog, &mut self.report_status);
+    }
+    fn show_delivery_notes(&mut self, ui: &mut egui::Ui) {
+        let catalog = &self.catalog;
+        ui.heading(catalog.get("delivery-notes"));
+
+        let mut engine = self.engine.lock().unwrap();
+        let mut to_invoice = None;
+        show_format(ui, catalog, &mut self.format);
+        egui::Grid::new("delivery_notes")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong(catalog.get("column-number"));
+                ui.strong(catalog.get("column-date"));
+                ui.strong(catalog.get("column-client"));
+                ui.strong(catalog.get("column-products"));
+                ui.end_row();
+                for note in engine.get_delivery_notes().iter().rev() {
+                    ui.label(&note.number);
+                    ui.label(catalog.language.format_date(note.date));
+                    ui.label(&note.client.name);
+                    ui.label(note.items.len().to_string());
+                    if ui.button(catalog.get("reprint")).clicked() {
+                        let report = DeliveryNote::new(note.clone());
+                        self.report_status = Some(
+                            engine
+                                .generate_report(report, self.format)
+                                .map_err(|e| e.to_string()),
+                        );
+                    }
+                    if ui.button(catalog.get("create-invoice")).clicked() {
+                        to_invoice = Some(note.number.clone());
+                    }
+                    ui.end_row();
                 }
+            });
+        if let Some(number) = to_invoice {
+            let date = Local::now().date_naive();
+            let due_date = date + Days::new(PAYMENT_DAYS);
+            self.report_status = Some(
+                engine
+                    .create_invoice(&number, date, due_date)
+                    .and_then(|invoice| engine.generate_report(Invoice::new(invoice), self.format))
+                    .map_err(|e| e.to_string()),
+            );
+        }
+        show_report_status(ui, catalog, &mut self.report_status);
+    }
+    fn show_invoices(&mut self, ui: &mut egui::Ui) {
+        let catalog = &self.catalog;
+        ui.heading(catalog.get("invoices"));
+
+        let engine = self.engine.lock().unwrap();
+        show_format(ui, catalog, &mut self.format);
+        egui::Grid::new("invoices").striped(true).show(ui, |ui| {
+            ui.strong(catalog.get("column-number"));
+            ui.strong(catalog.get("column-date"));
+            ui.strong(catalog.get("column-client"));
+            ui.strong(catalog.get("column-delivery-note"));
+            ui.strong(catalog.get("column-total"));
+            ui.end_row();
+            for invoice in engine.get_invoices().iter().rev() {
+                ui.label(&invoice.number);
+                ui.label(catalog.language.format_date(invoice.date));
+                ui.label(&invoice.client.name);
+                ui.label(&invoice.delivery_note);
+                ui.label(
+                    catalog
+                        .language
+                        .format_amount(invoice.total_including_tax()),
+                );
+                if ui.button(catalog.get("reprint")).clicked() {
+                    let report = Invoice::new(invoice.clone());
+                    self.report_status = Some(
+                        engine
+                            .generate_report(report, self.format)
+                            .map_err(|e| e.to_string()),
+                    );
+                }
+                ui.end_row();
             }
         });
-        if ui.button("Générer un bon").clicked() {
-            let report = DeliveryNote::new(
-                Sender {
-                    name: "Alexandre".to_string(),
-                    addr1: "43 rue Courtalon".to_string(),
-                    addr2: "Appartement A102B".to_string(),
-                    postal_code: 10000,
-                    city: "Troyes".to_string(),
-                },
-                self.selected_client.clone(),
-                &self.selected_items,
-                Some("assets/consitainerLogo.png".to_string()),
+        show_report_status(ui, catalog, &mut self.report_status);
+    }
+    fn show_quotes(&mut self, ui: &mut egui::Ui) {
+        let catalog = &self.catalog;
+        ui.heading(catalog.get("quotes"));
+
+        let mut engine = self.engine.lock().unwrap();
+        let mut to_accept = None;
+        show_format(ui, catalog, &mut self.format);
+        egui::Grid::new("quotes").striped(true).show(ui, |ui| {
+            ui.strong(catalog.get("column-number"));
+            ui.strong(catalog.get("column-date"));
+            ui.strong(catalog.get("column-valid-until"));
+            ui.strong(catalog.get("column-client"));
+            ui.strong(catalog.get("column-delivery-note"));
+            ui.end_row();
+            for quote in engine.get_quotes().iter().rev() {
+                ui.label(&quote.number);
+                ui.label(catalog.language.format_date(quote.date));
+                ui.label(catalog.language.format_date(quote.valid_until));
+                ui.label(&quote.client.name);
+                ui.label(&quote.delivery_note);
+                if ui.button(catalog.get("reprint")).clicked() {
+                    let report = Quote::new(quote.clone());
+                    self.report_status = Some(
+                        engine
+                            .generate_report(report, self.format)
+                            .map_err(|e| e.to_string()),
+                    );
+                }
+                if !quote.is_accepted() && ui.button(catalog.get("accept")).clicked() {
+                    to_accept = Some(quote.number.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(number) = to_accept {
+            self.report_status = Some(
+                engine
+                    .accept_quote(&number, Local::now().date_naive())
+                    .and_then(|note| engine.generate_report(DeliveryNote::new(note), self.format))
+                    .map_err(|e| e.to_string()),
             );
-            engine.generate_report(report)
+        }
+        show_report_status(ui, catalog, &mut self.report_status);
+    }
+    fn show_settings(&mut self, ui: &mut egui::Ui) {
+        let catalog = &self.catalog;
+        let label = |id: &str| format!("{}:", catalog.get(id));
+        ui.heading(catalog.get("sender-profiles"));
+
+        let mut engine = self.engine.lock().unwrap();
+        let mut to_remove = None;
+        egui::Grid::new("senders").striped(true).show(ui, |ui| {
+            ui.strong(catalog.get("field-id"));
+            ui.strong(catalog.get("field-company"));
+            ui.strong(catalog.get("field-city"));
+            ui.end_row();
+            for sender in engine.get_senders() {
+                ui.label(&sender.id);
+                ui.label(&sender.name);
+                ui.label(&sender.city);
+                if ui.button(catalog.get("edit")).clicked() {
+                    self.sender = sender.clone();
+                }
+                if ui.button(catalog.get("remove")).clicked() {
+                    to_remove = Some(sender.id.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(id) = to_remove {
+            self.settings_error = engine.remove_sender(&id).err().map(|e| e.to_string());
+            if self.selected_sender.id == id {
+                self.selected_sender = Sender::default();
+            }
+        }
+
+        ui.separator();
+        let sender = &mut self.sender;
+        ui.horizontal(|ui| {
+            ui.label(label("field-id"));
+            ui.text_edit_singleline(&mut sender.id);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-company"));
+            ui.text_edit_singleline(&mut sender.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-address1"));
+            ui.text_edit_singleline(&mut sender.addr1);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-address2"));
+            ui.text_edit_singleline(&mut sender.addr2);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-postal-code"));
+            ui.text_edit_singleline(&mut sender.postal_code);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-city"));
+            ui.text_edit_singleline(&mut sender.city);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-siret"));
+            ui.text_edit_singleline(&mut sender.siret);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-vat-number"));
+            ui.text_edit_singleline(&mut sender.vat_number);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-phone"));
+            ui.text_edit_singleline(&mut sender.phone);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-email"));
+            ui.text_edit_singleline(&mut sender.email);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-logo"));
+            ui.text_edit_singleline(&mut sender.logo_path);
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("field-footer"));
+            ui.text_edit_multiline(&mut sender.footer);
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button(catalog.get("save-profile")).clicked() {
+                if sender.id.is_empty() {
+                    self.settings_error = Some(catalog.get("profile-needs-id"));
+                } else {
+                    self.settings_error = engine
+                        .save_sender(sender.clone())
+                        .err()
+                        .map(|error| error_text(catalog, error));
+                    if self.selected_sender.id == sender.id {
+                        self.selected_sender = sender.clone();
+                    }
+                }
+            }
+            if ui.button(catalog.get("new-profile")).clicked() {
+                *sender = Sender::default();
+            }
+        });
+
+        ui.separator();
+        ui.heading(catalog.get("output-files"));
+        let mut directory = self.output.directory.display().to_string();
+        ui.horizontal(|ui| {
+            ui.label(label("output-directory"));
+            if ui.text_edit_singleline(&mut directory).changed() {
+                self.output.directory = PathBuf::from(&directory);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(label("output-pattern"));
+            ui.text_edit_singleline(&mut self.output.pattern);
+        });
+        let names = ["{type}", "{number}", "{client}", "{date}"].join(", ");
+        ui.label(catalog.format("output-pattern-help", &[("values", names)]));
+        ui.checkbox(&mut self.output.overwrite, catalog.get("output-overwrite"));
+        if ui.button(catalog.get("apply")).clicked() {
+            self.settings_error = engine
+                .save_output(self.output.clone())
+                .err()
+                .map(|e| e.to_string());
+        }
+
+        ui.separator();
+        ui.heading(catalog.get("numbering"));
+        egui::Grid::new("numberings").show(ui, |ui| {
+            ui.strong(catalog.get("numbering-documents"));
+            ui.strong(catalog.get("field-prefix"));
+            ui.strong(catalog.get("field-width"));
+            ui.end_row();
+            for numbering in &mut self.numberings {
+                // Les documents numérotés portent le nom de leur stockage
+                ui.label(catalog.get(&numbering.id.replace('_', "-")));
+                ui.text_edit_singleline(&mut numbering.prefix);
+                ui.add(egui::DragValue::new(&mut numbering.width).clamp_range(1..=10));
+                ui.end_row();
+            }
+        });
+        if ui.button(catalog.get("apply-numbering")).clicked() {
+            let result = self
+                .numberings
+                .iter()
+                .try_for_each(|numbering| engine.set_numbering(numbering.clone()));
+            self.settings_error = result.err().map(|error| error_text(catalog, error));
+        }
+
+        if let Some(error) = &self.settings_error {
+            ui.colored_label(egui::Color32::RED, error);
         }
     }
 }
@@ -171,14 +868,38 @@ impl App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
         TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                if ui.button("Create Product").clicked() {
-                    self.tab = Tab::CreateProduct;
+                let tabs = [
+                    (Tab::CreateProduct, "tab-create-product"),
+                    (Tab::CreateClient, "tab-create-client"),
+                    (Tab::Clients, "tab-clients"),
+                    (Tab::Products, "tab-products"),
+                    (Tab::GenerateDeliveryNote, "tab-generate"),
+                    (Tab::DeliveryNotes, "tab-delivery-notes"),
+                    (Tab::Invoices, "tab-invoices"),
+                    (Tab::Quotes, "tab-quotes"),
+                    (Tab::Settings, "tab-settings"),
+                ];
+                for (tab, id) in tabs {
+                    if ui.button(self.catalog.get(id)).clicked() {
+                        self.tab = tab;
+                    }
                 }
-                if ui.button("Create Client").clicked() {
-                    self.tab = Tab::CreateClient;
+                ui.separator();
+                ui.label(format!("{}:", self.catalog.get("language")));
+                let mut language = self.catalog.language;
+                show_language(ui, "language", &mut language);
+                // Les messages sont rechargés dès que la langue change
+                if language != self.catalog.language {
+                    match Catalog::load(language) {
+                        Ok(catalog) => {
+                            self.catalog = catalog;
+                            self.language_error = None;
+                        }
+                        Err(e) => self.language_error = Some(e.to_string()),
+                    }
                 }
-                if ui.button("Generate Delivery Note").clicked() {
-                    self.tab = Tab::GenerateDeliveryNote;
+                if let Some(error) = &self.language_error {
+                    ui.colored_label(egui::Color32::RED, error);
                 }
             });
         });
@@ -186,11 +907,13 @@ impl App for MyApp {
         CentralPanel::default().show(ctx, |ui| match self.tab {
             Tab::CreateProduct => self.show_create_product(ui),
             Tab::CreateClient => self.show_create_client(ui),
+            Tab::Clients => self.show_clients(ui),
+            Tab::Products => self.show_products(ui),
             Tab::GenerateDeliveryNote => self.show_generate_report(ui),
+            Tab::DeliveryNotes => self.show_delivery_notes(ui),
+            Tab::Invoices => self.show_invoices(ui),
+            Tab::Quotes => self.show_quotes(ui),
+            Tab::Settings => self.show_settings(ui),
         });
     }
-    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        let mut engine = self.engine.lock().unwrap();
-        engine.save();
-    }
 }
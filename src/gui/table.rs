@@ -0,0 +1,279 @@
+use eframe::egui;
+
+use crate::{
+    error::Error,
+    i18n::Catalog,
+    models::{client::Client, product::Product, validation::FieldError},
+};
+
+use super::app::{
+    form_errors, show_country, show_field_error, show_language, show_unit, show_vat_rate,
+};
+
+/// Entité affichée sur une ligne d'un `Table`.
+pub trait Row: Clone {
+    /// Messages des en-têtes des colonnes.
+    const COLUMNS: &'static [&'static str];
+    /// Nom, tel qu'il est stocké, du champ de chaque colonne, auquel sont
+    /// rattachées les erreurs de validation.
+    const FIELDS: &'static [&'static str];
+    /// Message demandant de confirmer la suppression, qui reçoit l'`id` de la ligne.
+    const CONFIRM_REMOVE: &'static str;
+
+    fn row_id(&self) -> &str;
+    /// Texte de chaque colonne, dans la langue de `catalog`.
+    fn cells(&self, catalog: &Catalog) -> Vec<String>;
+    /// Affiche le champ qui modifie la colonne `column`.
+    fn edit(&mut self, ui: &mut egui::Ui, catalog: &Catalog, column: usize);
+}
+
+impl Row for Client {
+    const COLUMNS: &'static [&'static str] = &[
+        "field-id",
+        "field-name",
+        "field-address1",
+        "field-address2",
+        "field-postal-code",
+        "field-city",
+        "field-country",
+        "field-language",
+    ];
+    const FIELDS: &'static [&'static str] = &[
+        "id",
+        "name",
+        "address1",
+        "address2",
+        "postal_code",
+        "city",
+        "country",
+        "language",
+    ];
+    const CONFIRM_REMOVE: &'static str = "confirm-remove-client";
+
+    fn row_id(&self) -> &str {
+        return &self.id;
+    }
+
+    fn cells(&self, _catalog: &Catalog) -> Vec<String> {
+        return vec![
+            self.id.clone(),
+            self.name.clone(),
+            self.address1.clone(),
+            self.address2.clone(),
+            self.postal_code.clone(),
+            self.city.clone(),
+            self.country.name().to_string(),
+            self.language.name().to_string(),
+        ];
+    }
+
+    fn edit(&mut self, ui: &mut egui::Ui, _catalog: &Catalog, column: usize) {
+        let field = match column {
+            0 => &mut self.id,
+            1 => &mut self.name,
+            2 => &mut self.address1,
+            3 => &mut self.address2,
+            4 => &mut self.postal_code,
+            5 => &mut self.city,
+            6 => return show_country(ui, "edit_client_country", &mut self.country),
+            _ => return show_language(ui, "edit_client_language", &mut self.language),
+        };
+        ui.text_edit_singleline(field);
+    }
+}
+
+impl Row for Product {
+    const COLUMNS: &'static [&'static str] = &[
+        "field-id",
+        "field-description",
+        "field-reference",
+        "field-unit",
+        "field-vat-rate",
+    ];
+    const FIELDS: &'static [&'static str] = &["id", "description", "reference", "unit", "vat_rate"];
+    const CONFIRM_REMOVE: &'static str = "confirm-remove-product";
+
+    fn row_id(&self) -> &str {
+        return self.id();
+    }
+
+    fn cells(&self, catalog: &Catalog) -> Vec<String> {
+        return vec![
+            self.id().clone(),
+            self.description().clone(),
+            self.reference().clone(),
+            catalog.get(self.unit().message()),
+            format!("{} %", catalog.language.format_decimal(self.vat_rate())),
+        ];
+    }
+
+    fn edit(&mut self, ui: &mut egui::Ui, catalog: &Catalog, column: usize) {
+        let field = match column {
+            0 => self.id_mut(),
+            1 => self.description_mut(),
+            2 => self.reference_mut(),
+            3 => return show_unit(ui, catalog, "edit_product_unit", self.unit_mut()),
+            _ => return show_vat_rate(ui, catalog, "edit_product_vat_rate", self.vat_rate_mut()),
+        };
+        ui.text_edit_singleline(field);
+    }
+}
+
+/// Ce qui a été demandé dans un `Table`, à faire par le moteur.
+pub enum Action<T> {
+    /// Remplacer la ligne dont l'ID est donné.
+    Update(String, T),
+    Remove(String),
+}
+
+/// Tableau d'entités, trié selon n'importe quelle colonne, filtré, modifiable
+/// sur place, dont les lignes sont supprimées après confirmation.
+pub struct Table<T> {
+    filter: String,
+    sort_column: usize,
+    ascending: bool,
+    /// ID d'origine de la ligne modifiée, et sa copie modifiée.
+    editing: Option<(String, T)>,
+    /// Ligne dont la suppression attend d'être confirmée.
+    to_remove: Option<String>,
+    /// Erreurs des champs de la ligne modifiée, refusée par le moteur.
+    field_errors: Vec<FieldError>,
+    error: Option<String>,
+}
+
+impl<T> Default for Table<T> {
+    fn default() -> Self {
+        return Table {
+            filter: String::new(),
+            sort_column: 0,
+            ascending: true,
+            editing: None,
+            to_remove: None,
+            field_errors: Vec::new(),
+            error: None,
+        };
+    }
+}
+
+impl<T: Row> Table<T> {
+    /// Lignes dont une colonne contient le filtre, triées.
+    fn visible<'a>(&self, catalog: &Catalog, rows: &'a [T]) -> Vec<&'a T> {
+        let filter = self.filter.to_lowercase();
+        let mut visible = rows
+            .iter()
+            .filter(|row| {
+                return row
+                    .cells(catalog)
+                    .iter()
+                    .any(|cell| cell.to_lowercase().contains(&filter));
+            })
+            .collect::<Vec<&T>>();
+        visible.sort_by_cached_key(|row| row.cells(catalog)[self.sort_column].to_lowercase());
+        if !self.ascending {
+            visible.reverse();
+        }
+        return visible;
+    }
+
+    /// Affiche le tableau des `rows`, et renvoie ce qui a été demandé.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        catalog: &Catalog,
+        id: &str,
+        rows: &[T],
+    ) -> Option<Action<T>> {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            ui.label(format!("{}:", catalog.get("filter")));
+            ui.text_edit_singleline(&mut self.filter);
+        });
+        let visible = self.visible(catalog, rows);
+        egui::Grid::new(id).striped(true).show(ui, |ui| {
+            for (column, message) in T::COLUMNS.iter().enumerate() {
+                let mut header = catalog.get(message);
+                if column == self.sort_column {
+                    header.push_str(if self.ascending { " ▲" } else { " ▼" });
+                }
+                // Un clic sur la colonne triée inverse l'ordre
+                if ui.button(header).clicked() {
+                    if column == self.sort_column {
+                        self.ascending = !self.ascending;
+                    } else {
+                        self.sort_column = column;
+                        self.ascending = true;
+                    }
+                }
+            }
+            ui.end_row();
+            for row in visible {
+                match &mut self.editing {
+                    Some((id, edited)) if id == row.row_id() => {
+                        for (column, field) in T::FIELDS.iter().enumerate() {
+                            ui.vertical(|ui| {
+                                edited.edit(ui, catalog, column);
+                                show_field_error(ui, catalog, &self.field_errors, field);
+                            });
+                        }
+                        if ui.button(catalog.get("save")).clicked() {
+                            action = Some(Action::Update(id.clone(), edited.clone()));
+                        }
+                        if ui.button(catalog.get("cancel")).clicked() {
+                            self.editing = None;
+                            self.field_errors.clear();
+                            self.error = None;
+                        }
+                    }
+                    _ => {
+                        for cell in row.cells(catalog) {
+                            ui.label(cell);
+                        }
+                        if ui.button(catalog.get("edit")).clicked() {
+                            self.editing = Some((row.row_id().to_string(), row.clone()));
+                            self.field_errors.clear();
+                            self.error = None;
+                        }
+                        if ui.button(catalog.get("remove")).clicked() {
+                            self.to_remove = Some(row.row_id().to_string());
+                        }
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(id) = self.to_remove.clone() {
+            egui::Window::new(catalog.get("confirm-remove"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ui.ctx(), |ui| {
+                    ui.label(catalog.format(T::CONFIRM_REMOVE, &[("id", id.clone())]));
+                    ui.horizontal(|ui| {
+                        if ui.button(catalog.get("remove")).clicked() {
+                            action = Some(Action::Remove(id.clone()));
+                            self.to_remove = None;
+                        }
+                        if ui.button(catalog.get("cancel")).clicked() {
+                            self.to_remove = None;
+                        }
+                    });
+                });
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+        return action;
+    }
+
+    /// Termine la modification une fois l'action faite par le moteur, ou affiche
+    /// son erreur, sous les champs qu'elle concerne s'il y a lieu.
+    pub fn done(&mut self, result: Result<(), Error>) {
+        match result {
+            Ok(()) => {
+                self.editing = None;
+                self.field_errors.clear();
+                self.error = None;
+            }
+            Err(error) => (self.field_errors, self.error) = form_errors(error),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use fluent_syntax::ast::{Entry, Expression, InlineExpression, Pattern, PatternElement};
+
+use super::Language;
+use crate::error::Error;
+
+/// Where the catalogs are read from, so they can be changed without
+/// recompiling.
+pub const CATALOG_DIRECTORY: &str = "locales";
+const DEFAULT_FRENCH: &str = include_str!("../../locales/fr.ftl");
+const DEFAULT_ENGLISH: &str = include_str!("../../locales/en.ftl");
+const DEFAULT_GERMAN: &str = include_str!("../../locales/de.ftl");
+
+/// The catalog shipped with the application.
+fn default_source(language: Language) -> &'static str {
+    return match language {
+        Language::French => DEFAULT_FRENCH,
+        Language::English => DEFAULT_ENGLISH,
+        Language::German => DEFAULT_GERMAN,
+    };
+}
+
+/// The variables of `pattern` outside of selectors, such as `number` in
+/// `N° { $number }`.
+fn variables<'a>(pattern: &Pattern<&'a str>) -> Vec<&'a str> {
+    return pattern
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            PatternElement::Placeable {
+                expression: Expression::Inline(InlineExpression::VariableReference { id }),
+            } => Some(id.name),
+            _ => None,
+        })
+        .collect();
+}
+
+/// The messages of a language, in Fluent syntax, falling back on French for
+/// the messages it lacks.
+pub struct Catalog {
+    pub language: Language,
+    /// The catalog of the language first, then the French one.
+    bundles: Vec<FluentBundle<FluentResource>>,
+    /// Identifiers of the messages of all the bundles.
+    ids: Vec<String>,
+}
+
+impl Catalog {
+    /// Reads a catalog in Fluent syntax, and returns it with the identifiers
+    /// of its messages. `path` is only used in errors.
+    fn parse(
+        language: Language,
+        source: &str,
+        path: &Path,
+    ) -> Result<(FluentBundle<FluentResource>, Vec<String>), Error> {
+        let invalid = |messages: Vec<String>| Error::InvalidAsset {
+            path: path.to_owned(),
+            message: messages.join(", "),
+        };
+        let resource = FluentResource::try_new(source.to_string())
+            .map_err(|(_, errors)| invalid(errors.iter().map(|e| e.to_string()).collect()))?;
+        let ids = resource
+            .entries()
+            .filter_map(|entry| match entry {
+                Entry::Message(message) => Some(message.id.name.to_string()),
+                _ => None,
+            })
+            .collect();
+        let mut bundle = FluentBundle::new(vec![language.code().parse().unwrap()]);
+        // Les marques d'isolation Unicode n'existent pas dans les polices des PDF
+        bundle.set_use_isolating(false);
+        bundle
+            .add_resource(resource)
+            .map_err(|errors| invalid(errors.iter().map(|e| e.to_string()).collect()))?;
+        return Ok((bundle, ids));
+    }
+
+    /// The catalog of `language` from `CATALOG_DIRECTORY`, or the catalog
+    /// shipped with the application when the file does not exist.
+    fn load_bundle(
+        language: Language,
+    ) -> Result<(FluentBundle<FluentResource>, Vec<String>), Error> {
+        let path = Path::new(CATALOG_DIRECTORY).join(format!("{}.ftl", language.code()));
+        if !path.exists() {
+            return Self::parse(language, default_source(language), &path);
+        }
+        let source = fs::read_to_string(&path).map_err(|source| Error::MissingAsset {
+            path: path.clone(),
+            source,
+        })?;
+        return Self::parse(language, &source, &path);
+    }
+
+    pub fn load(language: Language) -> Result<Self, Error> {
+        let mut languages = vec![language];
+        if language != Language::default() {
+            languages.push(Language::default());
+        }
+        let mut bundles = Vec::new();
+        let mut ids = Vec::new();
+        for language in languages {
+            let (bundle, bundle_ids) = Self::load_bundle(language)?;
+            bundles.push(bundle);
+            for id in bundle_ids {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        return Ok(Catalog {
+            language,
+            bundles,
+            ids,
+        });
+    }
+
+    /// The message `id` of the first bundle that has it.
+    fn pattern(&self, id: &str) -> Option<(&FluentBundle<FluentResource>, &Pattern<&str>)> {
+        return self.bundles.iter().find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            return Some((bundle, pattern));
+        });
+    }
+
+    /// The message `id` with the values of its variables, or `id` itself when
+    /// no catalog has it.
+    pub fn format(&self, id: &str, args: &[(&str, String)]) -> String {
+        let Some((bundle, pattern)) = self.pattern(id) else {
+            return id.to_string();
+        };
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        let mut errors = Vec::new();
+        return bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors)
+            .to_string();
+    }
+
+    pub fn get(&self, id: &str) -> String {
+        return self.format(id, &[]);
+    }
+
+    /// Every message as `t.id`, its variables being written `{name}` so that
+    /// the templates fill them like their own values.
+    pub fn templates(&self) -> HashMap<String, String> {
+        let mut templates = HashMap::new();
+        for id in &self.ids {
+            let Some((_, pattern)) = self.pattern(id) else {
+                continue;
+            };
+            let placeholders = variables(pattern)
+                .into_iter()
+                .map(|name| (name, format!("{{{}}}", name)))
+                .collect::<Vec<(&str, String)>>();
+            templates.insert(format!("t.{}", id), self.format(id, &placeholders));
+        }
+        return templates;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs() {
+        // Every language has every message of the French catalog
+        let french = Catalog::load(Language::French).unwrap();
+        for language in Language::ALL {
+            let (_, ids) =
+                Catalog::parse(language, default_source(language), Path::new("default")).unwrap();
+            for id in &french.ids {
+                assert!(ids.contains(id), "{} has no message {}", language, id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        let catalog = Catalog::load(Language::German).unwrap();
+        assert_eq!(catalog.get("delivery-note"), "Lieferschein");
+        assert_eq!(
+            catalog.format("number", &[("number", "BL-2026-00001".to_string())]),
+            "Nr. BL-2026-00001"
+        );
+        assert_eq!(catalog.get("unknown-message"), "unknown-message");
+        let templates = catalog.templates();
+        assert_eq!(templates["t.page"], "Seite {page} / {pages}");
+    }
+
+    #[test]
+    fn test_fallback() {
+        let (bundle, ids) = Catalog::parse(
+            Language::English,
+            "delivery-note = Delivery Note",
+            Path::new("en.ftl"),
+        )
+        .unwrap();
+        let (french, french_ids) =
+            Catalog::parse(Language::French, DEFAULT_FRENCH, Path::new("fr.ftl")).unwrap();
+        let catalog = Catalog {
+            language: Language::English,
+            bundles: vec![bundle, french],
+            ids: ids.into_iter().chain(french_ids).collect(),
+        };
+        assert_eq!(catalog.get("delivery-note"), "Delivery Note");
+        assert_eq!(catalog.get("invoice"), "Facture");
+    }
+
+    #[test]
+    fn test_invalid_catalog() {
+        match Catalog::parse(Language::French, "= sans identifiant", Path::new("fr.ftl")) {
+            Err(Error::InvalidAsset { path, .. }) => assert_eq!(path, Path::new("fr.ftl")),
+            other => panic!("expected an invalid catalog, got {:?}", other.err()),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Languages of the documents and of the interface, with their message
+//! catalogs and their way of writing dates and numbers.
+
+use std::{env, fmt, str::FromStr};
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::models::amount::round_amount;
+
+pub mod catalog;
+
+pub use catalog::Catalog;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    #[default]
+    French,
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::French, Language::English, Language::German];
+
+    /// The ISO 639-1 code of the language, which names its catalog.
+    pub fn code(self) -> &'static str {
+        return match self {
+            Language::French => "fr",
+            Language::English => "en",
+            Language::German => "de",
+        };
+    }
+
+    /// The name of the language in that language, for the language choices.
+    pub fn name(self) -> &'static str {
+        return match self {
+            Language::French => "Français",
+            Language::English => "English",
+            Language::German => "Deutsch",
+        };
+    }
+
+    /// The language of the system, from `LC_ALL`, `LC_MESSAGES` or `LANG`
+    /// such as `de_DE.UTF-8`, French when none is supported.
+    pub fn from_env() -> Self {
+        return ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find_map(|value| value.get(..2)?.parse().ok())
+            .unwrap_or_default();
+    }
+
+    fn decimal_separator(self) -> char {
+        return match self {
+            Language::French | Language::German => ',',
+            Language::English => '.',
+        };
+    }
+
+    fn date_format(self) -> &'static str {
+        return match self {
+            Language::French => "%d/%m/%Y",
+            Language::English => "%d %B %Y",
+            Language::German => "%d.%m.%Y",
+        };
+    }
+
+    pub fn format_date(self, date: NaiveDate) -> String {
+        return date.format(self.date_format()).to_string();
+    }
+
+    /// Writes an amount with two decimals, e.g. `1234,50` in French.
+    pub fn format_amount(self, amount: Decimal) -> String {
+        let amount = format!("{:.2}", round_amount(amount));
+        return amount.replace('.', &self.decimal_separator().to_string());
+    }
+
+    /// Writes a quantity or a rate without trailing zeros, e.g. `5,5` in
+    /// French.
+    pub fn format_decimal(self, value: Decimal) -> String {
+        let value = value.normalize().to_string();
+        return value.replace('.', &self.decimal_separator().to_string());
+    }
+}
+
+/// Written as its code, which is how it is stored.
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.code());
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        return Language::ALL
+            .into_iter()
+            .find(|language| language.code().eq_ignore_ascii_case(code))
+            .ok_or_else(|| format!("unsupported language \"{}\"", code));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::dec;
+
+    #[test]
+    fn test_codes() {
+        for language in Language::ALL {
+            assert_eq!(language.to_string().parse::<Language>(), Ok(language));
+        }
+        assert_eq!("DE".parse::<Language>(), Ok(Language::German));
+        assert!("it".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 20).unwrap();
+        assert_eq!(Language::French.format_date(date), "20/03/2026");
+        assert_eq!(Language::English.format_date(date), "20 March 2026");
+        assert_eq!(Language::German.format_date(date), "20.03.2026");
+
+        assert_eq!(Language::French.format_amount(dec!(1234.5)), "1234,50");
+        assert_eq!(Language::English.format_amount(dec!(0.125)), "0.13");
+        assert_eq!(Language::German.format_amount(dec!(-3)), "-3,00");
+        assert_eq!(Language::French.format_decimal(dec!(5.50)), "5,5");
+        assert_eq!(Language::English.format_decimal(dec!(5.50)), "5.5");
+        assert_eq!(Language::German.format_decimal(dec!(20.00)), "20");
+    }
+}
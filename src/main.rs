@@ -1,35 +1,118 @@
-use std::sync::{Arc, Mutex};
+#![allow(clippy::needless_return)]
 
+use std::{
+    io,
+    path::Path,
+    process::ExitCode,
+    sync::{Arc, Mutex},
+};
+
+use clap::Parser;
+use cli::{Cli, Mode};
 use engine::Engine;
 use gui::app::MyApp;
-use models::{client::Client, product::Product};
-use storage::{Storage, StorageType};
+use i18n::{Catalog, Language};
+use server::ApiServer;
+use storage::StorageType;
 
+mod cli;
 mod engine;
+mod error;
 mod generators;
 mod gui;
+mod i18n;
 mod models;
+mod server;
 mod storage;
-// Définir une structure pour l'émetteur
-pub struct Sender {
-    pub name: String,
-    pub addr1: String,
-    pub addr2: String,
-    pub postal_code: u32,
-    pub city: String,
-}
 
-fn main() {
-    let native_options = eframe::NativeOptions::default();
-    let storage_type = StorageType::FileStorage("db");
-    let engine = match Engine::new(storage_type) {
+/// The database everything is stored in.
+const DATABASE: &str = "db/db.sqlite";
+
+fn main() -> ExitCode {
+    // clap exits with the code EXIT_USAGE when the arguments are invalid
+    let cli = Cli::parse();
+    let storage_type = StorageType::Sqlite(DATABASE);
+    let mode = match cli.mode {
+        Some(Mode::DryRunMigrations) => return check_migrations(&storage_type),
+        mode => mode,
+    };
+    // Import the CSV files of the previous file storage the first time the database is created
+    if !Path::new(DATABASE).exists() {
+        if let Err(e) = Engine::migrate(&StorageType::FileStorage("db"), DATABASE) {
+            eprintln!("Migration to SQLite failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    let mut engine = match Engine::new(storage_type) {
         Ok(e) => e,
         Err(e) => {
-            println!("Engine failed to load: {}", e);
-            return;
+            eprintln!("Engine failed to load: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match mode {
+        Some(Mode::Command(command)) => {
+            return match cli::run(&mut engine, command, &mut io::stdout().lock()) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    e.exit_code()
+                }
+            };
+        }
+        Some(Mode::Serve(args)) => {
+            if args.token.is_empty() {
+                eprintln!("The token must not be empty");
+                return ExitCode::from(cli::EXIT_USAGE);
+            }
+            let engine = Arc::new(Mutex::new(engine));
+            return match ApiServer::bind(&args.address, engine, args.token) {
+                Ok(server) => {
+                    if let Some(address) = server.address() {
+                        println!("Listening on http://{}", address);
+                    }
+                    server.run();
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Some(Mode::DryRunMigrations) | None => {}
+    }
+    let catalog = match Catalog::load(Language::from_env()) {
+        Ok(catalog) => catalog,
+        Err(e) => {
+            eprintln!("Messages failed to load: {}", e);
+            return ExitCode::FAILURE;
         }
     };
-    let app = MyApp::new(Arc::new(Mutex::new(engine)));
+    let native_options = eframe::NativeOptions::default();
+    let app = MyApp::new(Arc::new(Mutex::new(engine)), catalog);
 
-    eframe::run_native("My App", native_options, Box::new(|cc| Box::new(app)));
+    if let Err(e) = eframe::run_native("My App", native_options, Box::new(|_cc| Box::new(app))) {
+        eprintln!("Application failed: {}", e);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+/// Prints the migrations the database needs, without running them.
+fn check_migrations(storage_type: &StorageType) -> ExitCode {
+    // Opening it would create it, and the CSV files would then never be imported
+    if !Path::new(DATABASE).exists() {
+        println!("The database does not exist yet");
+        return ExitCode::SUCCESS;
+    }
+    match Engine::check_migrations(storage_type) {
+        Ok(reports) if reports.is_empty() => println!("Nothing to migrate"),
+        Ok(reports) => reports.iter().for_each(|report| print!("{}", report)),
+        Err(e) => {
+            eprintln!("Migration check failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    return ExitCode::SUCCESS;
 }
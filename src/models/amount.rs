@@ -0,0 +1,51 @@
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Rounds an amount to the cent, halves away from zero.
+pub fn round_amount(amount: Decimal) -> Decimal {
+    amount.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+}
+
+/// Parses a price or a quantity typed by the user, with a dot or a comma
+/// as decimal separator.
+pub fn parse_decimal(value: &str) -> Option<Decimal> {
+    Decimal::from_str(&value.trim().replace(',', ".")).ok()
+}
+
+/// Splits a quantity typed by the user into its number and its unit symbol, e.g.
+/// `12,5 kg` into `12,5` and `kg`. The unit is empty when there is none.
+pub fn split_quantity(value: &str) -> (&str, &str) {
+    let start = value.find(char::is_alphabetic).unwrap_or(value.len());
+    let (number, unit) = value.split_at(start);
+    (number.trim(), unit.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::dec;
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse_decimal("12,50"), Some(dec!(12.50)));
+        assert_eq!(parse_decimal(" 3.2 "), Some(dec!(3.2)));
+        assert_eq!(parse_decimal(""), None);
+        assert_eq!(parse_decimal("douze"), None);
+    }
+
+    #[test]
+    fn test_split_quantity() {
+        assert_eq!(split_quantity("12,5 kg"), ("12,5", "kg"));
+        assert_eq!(split_quantity("3m²"), ("3", "m²"));
+        assert_eq!(split_quantity(" 4 "), ("4", ""));
+        assert_eq!(split_quantity("abc"), ("", "abc"));
+    }
+
+    #[test]
+    fn test_round_amount() {
+        assert_eq!(round_amount(dec!(2.065)), dec!(2.07));
+        assert_eq!(round_amount(dec!(-2.065)), dec!(-2.07));
+        assert_eq!(round_amount(dec!(2.0649)), dec!(2.06));
+    }
+}
@@ -0,0 +1,83 @@
+use savable_derive::Savable;
+
+/// The last number given for a numbering sequence, such as `BL-2026`.
+#[derive(Debug, Default, Clone, PartialEq, Savable)]
+#[savable(name = "counter")]
+pub struct Counter {
+    pub id: String,
+    pub value: u32,
+}
+
+/// How documents are numbered: `{prefix}-{year}-{counter}`, the counter being
+/// padded to `width` digits and restarting every year. Saved once changed in
+/// the settings.
+#[derive(Debug, Clone, PartialEq, Savable)]
+#[savable(name = "numbering")]
+pub struct Numbering {
+    /// The store of the documents numbered, such as `delivery_note`.
+    pub id: String,
+    pub prefix: String,
+    pub width: usize,
+}
+
+/// The numbering of delivery notes, `BL-2026-00001`.
+impl Default for Numbering {
+    fn default() -> Self {
+        Self {
+            id: "delivery_note".to_string(),
+            prefix: "BL".to_string(),
+            width: 5,
+        }
+    }
+}
+
+impl Numbering {
+    /// The numbering of quotes, `DE-2026-00001`.
+    pub fn quote() -> Self {
+        Self {
+            id: "quote".to_string(),
+            prefix: "DE".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// The numbering of invoices, `FA-2026-00001`.
+    pub fn invoice() -> Self {
+        Self {
+            id: "invoice".to_string(),
+            prefix: "FA".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Id of the counter used for the documents of `year`.
+    pub fn counter_id(&self, year: i32) -> String {
+        format!("{}-{}", self.prefix, year)
+    }
+
+    pub fn format(&self, year: i32, value: u32) -> String {
+        format!(
+            "{}-{:0width$}",
+            self.counter_id(year),
+            value,
+            width = self.width
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_number() {
+        let numbering = Numbering::default();
+        assert_eq!(numbering.format(2026, 42), "BL-2026-00042");
+        let numbering = Numbering {
+            prefix: "FA".to_string(),
+            width: 3,
+            ..Numbering::invoice()
+        };
+        assert_eq!(numbering.format(2027, 1234), "FA-2027-1234");
+    }
+}
@@ -0,0 +1,166 @@
+use std::{fmt, str::FromStr};
+
+/// Country of a postal address, which decides the format of its postal code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Country {
+    #[default]
+    France,
+    Germany,
+    UnitedKingdom,
+    Belgium,
+    Switzerland,
+    Austria,
+    Luxembourg,
+    Netherlands,
+    Italy,
+    Spain,
+}
+
+impl Country {
+    pub const ALL: [Country; 10] = [
+        Country::France,
+        Country::Germany,
+        Country::UnitedKingdom,
+        Country::Belgium,
+        Country::Switzerland,
+        Country::Austria,
+        Country::Luxembourg,
+        Country::Netherlands,
+        Country::Italy,
+        Country::Spain,
+    ];
+
+    /// The ISO 3166-1 alpha-2 code of the country.
+    pub fn code(self) -> &'static str {
+        return match self {
+            Country::France => "FR",
+            Country::Germany => "DE",
+            Country::UnitedKingdom => "GB",
+            Country::Belgium => "BE",
+            Country::Switzerland => "CH",
+            Country::Austria => "AT",
+            Country::Luxembourg => "LU",
+            Country::Netherlands => "NL",
+            Country::Italy => "IT",
+            Country::Spain => "ES",
+        };
+    }
+
+    /// The name of the country in its own language, for the country choices.
+    pub fn name(self) -> &'static str {
+        return match self {
+            Country::France => "France",
+            Country::Germany => "Deutschland",
+            Country::UnitedKingdom => "United Kingdom",
+            Country::Belgium => "Belgique",
+            Country::Switzerland => "Schweiz",
+            Country::Austria => "Österreich",
+            Country::Luxembourg => "Luxembourg",
+            Country::Netherlands => "Nederland",
+            Country::Italy => "Italia",
+            Country::Spain => "España",
+        };
+    }
+
+    /// Whether `postal_code` is written the way the post of the country
+    /// expects, e.g. `75001` in France or `SW1A 1AA` in the United Kingdom.
+    pub fn is_valid_postal_code(self, postal_code: &str) -> bool {
+        let code = postal_code.trim();
+        if !code.is_ascii() {
+            return false;
+        }
+        let digits = |count| code.len() == count && code.chars().all(|c| c.is_ascii_digit());
+        return match self {
+            Country::France | Country::Germany | Country::Italy | Country::Spain => digits(5),
+            Country::Belgium | Country::Switzerland | Country::Austria | Country::Luxembourg => {
+                digits(4)
+            }
+            Country::Netherlands => {
+                let (number, letters) = code.split_at(code.len().min(4));
+                let letters = letters.trim_start();
+                number.len() == 4
+                    && number.chars().all(|c| c.is_ascii_digit())
+                    && !number.starts_with('0')
+                    && letters.len() == 2
+                    && letters.chars().all(|c| c.is_ascii_alphabetic())
+            }
+            Country::UnitedKingdom => is_valid_uk_postcode(code),
+        };
+    }
+}
+
+/// A British postcode is an outward code of an area (one or two letters)
+/// and a district (a digit, then maybe a digit or a letter), then an inward
+/// code of a digit and two letters, e.g. `EC1A 1BB` or `M1 1AE`.
+fn is_valid_uk_postcode(code: &str) -> bool {
+    let code = code.to_ascii_uppercase().replace(' ', "");
+    if code.len() < 5 || code.len() > 7 || !code.is_ascii() {
+        return false;
+    }
+    let (outward, inward) = code.split_at(code.len() - 3);
+    let inward = inward.as_bytes();
+    if !inward[0].is_ascii_digit() || !inward[1..].iter().all(u8::is_ascii_alphabetic) {
+        return false;
+    }
+    let area = outward
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .count();
+    let district = &outward.as_bytes()[area..];
+    return (1..=2).contains(&area)
+        && !district.is_empty()
+        && district[0].is_ascii_digit()
+        && district[1..].len() <= 1
+        && district[1..].iter().all(u8::is_ascii_alphanumeric);
+}
+
+/// Written as its code, which is how it is stored.
+impl fmt::Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.code());
+    }
+}
+
+impl FromStr for Country {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        return Country::ALL
+            .into_iter()
+            .find(|country| country.code().eq_ignore_ascii_case(code))
+            .ok_or_else(|| format!("unsupported country \"{}\"", code));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes() {
+        for country in Country::ALL {
+            assert_eq!(country.to_string().parse::<Country>(), Ok(country));
+        }
+        assert_eq!("be".parse::<Country>(), Ok(Country::Belgium));
+        assert!("US".parse::<Country>().is_err());
+    }
+
+    #[test]
+    fn test_postal_codes() {
+        assert!(Country::France.is_valid_postal_code("75001"));
+        assert!(!Country::France.is_valid_postal_code("7500"));
+        assert!(!Country::France.is_valid_postal_code("75OO1"));
+        assert!(Country::Belgium.is_valid_postal_code("1000"));
+        assert!(!Country::Belgium.is_valid_postal_code("10000"));
+        assert!(Country::Netherlands.is_valid_postal_code("1012 AB"));
+        assert!(Country::Netherlands.is_valid_postal_code("1012AB"));
+        assert!(!Country::Netherlands.is_valid_postal_code("0123 AB"));
+        assert!(!Country::Netherlands.is_valid_postal_code("1éé"));
+        assert!(!Country::Netherlands.is_valid_postal_code("101é AB"));
+        assert!(Country::UnitedKingdom.is_valid_postal_code("SW1A 1AA"));
+        assert!(Country::UnitedKingdom.is_valid_postal_code("m1 1ae"));
+        assert!(Country::UnitedKingdom.is_valid_postal_code("EC1A1BB"));
+        assert!(!Country::UnitedKingdom.is_valid_postal_code("12345"));
+        assert!(!Country::UnitedKingdom.is_valid_postal_code("SW1A 1A"));
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::NaiveDate;
+
+use crate::storage::{Savable, StorageError};
+
+use super::{
+    client::Client,
+    fields::{
+        client_from_fields, client_to_fields, date_field, items_from_field, items_to_field,
+        sender_from_fields, sender_to_fields, take, take_date, take_or_default,
+    },
+    item::Item,
+    sender::Sender,
+};
+
+/// A generated delivery note. The sender, the client and the items are copied
+/// when the note is created, so later changes do not alter past notes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryNote {
+    pub number: String,
+    pub date: NaiveDate,
+    pub sender: Sender,
+    pub client: Client,
+    pub items: Vec<Item>,
+    /// Number of the quote the note was created from, if any.
+    pub quote: String,
+}
+
+impl Savable for DeliveryNote {
+    fn savable_name() -> String {
+        "delivery_note".to_string()
+    }
+
+    fn schema_version() -> u32 {
+        4
+    }
+
+    fn id_column() -> String {
+        "number".to_string()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        let mut fields = vec![
+            ("number".to_string(), self.number.clone()),
+            date_field("date", &self.date),
+        ];
+        fields.extend(sender_to_fields(&self.sender));
+        fields.extend(client_to_fields(&self.client));
+        fields.push(("items".to_string(), items_to_field(&self.items)));
+        fields.push(("quote".to_string(), self.quote.clone()));
+        fields
+    }
+
+    fn from_fields(mut fields: Vec<(String, String)>) -> Result<Self, StorageError> {
+        Ok(DeliveryNote {
+            number: take(&mut fields, "number")?,
+            date: take_date(&mut fields, "date")?,
+            sender: sender_from_fields(&mut fields)?,
+            client: client_from_fields(&mut fields)?,
+            items: items_from_field(&take(&mut fields, "items")?)?,
+            // Notes saved before quotes existed have no quote
+            quote: take_or_default(&mut fields, "quote"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        fields::{add_item_units, add_item_vat_rates},
+        product::Product,
+        unit::Unit,
+    };
+    use rust_decimal::{dec, Decimal};
+
+    #[test]
+    fn test_fields_round_trip() {
+        let mut sand = Product::new("2", "Sable\nde Loire", "SA-01");
+        *sand.unit_mut() = Unit::Kilogram;
+        let note = DeliveryNote {
+            number: "BL-2026-00042".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 3, 14).unwrap(),
+            sender: Sender {
+                name: "Alexandre".to_string(),
+                addr1: "43 rue Courtalon".to_string(),
+                addr2: String::new(),
+                postal_code: "10000".to_string(),
+                city: "Troyes".to_string(),
+                siret: "123 456 789 00012".to_string(),
+                footer: "Merci de votre confiance".to_string(),
+                ..Default::default()
+            },
+            client: Client {
+                id: "C1".to_string(),
+                name: "Dupont, Fils & Cie".to_string(),
+                ..Default::default()
+            },
+            items: vec![
+                Item::new(
+                    Product::new("1", "Chaise, \"pliante\"", "CH-01"),
+                    dec!(12.50),
+                    dec!(4),
+                ),
+                Item::new(sand.clone(), Decimal::ZERO, dec!(2.5)),
+            ],
+            quote: "DE-2026-00003".to_string(),
+        };
+
+        let fields = note.to_fields();
+        assert_eq!(fields[1], ("date".to_string(), "2026-03-14".to_string()));
+        assert_eq!(DeliveryNote::from_fields(fields).unwrap(), note);
+    }
+
+    #[test]
+    fn test_add_item_units() {
+        let fields = vec![(
+            "items".to_string(),
+            "1,Chaise,CH-01,\"12,50\",4\n2,Sable,SA-01,,500 g".to_string(),
+        )];
+        let migrated = add_item_units(fields).unwrap();
+        assert_eq!(add_item_units(migrated.clone()).unwrap(), migrated);
+        let items = items_from_field(&migrated[0].1).unwrap();
+        assert_eq!(
+            (
+                items[0].price(),
+                items[0].quantity(),
+                *items[0].product().unit()
+            ),
+            (dec!(12.50), dec!(4), Unit::Piece)
+        );
+        assert_eq!(
+            (
+                items[1].price(),
+                items[1].quantity(),
+                *items[1].product().unit()
+            ),
+            (Decimal::ZERO, dec!(0.5), Unit::Kilogram)
+        );
+
+        // Free-form quantities are kept in the description
+        let fields = vec![(
+            "items".to_string(),
+            "1,Sable,SA-01,1,4 sacs\n2,Gravier,GR-01,,quelques-uns".to_string(),
+        )];
+        let migrated = add_item_units(fields).unwrap();
+        assert_eq!(add_item_units(migrated.clone()).unwrap(), migrated);
+        let items = items_from_field(&migrated[0].1).unwrap();
+        assert_eq!(
+            (
+                items[0].product().description().as_str(),
+                items[0].quantity(),
+                *items[0].product().unit()
+            ),
+            ("Sable (4 sacs)", dec!(4), Unit::Piece)
+        );
+        assert_eq!(
+            (
+                items[1].product().description().as_str(),
+                items[1].quantity()
+            ),
+            ("Gravier (quelques-uns)", dec!(1))
+        );
+
+        let fields = vec![("items".to_string(), "1,Chaise,CH-01,cher,4".to_string())];
+        assert!(add_item_units(fields).is_err());
+    }
+
+    #[test]
+    fn test_add_item_vat_rates() {
+        let fields = vec![(
+            "items".to_string(),
+            "1,Chaise,CH-01,12.50,4,pcs\n2,Livre,LI-01,8,1,pcs,5.5".to_string(),
+        )];
+        let migrated = add_item_vat_rates(fields).unwrap();
+        assert_eq!(add_item_vat_rates(migrated.clone()).unwrap(), migrated);
+        let items = items_from_field(&migrated[0].1).unwrap();
+        assert_eq!(items[0].product().vat_rate(), dec!(20));
+        assert_eq!(items[1].product().vat_rate(), dec!(5.5));
+    }
+
+    #[test]
+    fn test_fields_without_profile() {
+        let fields = [
+            ("number", "BL-2026-00001"),
+            ("date", "2026-03-14"),
+            ("sender_name", "Alexandre"),
+            ("sender_addr1", "43 rue Courtalon"),
+            ("sender_addr2", ""),
+            ("sender_postal_code", "10000"),
+            ("sender_city", "Troyes"),
+            ("client_id", "C1"),
+            ("client_name", "Dupont"),
+            ("client_address1", ""),
+            ("client_address2", ""),
+            ("client_postal_code", ""),
+            ("client_city", ""),
+            ("items", ""),
+        ]
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+        let note = DeliveryNote::from_fields(fields).unwrap();
+        assert_eq!(note.sender.id, "");
+        assert_eq!(note.sender.city, "Troyes");
+        assert_eq!(note.sender.siret, "");
+    }
+}
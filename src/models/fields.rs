@@ -0,0 +1,238 @@
+//! Helpers for the models whose `Savable` implementation is written by hand.
+
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::{
+    i18n::Language,
+    storage::{csv, Savable, StorageError},
+};
+
+use super::{
+    amount::{parse_decimal, split_quantity},
+    client::Client,
+    country::Country,
+    item::Item,
+    product::{standard_vat_rate, Product},
+    sender::Sender,
+    unit::Unit,
+};
+
+const ITEM_DELIMITER: char = ',';
+
+pub type Fields = Vec<(String, String)>;
+
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Removes the field `key` and returns its value.
+pub fn take(fields: &mut Fields, key: &str) -> Result<String, StorageError> {
+    match fields.iter().position(|field| field.0 == key) {
+        Some(idx) => Ok(fields.swap_remove(idx).1),
+        None => Err(StorageError::missing_field(key)),
+    }
+}
+
+/// Removes the field `key` and returns its value, or an empty string when
+/// the field is missing.
+pub fn take_or_default(fields: &mut Fields, key: &str) -> String {
+    take(fields, key).unwrap_or_default()
+}
+
+pub fn date_field(key: &str, date: &NaiveDate) -> (String, String) {
+    (key.to_string(), date.format(DATE_FORMAT).to_string())
+}
+
+/// Removes the field `key` and parses its value as a date.
+pub fn take_date(fields: &mut Fields, key: &str) -> Result<NaiveDate, StorageError> {
+    let value = take(fields, key)?;
+    NaiveDate::parse_from_str(&value, DATE_FORMAT)
+        .map_err(|e| StorageError::invalid_value(key, &value, e.to_string()))
+}
+
+/// The fields of the sender copied in a document, prefixed by `sender_`.
+pub fn sender_to_fields(sender: &Sender) -> Fields {
+    sender
+        .to_fields()
+        .into_iter()
+        .map(|(key, value)| (format!("sender_{}", key), value))
+        .collect()
+}
+
+/// Documents saved before the sender profiles only have the address.
+pub fn sender_from_fields(fields: &mut Fields) -> Result<Sender, StorageError> {
+    let (sender_fields, others): (Fields, Fields) = fields
+        .drain(..)
+        .partition(|field| field.0.starts_with("sender_"));
+    *fields = others;
+    let mut sender_fields = sender_fields
+        .into_iter()
+        .map(|(key, value)| (key["sender_".len()..].to_string(), value))
+        .collect::<Fields>();
+    if !sender_fields.iter().any(|field| field.0 == "id") {
+        sender_fields.push(("id".to_string(), String::new()));
+    }
+    Sender::from_fields(sender_fields)
+}
+
+pub fn client_to_fields(client: &Client) -> Fields {
+    vec![
+        ("client_id".to_string(), client.id.clone()),
+        ("client_name".to_string(), client.name.clone()),
+        ("client_address1".to_string(), client.address1.clone()),
+        ("client_address2".to_string(), client.address2.clone()),
+        ("client_postal_code".to_string(), client.postal_code.clone()),
+        ("client_city".to_string(), client.city.clone()),
+        ("client_country".to_string(), client.country.to_string()),
+        ("client_language".to_string(), client.language.to_string()),
+    ]
+}
+
+pub fn client_from_fields(fields: &mut Fields) -> Result<Client, StorageError> {
+    // Documents saved before the languages are in French
+    let language = match take_or_default(fields, "client_language") {
+        language if language.is_empty() => Language::default(),
+        language => language
+            .parse()
+            .map_err(|e| StorageError::invalid_value("client_language", &language, e))?,
+    };
+    // and the addresses in France
+    let country = match take_or_default(fields, "client_country") {
+        country if country.is_empty() => Country::default(),
+        country => country
+            .parse()
+            .map_err(|e| StorageError::invalid_value("client_country", &country, e))?,
+    };
+    Ok(Client {
+        id: take(fields, "client_id")?,
+        name: take(fields, "client_name")?,
+        address1: take(fields, "client_address1")?,
+        address2: take(fields, "client_address2")?,
+        postal_code: take(fields, "client_postal_code")?,
+        city: take(fields, "client_city")?,
+        country,
+        language,
+    })
+}
+
+/// Items are stored in a single field, one CSV record per item.
+pub fn items_to_field(items: &[Item]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            csv::write_record(
+                &[
+                    item.product().id().clone(),
+                    item.product().description().clone(),
+                    item.product().reference().clone(),
+                    item.price().to_string(),
+                    item.quantity().to_string(),
+                    item.product().unit().to_string(),
+                    item.product().vat_rate().to_string(),
+                ],
+                ITEM_DELIMITER,
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn items_from_field(field: &str) -> Result<Vec<Item>, StorageError> {
+    let decimal = |name: &str, value: &str| {
+        Decimal::from_str(value)
+            .map_err(|e| StorageError::invalid_value(name, value, e.to_string()))
+    };
+    let mut items = Vec::new();
+    for record in csv::parse_records(field, ITEM_DELIMITER)? {
+        match record.values.as_slice() {
+            [id, description, reference, price, quantity, unit, vat_rate] => {
+                let mut product = Product::new(id, description, reference);
+                *product.unit_mut() = Unit::from_str(unit)
+                    .map_err(|e| StorageError::invalid_value("unit", unit, e))?;
+                *product.vat_rate_mut() = decimal("vat_rate", vat_rate)?;
+                items.push(Item::new(
+                    product,
+                    decimal("price", price)?,
+                    decimal("quantity", quantity)?,
+                ))
+            }
+            _ => {
+                return Err(StorageError::invalid_value(
+                    "items",
+                    field,
+                    format!("item {} does not have 7 values", record.line),
+                ))
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Version 2 of the documents with items stores their prices and quantities
+/// as decimal numbers, and the unit of their products, read from the symbol
+/// after the quantity, or pieces when there is none. A missing price is 0.
+/// A quantity typed freely, such as `4 sacs`, is kept after the description
+/// and counted in pieces, so that the document still loads.
+pub fn add_item_units(mut fields: Fields) -> Result<Fields, StorageError> {
+    for (key, value) in fields.iter_mut().filter(|field| field.0 == "items") {
+        let records = csv::parse_records(value, ITEM_DELIMITER)?;
+        // Already migrated
+        if records.iter().all(|record| record.values.len() > 5) {
+            continue;
+        }
+        let mut items = Vec::new();
+        for record in records {
+            let [id, description, reference, price, quantity] = record.values.as_slice() else {
+                return Err(StorageError::invalid_value(
+                    key,
+                    value,
+                    format!("item {} does not have 5 values", record.line),
+                ));
+            };
+            let invalid = |name: &str, value: &str, message: &str| {
+                StorageError::invalid_value(name, value, message.to_string())
+            };
+            let price = match price.trim() {
+                "" => Decimal::ZERO,
+                price => parse_decimal(price)
+                    .ok_or_else(|| invalid("price", price, "expected a decimal number"))?,
+            };
+            let (number, symbol) = split_quantity(quantity);
+            let unit = match symbol {
+                "" => Some((Unit::Piece, Decimal::ONE)),
+                symbol => Unit::from_symbol(symbol),
+            };
+            let mut product = Product::new(id, description, reference);
+            let quantity = match (parse_decimal(number), unit) {
+                (Some(number), Some((unit, factor))) => {
+                    *product.unit_mut() = unit;
+                    (number * factor).normalize()
+                }
+                (number, _) => {
+                    *product.description_mut() = format!("{} ({})", description, quantity.trim());
+                    number.unwrap_or(Decimal::ONE)
+                }
+            };
+            items.push(Item::new(product, price, quantity));
+        }
+        *value = items_to_field(&items);
+    }
+    Ok(fields)
+}
+
+/// Version 3 of the documents with items stores the VAT rate of their
+/// products, which were all billed at the standard rate.
+pub fn add_item_vat_rates(mut fields: Fields) -> Result<Fields, StorageError> {
+    for (_, value) in fields.iter_mut().filter(|field| field.0 == "items") {
+        let mut records = Vec::new();
+        for mut record in csv::parse_records(value, ITEM_DELIMITER)? {
+            if record.values.len() == 6 {
+                record.values.push(standard_vat_rate().to_string());
+            }
+            records.push(csv::write_record(&record.values, ITEM_DELIMITER));
+        }
+        *value = records.join("\n");
+    }
+    Ok(fields)
+}
@@ -0,0 +1,322 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::storage::{csv, migration::Fields, Savable, StorageError};
+
+use super::{
+    amount::round_amount,
+    client::Client,
+    delivery_note::DeliveryNote,
+    fields::{
+        client_from_fields, client_to_fields, date_field, sender_from_fields, sender_to_fields,
+        take, take_date,
+    },
+    product::Product,
+    sender::Sender,
+    unit::Unit,
+};
+
+const LINE_DELIMITER: char = ',';
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvoiceLine {
+    pub product: Product,
+    /// Price of one unit, excluding tax.
+    pub unit_price: Decimal,
+    pub quantity: Decimal,
+    /// VAT rate in percent.
+    pub vat_rate: Decimal,
+}
+
+impl InvoiceLine {
+    /// Price of the line, excluding tax.
+    pub fn total(&self) -> Decimal {
+        round_amount(self.unit_price * self.quantity)
+    }
+}
+
+/// The VAT due for one rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VatAmount {
+    pub rate: Decimal,
+    /// Total of the lines at this rate, excluding tax.
+    pub base: Decimal,
+    pub amount: Decimal,
+}
+
+/// An invoice, usually created from a delivery note. Like delivery notes, it
+/// keeps a copy of the sender, the client and the products.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invoice {
+    pub number: String,
+    pub date: NaiveDate,
+    pub due_date: NaiveDate,
+    /// Number of the delivery note the invoice was created from, if any.
+    pub delivery_note: String,
+    pub sender: Sender,
+    pub client: Client,
+    pub lines: Vec<InvoiceLine>,
+}
+
+impl Invoice {
+    /// Bills the items of `note`, all at `vat_rate`.
+    pub fn from_delivery_note(
+        number: String,
+        note: &DeliveryNote,
+        date: NaiveDate,
+        due_date: NaiveDate,
+    ) -> Self {
+        let lines = note
+            .items
+            .iter()
+            .map(|item| InvoiceLine {
+                product: item.product().clone(),
+                unit_price: item.price(),
+                quantity: item.quantity(),
+                vat_rate: item.product().vat_rate(),
+            })
+            .collect();
+        Invoice {
+            number,
+            date,
+            due_date,
+            delivery_note: note.number.clone(),
+            sender: note.sender.clone(),
+            client: note.client.clone(),
+            lines,
+        }
+    }
+
+    /// The VAT of each rate, by increasing rate. The VAT is rounded once per
+    /// rate, not once per line.
+    pub fn vat_breakdown(&self) -> Vec<VatAmount> {
+        let mut breakdown: Vec<VatAmount> = Vec::new();
+        for line in &self.lines {
+            match breakdown.iter_mut().find(|vat| vat.rate == line.vat_rate) {
+                Some(vat) => vat.base += line.total(),
+                None => breakdown.push(VatAmount {
+                    rate: line.vat_rate,
+                    base: line.total(),
+                    amount: Decimal::ZERO,
+                }),
+            }
+        }
+        for vat in breakdown.iter_mut() {
+            vat.amount = round_amount(vat.base * vat.rate / Decimal::ONE_HUNDRED);
+        }
+        breakdown.sort_by_key(|vat| vat.rate);
+        breakdown
+    }
+
+    pub fn total_excluding_tax(&self) -> Decimal {
+        self.lines.iter().map(|line| line.total()).sum()
+    }
+
+    pub fn total_vat(&self) -> Decimal {
+        self.vat_breakdown().iter().map(|vat| vat.amount).sum()
+    }
+
+    pub fn total_including_tax(&self) -> Decimal {
+        self.total_excluding_tax() + self.total_vat()
+    }
+}
+
+/// Lines are stored in a single field, one CSV record per line.
+fn lines_to_field(lines: &[InvoiceLine]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            csv::write_record(
+                &[
+                    line.product.id().clone(),
+                    line.product.description().clone(),
+                    line.product.reference().clone(),
+                    line.unit_price.to_string(),
+                    line.quantity.to_string(),
+                    line.vat_rate.to_string(),
+                    line.product.unit().to_string(),
+                ],
+                LINE_DELIMITER,
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn lines_from_field(field: &str) -> Result<Vec<InvoiceLine>, StorageError> {
+    let decimal = |name: &str, value: &str| {
+        Decimal::from_str(value)
+            .map_err(|e| StorageError::invalid_value(name, value, e.to_string()))
+    };
+    let mut lines = Vec::new();
+    for record in csv::parse_records(field, LINE_DELIMITER)? {
+        match record.values.as_slice() {
+            [id, description, reference, unit_price, quantity, vat_rate, unit] => {
+                let mut product = Product::new(id, description, reference);
+                *product.unit_mut() = Unit::from_str(unit)
+                    .map_err(|e| StorageError::invalid_value("unit", unit, e))?;
+                // The line keeps the rate its product was billed at
+                let vat_rate = decimal("vat_rate", vat_rate)?;
+                *product.vat_rate_mut() = vat_rate;
+                lines.push(InvoiceLine {
+                    product,
+                    unit_price: decimal("unit_price", unit_price)?,
+                    quantity: decimal("quantity", quantity)?,
+                    vat_rate,
+                })
+            }
+            _ => {
+                return Err(StorageError::invalid_value(
+                    "lines",
+                    field,
+                    format!("line {} does not have 7 values", record.line),
+                ))
+            }
+        }
+    }
+    Ok(lines)
+}
+
+/// Version 2 stores the unit of the products of the lines, which were all
+/// counted in pieces.
+pub fn add_line_units(mut fields: Fields) -> Result<Fields, StorageError> {
+    for (_, value) in fields.iter_mut().filter(|field| field.0 == "lines") {
+        *value = csv::parse_records(value, LINE_DELIMITER)?
+            .into_iter()
+            .map(|record| {
+                let mut values = record.values;
+                // Lines that already have their unit were migrated
+                if values.len() == 6 {
+                    values.push(Unit::Piece.to_string());
+                }
+                csv::write_record(&values, LINE_DELIMITER)
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+    }
+    Ok(fields)
+}
+
+impl Savable for Invoice {
+    fn savable_name() -> String {
+        "invoice".to_string()
+    }
+
+    fn schema_version() -> u32 {
+        3
+    }
+
+    fn id_column() -> String {
+        "number".to_string()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        let mut fields = vec![
+            ("number".to_string(), self.number.clone()),
+            date_field("date", &self.date),
+            date_field("due_date", &self.due_date),
+            ("delivery_note".to_string(), self.delivery_note.clone()),
+        ];
+        fields.extend(sender_to_fields(&self.sender));
+        fields.extend(client_to_fields(&self.client));
+        fields.push(("lines".to_string(), lines_to_field(&self.lines)));
+        fields
+    }
+
+    fn from_fields(mut fields: Vec<(String, String)>) -> Result<Self, StorageError> {
+        Ok(Invoice {
+            number: take(&mut fields, "number")?,
+            date: take_date(&mut fields, "date")?,
+            due_date: take_date(&mut fields, "due_date")?,
+            delivery_note: take(&mut fields, "delivery_note")?,
+            sender: sender_from_fields(&mut fields)?,
+            client: client_from_fields(&mut fields)?,
+            lines: lines_from_field(&take(&mut fields, "lines")?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::item::Item;
+    use rust_decimal::dec;
+
+    fn note() -> DeliveryNote {
+        DeliveryNote {
+            number: "BL-2026-00007".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 3, 14).unwrap(),
+            sender: Sender {
+                name: "Alexandre".to_string(),
+                postal_code: "10000".to_string(),
+                ..Default::default()
+            },
+            client: Client {
+                id: "C1".to_string(),
+                name: "Dupont".to_string(),
+                ..Default::default()
+            },
+            items: vec![
+                Item::new(Product::new("1", "Chaise", "CH-01"), dec!(12.50), dec!(3)),
+                Item::new(Product::new("2", "Vis", "VI-01"), dec!(0.015), dec!(100)),
+            ],
+            quote: String::new(),
+        }
+    }
+
+    fn invoice() -> Invoice {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 20).unwrap();
+        let due_date = NaiveDate::from_ymd_opt(2026, 4, 19).unwrap();
+        Invoice::from_delivery_note("FA-2026-00001".to_string(), &note(), date, due_date)
+    }
+
+    #[test]
+    fn test_totals() {
+        let mut invoice = invoice();
+        assert_eq!(invoice.lines[0].total(), dec!(37.50));
+        assert_eq!(invoice.lines[1].total(), dec!(1.50));
+        assert_eq!(invoice.total_excluding_tax(), dec!(39.00));
+        assert_eq!(invoice.total_vat(), dec!(7.80));
+        assert_eq!(invoice.total_including_tax(), dec!(46.80));
+
+        invoice.lines[1].vat_rate = dec!(5.5);
+        assert_eq!(
+            invoice.vat_breakdown(),
+            vec![
+                VatAmount {
+                    rate: dec!(5.5),
+                    base: dec!(1.50),
+                    amount: dec!(0.08),
+                },
+                VatAmount {
+                    rate: dec!(20),
+                    base: dec!(37.50),
+                    amount: dec!(7.50),
+                },
+            ]
+        );
+        assert_eq!(invoice.total_including_tax(), dec!(46.58));
+    }
+
+    #[test]
+    fn test_add_line_units() {
+        let fields = vec![(
+            "lines".to_string(),
+            "1,Chaise,CH-01,12.50,3,20\n2,Vis,VI-01,0.015,100,20".to_string(),
+        )];
+        let migrated = add_line_units(fields).unwrap();
+        assert_eq!(add_line_units(migrated.clone()).unwrap(), migrated);
+        let lines = lines_from_field(&migrated[0].1).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].product.unit(), &Unit::Piece);
+        assert_eq!(lines[1].quantity, dec!(100));
+    }
+
+    #[test]
+    fn test_fields_round_trip() {
+        let invoice = invoice();
+        assert_eq!(Invoice::from_fields(invoice.to_fields()).unwrap(), invoice);
+    }
+}
@@ -1,33 +1,128 @@
-use super::product::Product;
+use rust_decimal::Decimal;
 
-#[derive(Clone, Default)]
+use super::{
+    amount::{parse_decimal, split_quantity},
+    product::Product,
+    unit::Unit,
+    validation::{FieldError, Problem},
+};
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Item {
     product: Product,
-    price: String,
-    quantity: String,
+    /// Price of one unit of the product.
+    price: Decimal,
+    /// Quantity in the unit of the product.
+    quantity: Decimal,
 }
 
 impl Item {
-    pub fn new(product: Product, price: String, quantity: String) -> Self {
+    pub fn new(product: Product, price: Decimal, quantity: Decimal) -> Self {
         return Self {
             product,
             price,
             quantity,
         };
     }
+    /// Reads the price and the quantity typed by the user. The quantity may
+    /// be followed by a symbol of the unit of the product or of a multiple of
+    /// it, such as `500 g` for a product sold by the kilogram.
+    pub fn parse(product: Product, price: &str, quantity: &str) -> Result<Self, Vec<FieldError>> {
+        let mut errors = Vec::new();
+        let mut read = |field: &str, value: &str| {
+            let problem = if value.trim().is_empty() {
+                Problem::Required
+            } else {
+                match parse_decimal(value) {
+                    Some(number) => return number,
+                    None => Problem::NotANumber,
+                }
+            };
+            errors.push(FieldError::new(field, problem));
+            return Decimal::ZERO;
+        };
+        let price = read("price", price);
+        let (number, symbol) = split_quantity(quantity);
+        let mut quantity = read(
+            "quantity",
+            if number.is_empty() { quantity } else { number },
+        );
+        if !symbol.is_empty() && !number.is_empty() {
+            let unit = *product.unit();
+            match unit.convert(quantity, symbol) {
+                Some(converted) => quantity = converted,
+                None if Unit::from_symbol(symbol).is_some() => {
+                    errors.push(FieldError::new("quantity", Problem::WrongUnit(unit)));
+                }
+                None => errors.push(FieldError::new(
+                    "quantity",
+                    Problem::UnknownUnit(symbol.to_string()),
+                )),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        return Ok(Self::new(product, price, quantity));
+    }
     pub fn product(&self) -> &Product {
         return &self.product;
     }
-    pub fn product_mut(&mut self) -> &mut Product {
-        return &mut self.product;
+    pub fn price(&self) -> Decimal {
+        return self.price;
     }
-    pub fn price(&self) -> &String {
-        return &self.price;
+    pub fn quantity(&self) -> Decimal {
+        return self.quantity;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::dec;
+
+    fn product(unit: Unit) -> Product {
+        let mut product = Product::new("1", "Sable", "SA-01");
+        *product.unit_mut() = unit;
+        return product;
     }
-    pub fn quantity(&self) -> &String {
-        return &self.quantity;
+
+    #[test]
+    fn test_parse() {
+        let item = Item::parse(product(Unit::Kilogram), "12,50", "2,5").unwrap();
+        assert_eq!((item.price(), item.quantity()), (dec!(12.50), dec!(2.5)));
+        let item = Item::parse(product(Unit::Kilogram), "1", "1,2 t").unwrap();
+        assert_eq!(item.quantity(), dec!(1200));
+        let item = Item::parse(product(Unit::Metre), "1", "25cm").unwrap();
+        assert_eq!(item.quantity(), dec!(0.25));
     }
-    pub fn quantity_mut(&mut self) -> &mut String {
-        return &mut self.quantity;
+
+    #[test]
+    fn test_parse_errors() {
+        let problems = |price, quantity, unit| {
+            return Item::parse(product(unit), price, quantity)
+                .unwrap_err()
+                .into_iter()
+                .map(|error| (error.field, error.problem))
+                .collect::<Vec<(String, Problem)>>();
+        };
+        assert_eq!(
+            problems("", "abc", Unit::Piece),
+            vec![
+                ("price".to_string(), Problem::Required),
+                ("quantity".to_string(), Problem::NotANumber),
+            ]
+        );
+        assert_eq!(
+            problems("1", "3 kg", Unit::Piece),
+            vec![("quantity".to_string(), Problem::WrongUnit(Unit::Piece))]
+        );
+        assert_eq!(
+            problems("1", "3 sacs", Unit::Piece),
+            vec![(
+                "quantity".to_string(),
+                Problem::UnknownUnit("sacs".to_string())
+            )]
+        );
     }
 }
@@ -1,5 +1,32 @@
-use egui::TextBuffer;
-
+pub mod amount;
 pub mod client;
+pub mod counter;
+pub mod country;
+pub mod delivery_note;
+pub mod fields;
+pub mod invoice;
 pub mod item;
 pub mod product;
+pub mod quote;
+pub mod sender;
+pub mod unit;
+pub mod validation;
+
+use crate::storage::migration::Migrations;
+
+/// Migrations of the stored models, to register whenever the schema version
+/// of a model is increased.
+pub fn migrations() -> Migrations {
+    Migrations::new()
+        .register("Product", 1, product::add_unit)
+        .register("Product", 2, product::add_vat_rate)
+        .register("delivery_note", 1, fields::add_item_units)
+        .register("quote", 1, fields::add_item_units)
+        .register("delivery_note", 2, fields::add_item_vat_rates)
+        .register("quote", 2, fields::add_item_vat_rates)
+        .register("invoice", 1, invoice::add_line_units)
+        .register("sender", 1, sender::pad_postal_code)
+        .register("delivery_note", 3, sender::pad_postal_code)
+        .register("quote", 3, sender::pad_postal_code)
+        .register("invoice", 2, sender::pad_postal_code)
+}
@@ -1,11 +1,38 @@
-use crate::storage::Savable;
+use rust_decimal::{dec, Decimal};
+use savable_derive::Savable;
+
+use crate::storage::{migration::Fields, StorageError};
+
+use super::unit::Unit;
+
+/// The VAT rates of France, in percent, the standard rate first.
+pub const VAT_RATES: [Decimal; 5] = [dec!(20), dec!(10), dec!(5.5), dec!(2.1), dec!(0)];
+
+pub fn standard_vat_rate() -> Decimal {
+    return VAT_RATES[0];
+}
 
 // Définir une structure pour un objet acheté
-#[derive(Default, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Savable)]
+#[savable(name = "Product", version = 3)]
 pub struct Product {
     id: String,
+    #[savable(default)]
     description: String,
+    #[savable(default)]
     reference: String,
+    /// Unit the quantities of the product are in.
+    #[savable(default)]
+    unit: Unit,
+    /// VAT rate the product is billed at, in percent.
+    #[savable(default = "standard_vat_rate")]
+    vat_rate: Decimal,
+}
+
+impl Default for Product {
+    fn default() -> Self {
+        return Product::new("", "", "");
+    }
 }
 
 impl Product {
@@ -14,6 +41,8 @@ impl Product {
             id: id.to_owned(),
             description: description.to_owned(),
             reference: reference.to_owned(),
+            unit: Unit::default(),
+            vat_rate: standard_vat_rate(),
         };
     }
     pub fn id(&self) -> &String {
@@ -34,43 +63,105 @@ impl Product {
     pub fn reference_mut(&mut self) -> &mut String {
         return &mut self.reference;
     }
+    pub fn unit(&self) -> &Unit {
+        return &self.unit;
+    }
+    pub fn unit_mut(&mut self) -> &mut Unit {
+        return &mut self.unit;
+    }
+    pub fn vat_rate(&self) -> Decimal {
+        return self.vat_rate;
+    }
+    pub fn vat_rate_mut(&mut self) -> &mut Decimal {
+        return &mut self.vat_rate;
+    }
+}
+
+/// Version 2 adds the unit of the products, which were all counted in pieces.
+pub fn add_unit(mut fields: Fields) -> Result<Fields, StorageError> {
+    if !fields.iter().any(|field| field.0 == "unit") {
+        fields.push(("unit".to_string(), Unit::Piece.to_string()));
+    }
+    Ok(fields)
 }
 
-impl Savable for Product {
-    fn savable_name() -> String
-    where
-        Self: Sized,
-    {
-        return "Product".to_owned();
-    }
-
-    fn to_fields(&self) -> Vec<(String, String)>
-    where
-        Self: Sized,
-    {
-        return vec![
-            ("id".to_owned(), self.id.clone()),
-            ("description".to_owned(), self.description.clone()),
-            ("reference".to_owned(), self.reference.clone()),
-        ];
-    }
-
-    fn from_fields(fields: Vec<(String, String)>) -> Result<Self, String>
-    where
-        Self: Sized,
-    {
-        if fields.len() == 3 {
-            let id = fields.get(0).unwrap();
-            let description = fields.get(1).unwrap();
-            let reference = fields.get(2).unwrap();
-            if id.0 == "id" && description.0 == "description" && reference.0 == "reference" {
-                return Ok(Self {
-                    id: id.1.clone(),
-                    description: description.1.clone(),
-                    reference: reference.1.clone(),
-                });
+/// Version 3 adds the VAT rate of the products, which were all billed at the
+/// standard rate.
+pub fn add_vat_rate(mut fields: Fields) -> Result<Fields, StorageError> {
+    if !fields.iter().any(|field| field.0 == "vat_rate") {
+        fields.push(("vat_rate".to_string(), standard_vat_rate().to_string()));
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{Savable, Storage, StorageError, StorageType};
+    use std::{fs, path::Path};
+
+    fn setup_test_folder(folder: &str) {
+        let path = Path::new(folder);
+        if path.exists() {
+            fs::remove_dir_all(path).unwrap();
+        }
+        fs::create_dir(path).unwrap();
+    }
+
+    #[test]
+    fn test_add_unit() {
+        let fields = vec![("id".to_string(), "1".to_string())];
+        let migrated = add_unit(fields).unwrap();
+        assert_eq!(migrated[1], ("unit".to_string(), "pcs".to_string()));
+        assert_eq!(add_unit(migrated.clone()).unwrap(), migrated);
+    }
+
+    #[test]
+    fn test_add_vat_rate() {
+        let fields = vec![("id".to_string(), "1".to_string())];
+        let migrated = add_vat_rate(fields).unwrap();
+        assert_eq!(migrated[1], ("vat_rate".to_string(), "20".to_string()));
+        assert_eq!(add_vat_rate(migrated.clone()).unwrap(), migrated);
+        let product = Product::from_fields(migrated).unwrap();
+        assert_eq!(product.vat_rate(), dec!(20));
+    }
+
+    #[test]
+    fn test_load_reordered_columns() {
+        let folder = "test_product_reordered";
+        setup_test_folder(folder);
+        fs::write(
+            format!("{}/Product.csv", folder),
+            "reference;weight;id\nCH-01;4kg;1\nTA-01;20kg;2\n",
+        )
+        .unwrap();
+
+        let storage = StorageType::FileStorage(folder).build();
+        assert_eq!(
+            storage.load::<Product>().unwrap(),
+            vec![
+                Product::new("1", "", "CH-01"),
+                Product::new("2", "", "TA-01")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_reports_failing_line() {
+        let folder = "test_product_failing_line";
+        setup_test_folder(folder);
+        fs::write(
+            format!("{}/Product.csv", folder),
+            "description;id\nChaise;1\nTable\n",
+        )
+        .unwrap();
+
+        let storage = StorageType::FileStorage(folder).build();
+        match storage.load::<Product>() {
+            Err(e @ StorageError::MissingField { .. }) => {
+                assert_eq!(e.to_string(), "Missing field \"id\" on line 3")
             }
+            _ => panic!("expected a missing field error"),
         }
-        return Err("Cannot create Product from fields".to_string());
     }
 }
@@ -0,0 +1,102 @@
+use chrono::NaiveDate;
+
+use crate::storage::{Savable, StorageError};
+
+use super::{
+    client::Client,
+    fields::{
+        client_from_fields, client_to_fields, date_field, items_from_field, items_to_field,
+        sender_from_fields, sender_to_fields, take, take_date,
+    },
+    item::Item,
+    sender::Sender,
+};
+
+/// A quote sent to a client before delivery. Once accepted, it is converted
+/// into a delivery note, whose number is kept in `delivery_note`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub number: String,
+    pub date: NaiveDate,
+    /// Last day the client can accept the quote.
+    pub valid_until: NaiveDate,
+    pub sender: Sender,
+    pub client: Client,
+    pub items: Vec<Item>,
+    /// Number of the delivery note the quote was converted into, if any.
+    pub delivery_note: String,
+}
+
+impl Quote {
+    pub fn is_accepted(&self) -> bool {
+        !self.delivery_note.is_empty()
+    }
+}
+
+impl Savable for Quote {
+    fn savable_name() -> String {
+        "quote".to_string()
+    }
+
+    fn schema_version() -> u32 {
+        4
+    }
+
+    fn id_column() -> String {
+        "number".to_string()
+    }
+
+    fn to_fields(&self) -> Vec<(String, String)> {
+        let mut fields = vec![
+            ("number".to_string(), self.number.clone()),
+            date_field("date", &self.date),
+            date_field("valid_until", &self.valid_until),
+        ];
+        fields.extend(sender_to_fields(&self.sender));
+        fields.extend(client_to_fields(&self.client));
+        fields.push(("items".to_string(), items_to_field(&self.items)));
+        fields.push(("delivery_note".to_string(), self.delivery_note.clone()));
+        fields
+    }
+
+    fn from_fields(mut fields: Vec<(String, String)>) -> Result<Self, StorageError> {
+        Ok(Quote {
+            number: take(&mut fields, "number")?,
+            date: take_date(&mut fields, "date")?,
+            valid_until: take_date(&mut fields, "valid_until")?,
+            sender: sender_from_fields(&mut fields)?,
+            client: client_from_fields(&mut fields)?,
+            items: items_from_field(&take(&mut fields, "items")?)?,
+            delivery_note: take(&mut fields, "delivery_note")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::product::Product;
+    use rust_decimal::dec;
+
+    #[test]
+    fn test_fields_round_trip() {
+        let quote = Quote {
+            number: "DE-2026-00001".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            valid_until: NaiveDate::from_ymd_opt(2026, 3, 31).unwrap(),
+            sender: Sender::default(),
+            client: Client {
+                id: "C1".to_string(),
+                ..Default::default()
+            },
+            items: vec![Item::new(
+                Product::new("1", "Chaise", "CH-01"),
+                dec!(12.50),
+                dec!(4),
+            )],
+            delivery_note: String::new(),
+        };
+        assert!(!quote.is_accepted());
+        assert_eq!(Quote::from_fields(quote.to_fields()).unwrap(), quote);
+    }
+}
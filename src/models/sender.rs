@@ -0,0 +1,88 @@
+use savable_derive::Savable;
+
+use crate::storage::{migration::Fields, StorageError};
+
+/// A company profile, used as the sender of the documents. There is one
+/// profile for each business unit.
+#[derive(Debug, Default, Clone, PartialEq, Savable)]
+#[savable(name = "sender", version = 2)]
+pub struct Sender {
+    pub id: String,
+    /// Legal name of the company.
+    pub name: String,
+    pub addr1: String,
+    pub addr2: String,
+    /// Postal code in France, where the company is.
+    pub postal_code: String,
+    pub city: String,
+    #[savable(default)]
+    pub siret: String,
+    #[savable(default)]
+    pub vat_number: String,
+    #[savable(default)]
+    pub phone: String,
+    #[savable(default)]
+    pub email: String,
+    /// PNG image drawn on the documents, none when empty.
+    #[savable(default)]
+    pub logo_path: String,
+    /// Text written at the bottom of every page.
+    #[savable(default)]
+    pub footer: String,
+}
+
+impl Sender {
+    pub fn logo_path(&self) -> Option<String> {
+        if self.logo_path.is_empty() {
+            return None;
+        }
+        return Some(self.logo_path.clone());
+    }
+}
+
+/// Version 2 stores the postal codes of the senders as text. They were
+/// numbers, which dropped the leading zero of the codes in France and wrote
+/// 0 for no code. Documents copy the sender as `sender_postal_code`.
+pub fn pad_postal_code(mut fields: Fields) -> Result<Fields, StorageError> {
+    for (key, value) in fields.iter_mut() {
+        if key != "postal_code" && key != "sender_postal_code" {
+            continue;
+        }
+        if value == "0" {
+            value.clear();
+        } else if !value.is_empty() && value.len() < 5 && value.bytes().all(|b| b.is_ascii_digit())
+        {
+            *value = format!("{:0>5}", value);
+        }
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Savable;
+
+    #[test]
+    fn test_pad_postal_code() {
+        let fields = |postal_code: &str| {
+            vec![
+                ("id".to_string(), "BU1".to_string()),
+                ("name".to_string(), "Alexandre".to_string()),
+                ("addr1".to_string(), String::new()),
+                ("addr2".to_string(), String::new()),
+                ("postal_code".to_string(), postal_code.to_string()),
+                ("city".to_string(), "Bourg-en-Bresse".to_string()),
+            ]
+        };
+        let migrated = pad_postal_code(fields("1000")).unwrap();
+        assert_eq!(pad_postal_code(migrated.clone()).unwrap(), migrated);
+        let sender = Sender::from_fields(migrated).unwrap();
+        assert_eq!(sender.postal_code, "01000");
+
+        let sender = Sender::from_fields(pad_postal_code(fields("0")).unwrap()).unwrap();
+        assert_eq!(sender.postal_code, "");
+        let sender = Sender::from_fields(pad_postal_code(fields("10000")).unwrap()).unwrap();
+        assert_eq!(sender.postal_code, "10000");
+    }
+}
@@ -0,0 +1,153 @@
+use std::{fmt, str::FromStr};
+
+use rust_decimal::Decimal;
+
+/// Unit of measure a product is sold in. The quantities of the items of a
+/// product are in its unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Unit {
+    #[default]
+    Piece,
+    Kilogram,
+    Metre,
+    SquareMetre,
+    Litre,
+    Pallet,
+}
+
+/// Symbols a quantity may be typed with, the unit they are of, and what a
+/// quantity in the symbol is multiplied by to be in the unit.
+const SYMBOLS: [(&str, Unit, i64, u32); 24] = [
+    ("pcs", Unit::Piece, 1, 0),
+    ("pc", Unit::Piece, 1, 0),
+    ("pce", Unit::Piece, 1, 0),
+    ("u", Unit::Piece, 1, 0),
+    ("stk", Unit::Piece, 1, 0),
+    ("kg", Unit::Kilogram, 1, 0),
+    ("g", Unit::Kilogram, 1, 3),
+    ("t", Unit::Kilogram, 1000, 0),
+    ("m", Unit::Metre, 1, 0),
+    ("km", Unit::Metre, 1000, 0),
+    ("cm", Unit::Metre, 1, 2),
+    ("mm", Unit::Metre, 1, 3),
+    ("m²", Unit::SquareMetre, 1, 0),
+    ("m2", Unit::SquareMetre, 1, 0),
+    ("cm²", Unit::SquareMetre, 1, 4),
+    ("cm2", Unit::SquareMetre, 1, 4),
+    ("l", Unit::Litre, 1, 0),
+    ("hl", Unit::Litre, 100, 0),
+    ("cl", Unit::Litre, 1, 2),
+    ("ml", Unit::Litre, 1, 3),
+    ("pal", Unit::Pallet, 1, 0),
+    ("pallet", Unit::Pallet, 1, 0),
+    ("pallets", Unit::Pallet, 1, 0),
+    ("palettes", Unit::Pallet, 1, 0),
+];
+
+impl Unit {
+    pub const ALL: [Unit; 6] = [
+        Unit::Piece,
+        Unit::Kilogram,
+        Unit::Metre,
+        Unit::SquareMetre,
+        Unit::Litre,
+        Unit::Pallet,
+    ];
+
+    /// The code the unit is stored as, which is also a symbol it can be
+    /// typed with.
+    pub fn code(self) -> &'static str {
+        return match self {
+            Unit::Piece => "pcs",
+            Unit::Kilogram => "kg",
+            Unit::Metre => "m",
+            Unit::SquareMetre => "m2",
+            Unit::Litre => "l",
+            Unit::Pallet => "pal",
+        };
+    }
+
+    /// The id of the message naming the unit next to a quantity.
+    pub fn message(self) -> &'static str {
+        return match self {
+            Unit::Piece => "unit-piece",
+            Unit::Kilogram => "unit-kilogram",
+            Unit::Metre => "unit-metre",
+            Unit::SquareMetre => "unit-square-metre",
+            Unit::Litre => "unit-litre",
+            Unit::Pallet => "unit-pallet",
+        };
+    }
+
+    /// The unit `symbol` is of, and the factor converting a quantity in
+    /// `symbol` into that unit, e.g. `g` is 0.001 `kg`.
+    pub fn from_symbol(symbol: &str) -> Option<(Unit, Decimal)> {
+        let symbol = symbol.trim().to_lowercase();
+        return SYMBOLS
+            .iter()
+            .find(|(known, ..)| *known == symbol)
+            .map(|(_, unit, factor, scale)| (*unit, Decimal::new(*factor, *scale)));
+    }
+
+    /// The symbols a quantity may be typed with, for error messages.
+    pub fn symbols() -> String {
+        return SYMBOLS
+            .iter()
+            .map(|(symbol, ..)| *symbol)
+            .collect::<Vec<&str>>()
+            .join(", ");
+    }
+
+    /// Converts `quantity`, written in `symbol`, into this unit. Returns
+    /// `None` when `symbol` is of another unit.
+    pub fn convert(self, quantity: Decimal, symbol: &str) -> Option<Decimal> {
+        return match Unit::from_symbol(symbol) {
+            Some((unit, factor)) if unit == self => Some((quantity * factor).normalize()),
+            _ => None,
+        };
+    }
+}
+
+/// Written as its code, which is how it is stored.
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.code());
+    }
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        return Unit::ALL
+            .into_iter()
+            .find(|unit| unit.code().eq_ignore_ascii_case(code))
+            .ok_or_else(|| format!("unsupported unit \"{}\"", code));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::dec;
+
+    #[test]
+    fn test_codes() {
+        for unit in Unit::ALL {
+            assert_eq!(unit.to_string().parse::<Unit>(), Ok(unit));
+            assert_eq!(Unit::from_symbol(unit.code()), Some((unit, dec!(1))));
+        }
+        assert!("sacs".parse::<Unit>().is_err());
+    }
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(Unit::Kilogram.convert(dec!(500), "g"), Some(dec!(0.5)));
+        assert_eq!(Unit::Kilogram.convert(dec!(1.2), "T"), Some(dec!(1200)));
+        assert_eq!(Unit::Metre.convert(dec!(25), "cm"), Some(dec!(0.25)));
+        assert_eq!(Unit::SquareMetre.convert(dec!(3), "m²"), Some(dec!(3)));
+        assert_eq!(Unit::Litre.convert(dec!(75), "cl"), Some(dec!(0.75)));
+        assert_eq!(Unit::Piece.convert(dec!(2), "kg"), None);
+        assert_eq!(Unit::Pallet.convert(dec!(2), "sacs"), None);
+    }
+}
@@ -0,0 +1,343 @@
+//! Rules a model must follow before it is saved or printed, checked field
+//! by field so that each error can be shown next to its input.
+
+use std::fmt;
+
+use rust_decimal::Decimal;
+
+use crate::{error::Error, i18n::Catalog};
+
+use super::{
+    client::Client, counter::Numbering, country::Country, delivery_note::DeliveryNote, item::Item,
+    product::Product, quote::Quote, sender::Sender, unit::Unit,
+};
+
+/// What is wrong with the value of a field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    Required,
+    InvalidPostalCode(Country),
+    NotANumber,
+    NotPositive,
+    Negative,
+    UnknownUnit(String),
+    /// The quantity is written in a unit the product is not sold in.
+    WrongUnit(Unit),
+    /// Another entity already has this id.
+    Taken,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    /// Name of the field as it is stored, e.g. `postal_code`, prefixed by
+    /// the path of the entity it belongs to, e.g. `items[0].quantity`.
+    pub field: String,
+    pub problem: Problem,
+}
+
+impl FieldError {
+    pub fn new(field: &str, problem: Problem) -> Self {
+        return FieldError {
+            field: field.to_string(),
+            problem,
+        };
+    }
+
+    /// The problem in the language of `catalog`, without the field name.
+    pub fn message(&self, catalog: &Catalog) -> String {
+        return match &self.problem {
+            Problem::Required => catalog.get("error-required"),
+            Problem::InvalidPostalCode(country) => catalog.format(
+                "error-postal-code",
+                &[("country", country.name().to_string())],
+            ),
+            Problem::NotANumber => catalog.get("error-not-a-number"),
+            Problem::NotPositive => catalog.get("error-not-positive"),
+            Problem::Negative => catalog.get("error-negative"),
+            Problem::UnknownUnit(unit) => catalog.format(
+                "error-unit",
+                &[("unit", unit.clone()), ("units", Unit::symbols())],
+            ),
+            Problem::WrongUnit(unit) => {
+                catalog.format("error-wrong-unit", &[("unit", catalog.get(unit.message()))])
+            }
+            Problem::Taken => catalog.get("error-taken"),
+        };
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = &self.field;
+        return match &self.problem {
+            Problem::Required => write!(f, "{} is required", field),
+            Problem::InvalidPostalCode(country) => {
+                write!(f, "{} is not a postal code of {}", field, country.name())
+            }
+            Problem::NotANumber => write!(f, "{} is not a number", field),
+            Problem::NotPositive => write!(f, "{} must be greater than zero", field),
+            Problem::Negative => write!(f, "{} cannot be negative", field),
+            Problem::UnknownUnit(unit) => write!(
+                f,
+                "{} has an unknown unit \"{}\", expected one of {}",
+                field,
+                unit,
+                Unit::symbols()
+            ),
+            Problem::WrongUnit(unit) => write!(f, "{} must be in {}", field, unit.code()),
+            Problem::Taken => write!(f, "{} is already taken", field),
+        };
+    }
+}
+
+/// The first error of `field` in `errors`, to show next to its input.
+pub fn error_of<'a>(errors: &'a [FieldError], field: &str) -> Option<&'a FieldError> {
+    return errors.iter().find(|error| error.field == field);
+}
+
+pub trait Validate {
+    /// Every field that breaks a rule, empty when the entity is valid.
+    fn validate(&self) -> Vec<FieldError>;
+}
+
+/// Fails with every error of the `kind` entity `id` unless it is valid.
+pub fn check(kind: &'static str, id: &str, entity: &impl Validate) -> Result<(), Error> {
+    let errors = entity.validate();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    return Err(Error::Invalid {
+        kind,
+        id: id.to_string(),
+        errors,
+    });
+}
+
+/// The errors of the fields of an entity the engine refused, empty when it
+/// was refused for another reason.
+pub fn field_errors(error: &Error) -> Vec<FieldError> {
+    return match error {
+        Error::Invalid { errors, .. } => errors.clone(),
+        Error::DuplicateId { .. } => vec![FieldError::new("id", Problem::Taken)],
+        _ => Vec::new(),
+    };
+}
+
+fn required(errors: &mut Vec<FieldError>, field: &str, value: &str) {
+    if value.trim().is_empty() {
+        errors.push(FieldError::new(field, Problem::Required));
+    }
+}
+
+/// Adds the errors of an entity contained in another, such as the client of
+/// a delivery note, with their fields prefixed by `path`.
+fn nested(errors: &mut Vec<FieldError>, path: &str, entity: &impl Validate) {
+    errors.extend(entity.validate().into_iter().map(|error| FieldError {
+        field: format!("{}.{}", path, error.field),
+        problem: error.problem,
+    }));
+}
+
+impl Validate for Client {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        required(&mut errors, "id", &self.id);
+        required(&mut errors, "name", &self.name);
+        required(&mut errors, "address1", &self.address1);
+        required(&mut errors, "postal_code", &self.postal_code);
+        if !self.postal_code.trim().is_empty()
+            && !self.country.is_valid_postal_code(&self.postal_code)
+        {
+            errors.push(FieldError::new(
+                "postal_code",
+                Problem::InvalidPostalCode(self.country),
+            ));
+        }
+        required(&mut errors, "city", &self.city);
+        return errors;
+    }
+}
+
+/// The senders are in France.
+impl Validate for Sender {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        let country = Country::default();
+        if !self.postal_code.trim().is_empty() && !country.is_valid_postal_code(&self.postal_code) {
+            errors.push(FieldError::new(
+                "postal_code",
+                Problem::InvalidPostalCode(country),
+            ));
+        }
+        return errors;
+    }
+}
+
+impl Validate for Product {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        required(&mut errors, "id", self.id());
+        if self.vat_rate() < Decimal::ZERO {
+            errors.push(FieldError::new("vat_rate", Problem::Negative));
+        }
+        return errors;
+    }
+}
+
+impl Validate for Numbering {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        required(&mut errors, "prefix", &self.prefix);
+        if self.width == 0 {
+            errors.push(FieldError::new("width", Problem::NotPositive));
+        }
+        return errors;
+    }
+}
+
+impl Validate for Item {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        required(&mut errors, "product", self.product().id());
+        if self.quantity() <= Decimal::ZERO {
+            errors.push(FieldError::new("quantity", Problem::NotPositive));
+        }
+        if self.price() < Decimal::ZERO {
+            errors.push(FieldError::new("price", Problem::Negative));
+        }
+        return errors;
+    }
+}
+
+/// The client and the items of a document, which are printed on it.
+fn validate_document(client: &Client, items: &[Item]) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    nested(&mut errors, "client", client);
+    if items.is_empty() {
+        errors.push(FieldError::new("items", Problem::Required));
+    }
+    for (index, item) in items.iter().enumerate() {
+        nested(&mut errors, &format!("items[{}]", index), item);
+    }
+    return errors;
+}
+
+impl Validate for DeliveryNote {
+    fn validate(&self) -> Vec<FieldError> {
+        return validate_document(&self.client, &self.items);
+    }
+}
+
+impl Validate for Quote {
+    fn validate(&self) -> Vec<FieldError> {
+        return validate_document(&self.client, &self.items);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+
+    fn client() -> Client {
+        return Client {
+            id: "1".to_string(),
+            name: "Dupont".to_string(),
+            address1: "1 rue de la Paix".to_string(),
+            postal_code: "75002".to_string(),
+            city: "Paris".to_string(),
+            ..Default::default()
+        };
+    }
+
+    fn item(quantity: Decimal, price: Decimal) -> Item {
+        return Item::new(Product::new("1", "Chaise", "CH-01"), price, quantity);
+    }
+
+    fn problems(errors: Vec<FieldError>) -> Vec<(String, Problem)> {
+        return errors
+            .into_iter()
+            .map(|error| (error.field, error.problem))
+            .collect();
+    }
+
+    #[test]
+    fn test_client() {
+        assert_eq!(client().validate(), vec![]);
+        let mut invalid = Client {
+            postal_code: "750".to_string(),
+            ..client()
+        };
+        invalid.id.clear();
+        assert_eq!(
+            problems(invalid.validate()),
+            vec![
+                ("id".to_string(), Problem::Required),
+                (
+                    "postal_code".to_string(),
+                    Problem::InvalidPostalCode(Country::France)
+                ),
+            ]
+        );
+        invalid.postal_code = "SW1A 1AA".to_string();
+        invalid.country = Country::UnitedKingdom;
+        assert_eq!(invalid.validate().len(), 1);
+    }
+
+    #[test]
+    fn test_product() {
+        // Only the id is required, like the columns of the product files
+        assert_eq!(Product::new("1", "", "").validate(), vec![]);
+        assert_eq!(
+            problems(Product::new("", "Chaise", "CH-01").validate()),
+            vec![("id".to_string(), Problem::Required)]
+        );
+        let mut product = Product::new("1", "Chaise", "CH-01");
+        *product.vat_rate_mut() = dec!(-5.5);
+        assert_eq!(
+            problems(product.validate()),
+            vec![("vat_rate".to_string(), Problem::Negative)]
+        );
+    }
+
+    #[test]
+    fn test_item() {
+        assert_eq!(item(dec!(3), dec!(12.50)).validate(), vec![]);
+        assert_eq!(item(dec!(2.5), dec!(0)).validate(), vec![]);
+        assert_eq!(
+            problems(item(dec!(0), dec!(-1)).validate()),
+            vec![
+                ("quantity".to_string(), Problem::NotPositive),
+                ("price".to_string(), Problem::Negative),
+            ]
+        );
+        let unknown = Item::new(Product::new("", "Chaise", "CH-01"), dec!(1), dec!(1));
+        assert_eq!(
+            problems(unknown.validate()),
+            vec![("product".to_string(), Problem::Required)]
+        );
+    }
+
+    #[test]
+    fn test_nested_fields() {
+        let note = DeliveryNote {
+            number: "BL-2026-00001".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            sender: Default::default(),
+            client: Client {
+                name: String::new(),
+                ..client()
+            },
+            items: vec![item(dec!(1), dec!(1)), item(dec!(-2), dec!(1))],
+            quote: String::new(),
+        };
+        assert_eq!(
+            problems(note.validate()),
+            vec![
+                ("client.name".to_string(), Problem::Required),
+                ("items[1].quantity".to_string(), Problem::NotPositive),
+            ]
+        );
+    }
+}
@@ -0,0 +1,738 @@
+//! HTTP API over the engine, so that other tools can create delivery notes
+//! without the graphical interface. The schema of the API is served at
+//! `/openapi.json`.
+
+use std::{
+    fmt,
+    io::Read,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{Local, NaiveDate};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::{
+    cli::to_json,
+    engine::Engine,
+    error::Error,
+    generators::{delivery_note::DeliveryNote, formats::Format, Report},
+    i18n::Language,
+    models::{
+        amount::parse_decimal, client::Client, country::Country, delivery_note, item::Item,
+        product::Product, unit::Unit,
+    },
+};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+const OPENAPI: &str = include_str!("openapi.json");
+/// Larger request bodies are refused.
+const MAX_BODY: u64 = 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewClient {
+    id: String,
+    name: String,
+    #[serde(default)]
+    address1: String,
+    #[serde(default)]
+    address2: String,
+    #[serde(default)]
+    postal_code: String,
+    #[serde(default)]
+    city: String,
+    country: Option<String>,
+    language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewProduct {
+    id: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    reference: String,
+    unit: Option<String>,
+    vat_rate: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewItem {
+    /// Reference or id of the product.
+    product: String,
+    quantity: Value,
+    price: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewNote {
+    client: String,
+    sender: Option<String>,
+    items: Vec<NewItem>,
+    date: Option<String>,
+    format: Option<String>,
+}
+
+/// A response before it is sent.
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn json(status: u16, value: Value) -> Self {
+        return Reply {
+            status,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: value.to_string().into_bytes(),
+        };
+    }
+
+    fn empty() -> Self {
+        return Reply {
+            status: 204,
+            content_type: "text/plain",
+            headers: Vec::new(),
+            body: Vec::new(),
+        };
+    }
+
+    fn error(status: u16, message: impl fmt::Display) -> Self {
+        return Reply::json(status, json!({ "error": message.to_string() }));
+    }
+
+    fn not_allowed(allowed: &str) -> Self {
+        let mut reply = Reply::error(405, "Method not allowed");
+        reply.headers.push(("Allow", allowed.to_string()));
+        return reply;
+    }
+
+    fn document(status: u16, format: Format, body: Vec<u8>) -> Self {
+        return Reply {
+            status,
+            content_type: format.media_type(),
+            headers: Vec::new(),
+            body,
+        };
+    }
+}
+
+impl From<Error> for Reply {
+    fn from(error: Error) -> Self {
+        let status = match error {
+            Error::NotFound { .. } => 404,
+            Error::DuplicateId { .. }
+            | Error::InUse { .. }
+            | Error::AlreadyInvoiced { .. }
+            | Error::AlreadyConverted { .. } => 409,
+            Error::SenderNotChosen { .. } | Error::Invalid { .. } => 422,
+            _ => {
+                eprintln!("Request failed: {}", error);
+                500
+            }
+        };
+        return Reply::error(status, error);
+    }
+}
+
+/// Decodes the `%XX` escapes of a path segment.
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let escaped = segment
+            .get(idx + 1..idx + 3)
+            .filter(|_| bytes[idx] == b'%')
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                idx += 3;
+            }
+            None => {
+                decoded.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+    return String::from_utf8_lossy(&decoded).into_owned();
+}
+
+/// The value of the parameter `name` of the query string.
+fn query_value<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    return query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value);
+}
+
+/// The format named by its extension, PDF when none is given.
+fn parse_format(name: Option<&str>) -> Result<Format, Reply> {
+    let Some(name) = name else {
+        return Ok(Format::default());
+    };
+    return Format::from_extension(name)
+        .ok_or_else(|| Reply::error(422, format!("Unknown format \"{}\"", name)));
+}
+
+/// A quantity or a price, sent as a number or as a string such as `12,50`
+/// or `3 kg`, which the engine validates.
+fn decimal_text(name: &str, value: &Value) -> Result<String, Reply> {
+    return match value {
+        Value::Number(number) => Ok(number.to_string()),
+        Value::String(text) => Ok(text.clone()),
+        _ => Err(Reply::error(422, format!("Invalid {}: {}", name, value))),
+    };
+}
+
+fn parse_body<'a, T: Deserialize<'a>>(body: &'a [u8]) -> Result<T, Reply> {
+    return serde_json::from_slice(body)
+        .map_err(|e| Reply::error(400, format!("Invalid body: {}", e)));
+}
+
+fn note_json(note: &delivery_note::DeliveryNote) -> Value {
+    let items = note
+        .items
+        .iter()
+        .map(|item| {
+            json!({
+                "product": item.product().id(),
+                "reference": item.product().reference(),
+                "description": item.product().description(),
+                "quantity": item.quantity().to_string(),
+                "unit": item.product().unit().to_string(),
+                "price": item.price().to_string(),
+            })
+        })
+        .collect::<Vec<Value>>();
+    return json!({
+        "number": note.number,
+        "date": note.date.to_string(),
+        "client": note.client.id,
+        "sender": note.sender.id,
+        "quote": note.quote,
+        "items": items,
+    });
+}
+
+fn render(note: delivery_note::DeliveryNote, format: Format) -> Result<Vec<u8>, Error> {
+    let report = DeliveryNote::new(note);
+    report.validate()?;
+    return format.render(&report.document()?);
+}
+
+/// Compares the tokens in a time that does not depend on where they differ.
+fn same_token(sent: &[u8], token: &[u8]) -> bool {
+    if sent.len() != token.len() {
+        return false;
+    }
+    return sent
+        .iter()
+        .zip(token)
+        .fold(0, |difference, (a, b)| difference | (a ^ b))
+        == 0;
+}
+
+/// Serves the engine over HTTP, one request at a time.
+pub struct ApiServer {
+    http: tiny_http::Server,
+    engine: Arc<Mutex<Engine>>,
+    token: String,
+}
+
+impl ApiServer {
+    /// Listens on `address`. Requests must send `token` as a bearer token.
+    pub fn bind(address: &str, engine: Arc<Mutex<Engine>>, token: String) -> Result<Self, Error> {
+        let http = tiny_http::Server::http(address).map_err(|e| Error::Listen {
+            address: address.to_string(),
+            message: e.to_string(),
+        })?;
+        return Ok(ApiServer {
+            http,
+            engine,
+            token,
+        });
+    }
+
+    /// The address listened on, whose port is chosen by the system when
+    /// bound to port 0.
+    pub fn address(&self) -> Option<SocketAddr> {
+        return self.http.server_addr().to_ip();
+    }
+
+    /// Answers the requests until the process is stopped.
+    pub fn run(&self) {
+        for request in self.http.incoming_requests() {
+            self.respond(request);
+        }
+    }
+
+    /// Makes `run` return, for the tests to shut the server down.
+    #[cfg(test)]
+    pub fn stop(&self) {
+        self.http.unblock();
+    }
+
+    fn respond(&self, mut request: Request) {
+        let reply = self.reply(&mut request);
+        let mut response = Response::from_data(reply.body).with_status_code(reply.status);
+        let headers = [("Content-Type", reply.content_type.to_string())]
+            .into_iter()
+            .chain(reply.headers);
+        for (name, value) in headers {
+            if let Ok(header) = Header::from_bytes(name, value) {
+                response.add_header(header);
+            }
+        }
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to answer a request: {}", e);
+        }
+    }
+
+    fn authorized(&self, request: &Request) -> bool {
+        return request
+            .headers()
+            .iter()
+            .filter(|header| header.field.equiv("Authorization"))
+            .filter_map(|header| header.value.as_str().strip_prefix("Bearer "))
+            .any(|sent| same_token(sent.trim().as_bytes(), self.token.as_bytes()));
+    }
+
+    fn reply(&self, request: &mut Request) -> Reply {
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        if path == "/openapi.json" {
+            return match request.method() {
+                Method::Get => Reply {
+                    status: 200,
+                    content_type: "application/json",
+                    headers: Vec::new(),
+                    body: OPENAPI.as_bytes().to_vec(),
+                },
+                _ => Reply::not_allowed("GET"),
+            };
+        }
+        if !self.authorized(request) {
+            let mut reply = Reply::error(401, "Missing or wrong token");
+            reply
+                .headers
+                .push(("WWW-Authenticate", "Bearer".to_string()));
+            return reply;
+        }
+        let mut body = Vec::new();
+        let read = request
+            .as_reader()
+            .take(MAX_BODY + 1)
+            .read_to_end(&mut body);
+        if let Err(e) = read {
+            return Reply::error(400, format!("Cannot read the body: {}", e));
+        }
+        if body.len() as u64 > MAX_BODY {
+            return Reply::error(413, "The body is too large");
+        }
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(percent_decode)
+            .collect::<Vec<String>>();
+        let segments = segments.iter().map(String::as_str).collect::<Vec<&str>>();
+        let result = match segments.as_slice() {
+            ["clients"] => match request.method() {
+                Method::Get => self.list_clients(),
+                Method::Post => self.create_client(&body),
+                _ => Err(Reply::not_allowed("GET, POST")),
+            },
+            ["clients", id] => match request.method() {
+                Method::Get => self.show_client(id),
+                Method::Delete => self.delete_client(id),
+                _ => Err(Reply::not_allowed("GET, DELETE")),
+            },
+            ["products"] => match request.method() {
+                Method::Get => self.list_products(),
+                Method::Post => self.create_product(&body),
+                _ => Err(Reply::not_allowed("GET, POST")),
+            },
+            ["products", id] => match request.method() {
+                Method::Get => self.show_product(id),
+                Method::Delete => self.delete_product(id),
+                _ => Err(Reply::not_allowed("GET, DELETE")),
+            },
+            ["notes"] => match request.method() {
+                Method::Get => self.list_notes(),
+                Method::Post => self.create_note(&body),
+                _ => Err(Reply::not_allowed("GET, POST")),
+            },
+            ["notes", number] => match request.method() {
+                Method::Get => self.print_note(number, query_value(query, "format")),
+                _ => Err(Reply::not_allowed("GET")),
+            },
+            _ => Err(Reply::error(404, format!("No resource at {}", path))),
+        };
+        return result.unwrap_or_else(|reply| reply);
+    }
+
+    fn list_clients(&self) -> Result<Reply, Reply> {
+        let engine = self.engine.lock().unwrap();
+        let clients = engine.get_clients().iter().map(to_json).collect();
+        return Ok(Reply::json(200, Value::Array(clients)));
+    }
+
+    fn create_client(&self, body: &[u8]) -> Result<Reply, Reply> {
+        let new = parse_body::<NewClient>(body)?;
+        let language = match new.language {
+            Some(code) => code.parse::<Language>().map_err(|e| Reply::error(422, e))?,
+            None => Language::default(),
+        };
+        let country = match new.country {
+            Some(code) => code.parse::<Country>().map_err(|e| Reply::error(422, e))?,
+            None => Country::default(),
+        };
+        let client = Client {
+            id: new.id,
+            name: new.name,
+            address1: new.address1,
+            address2: new.address2,
+            postal_code: new.postal_code,
+            city: new.city,
+            country,
+            language,
+        };
+        let value = to_json(&client);
+        self.engine.lock().unwrap().create_client(client)?;
+        return Ok(Reply::json(201, value));
+    }
+
+    fn show_client(&self, id: &str) -> Result<Reply, Reply> {
+        let engine = self.engine.lock().unwrap();
+        return Ok(Reply::json(200, to_json(engine.find_client(id)?)));
+    }
+
+    fn delete_client(&self, id: &str) -> Result<Reply, Reply> {
+        self.engine.lock().unwrap().remove_client(id)?;
+        return Ok(Reply::empty());
+    }
+
+    fn list_products(&self) -> Result<Reply, Reply> {
+        let engine = self.engine.lock().unwrap();
+        let products = engine.get_products().iter().map(to_json).collect();
+        return Ok(Reply::json(200, Value::Array(products)));
+    }
+
+    fn create_product(&self, body: &[u8]) -> Result<Reply, Reply> {
+        let new = parse_body::<NewProduct>(body)?;
+        let mut product = Product::new(&new.id, &new.description, &new.reference);
+        if let Some(code) = new.unit {
+            *product.unit_mut() = code.parse::<Unit>().map_err(|e| Reply::error(422, e))?;
+        }
+        if let Some(rate) = &new.vat_rate {
+            let text = decimal_text("vat_rate", rate)?;
+            *product.vat_rate_mut() = parse_decimal(&text)
+                .ok_or_else(|| Reply::error(422, format!("Invalid vat_rate: {}", text)))?;
+        }
+        let value = to_json(&product);
+        self.engine.lock().unwrap().create_product(product)?;
+        return Ok(Reply::json(201, value));
+    }
+
+    fn show_product(&self, reference: &str) -> Result<Reply, Reply> {
+        let engine = self.engine.lock().unwrap();
+        return Ok(Reply::json(200, to_json(engine.find_product(reference)?)));
+    }
+
+    fn delete_product(&self, id: &str) -> Result<Reply, Reply> {
+        self.engine.lock().unwrap().remove_product(id)?;
+        return Ok(Reply::empty());
+    }
+
+    fn list_notes(&self) -> Result<Reply, Reply> {
+        let engine = self.engine.lock().unwrap();
+        let notes = engine.get_delivery_notes().iter().map(note_json).collect();
+        return Ok(Reply::json(200, Value::Array(notes)));
+    }
+
+    /// Creates the note and answers with its document.
+    fn create_note(&self, body: &[u8]) -> Result<Reply, Reply> {
+        let new = parse_body::<NewNote>(body)?;
+        let format = parse_format(new.format.as_deref())?;
+        let date = match &new.date {
+            Some(date) => date
+                .parse::<NaiveDate>()
+                .map_err(|e| Reply::error(422, format!("Invalid date \"{}\": {}", date, e)))?,
+            None => Local::now().date_naive(),
+        };
+        if new.items.is_empty() {
+            return Err(Reply::error(422, "A delivery note needs items"));
+        }
+        let mut engine = self.engine.lock().unwrap();
+        let client = engine.find_client(&new.client)?.clone();
+        let sender = engine.find_sender(new.sender.as_deref())?.clone();
+        let mut items = Vec::new();
+        for item in &new.items {
+            let price = match &item.price {
+                Some(price) => decimal_text("price", price)?,
+                None => "0".to_string(),
+            };
+            let quantity = decimal_text("quantity", &item.quantity)?;
+            let product = engine.find_product(&item.product)?.clone();
+            let item =
+                Item::parse(product, &price, &quantity).map_err(|errors| Error::Invalid {
+                    kind: "item",
+                    id: item.product.clone(),
+                    errors,
+                })?;
+            items.push(item);
+        }
+        let note = engine.create_delivery_note(sender, client, &items, date)?;
+        drop(engine);
+        let number = note.number.clone();
+        // The note is saved even when it cannot be rendered, so the number is
+        // sent either way for the caller to fetch it instead of creating it again
+        let mut reply = match render(note, format) {
+            Ok(document) => Reply::document(201, format, document),
+            Err(e) => Reply::from(e),
+        };
+        reply
+            .headers
+            .push(("Location", format!("/notes/{}", number)));
+        reply.headers.push(("X-Note-Number", number));
+        return Ok(reply);
+    }
+
+    fn print_note(&self, number: &str, format: Option<&str>) -> Result<Reply, Reply> {
+        let format = parse_format(format)?;
+        let note = self
+            .engine
+            .lock()
+            .unwrap()
+            .find_delivery_note(number)?
+            .clone();
+        return Ok(Reply::document(200, format, render(note, format)?));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::sender::Sender, storage::StorageType};
+    use std::{
+        fs,
+        io::Write,
+        net::TcpStream,
+        path::Path,
+        thread::{self, JoinHandle},
+    };
+
+    const TOKEN: &str = "secret";
+
+    struct TestServer {
+        server: Arc<ApiServer>,
+        address: SocketAddr,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            self.server.stop();
+            if let Some(thread) = self.thread.take() {
+                thread.join().unwrap();
+            }
+        }
+    }
+
+    /// Serves an empty file storage on a free port of localhost.
+    fn start(folder: &'static str) -> TestServer {
+        let path = Path::new(folder);
+        if path.exists() {
+            fs::remove_dir_all(path).unwrap();
+        }
+        fs::create_dir(path).unwrap();
+        let mut engine = Engine::new(StorageType::FileStorage(folder)).unwrap();
+        engine
+            .save_sender(Sender {
+                id: "main".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        // Its logo cannot be read, so its PDF documents cannot be rendered
+        engine
+            .save_sender(Sender {
+                id: "missing-logo".to_string(),
+                logo_path: format!("{}/missing.png", folder),
+                ..Default::default()
+            })
+            .unwrap();
+        let engine = Arc::new(Mutex::new(engine));
+        let server = Arc::new(ApiServer::bind("127.0.0.1:0", engine, TOKEN.to_string()).unwrap());
+        let address = server.address().unwrap();
+        let running = server.clone();
+        let thread = thread::spawn(move || running.run());
+        TestServer {
+            server,
+            address,
+            thread: Some(thread),
+        }
+    }
+
+    /// Sends a request and returns the status, the headers and the body of
+    /// the response. HTTP/1.0 keeps large responses from being chunked.
+    fn send(
+        server: &TestServer,
+        method: &str,
+        path: &str,
+        token: Option<&str>,
+        body: &str,
+    ) -> (u16, String, Vec<u8>) {
+        let mut stream = TcpStream::connect(server.address).unwrap();
+        let authorization = token
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default();
+        write!(
+            stream,
+            "{} {} HTTP/1.0\r\nHost: localhost\r\n{}Content-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            authorization,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        let end = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let head = String::from_utf8(response[..end].to_vec()).unwrap();
+        let status = head[9..12].parse().unwrap();
+        (status, head, response[end + 4..].to_vec())
+    }
+
+    fn send_json(server: &TestServer, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let (status, _, body) = send(server, method, path, Some(TOKEN), body);
+        let value = match body.is_empty() {
+            true => Value::Null,
+            false => serde_json::from_slice(&body).unwrap(),
+        };
+        (status, value)
+    }
+
+    #[test]
+    fn test_authentication() {
+        let server = start("test_server_authentication");
+        assert_eq!(send(&server, "GET", "/clients", None, "").0, 401);
+        let (status, head, _) = send(&server, "GET", "/clients", Some("wrong"), "");
+        assert_eq!(status, 401);
+        assert!(head.contains("WWW-Authenticate: Bearer"), "{}", head);
+        assert_eq!(send(&server, "GET", "/clients", Some(TOKEN), "").0, 200);
+
+        // The schema is public and lists every resource
+        let (status, _, body) = send(&server, "GET", "/openapi.json", None, "");
+        assert_eq!(status, 200);
+        let schema: Value = serde_json::from_slice(&body).unwrap();
+        for path in [
+            "/clients",
+            "/clients/{id}",
+            "/products",
+            "/products/{id}",
+            "/notes",
+            "/notes/{number}",
+        ] {
+            assert!(schema["paths"][path].is_object(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_clients_and_products() {
+        let server = start("test_server_clients");
+        let invalid = r#"{"id": "1", "name": "Alice", "city": "Lyon", "postal_code": "690"}"#;
+        assert_eq!(send_json(&server, "POST", "/clients", invalid).0, 422);
+        let client = r#"{"id": "1", "name": "Alice", "address1": "1 place Bellecour",
+            "postal_code": "69002", "city": "Lyon", "language": "de"}"#;
+        let (status, created) = send_json(&server, "POST", "/clients", client);
+        assert_eq!(status, 201);
+        assert_eq!(created["language"], "de");
+        assert_eq!(send_json(&server, "POST", "/clients", client).0, 409);
+        assert_eq!(send_json(&server, "POST", "/clients", "{").0, 400);
+        assert_eq!(send_json(&server, "PUT", "/clients", client).0, 405);
+
+        let (status, clients) = send_json(&server, "GET", "/clients", "");
+        assert_eq!(status, 200);
+        assert_eq!(clients[0]["city"], "Lyon");
+        assert_eq!(send_json(&server, "DELETE", "/clients/1", "").0, 204);
+        assert_eq!(send_json(&server, "GET", "/clients/1", "").0, 404);
+
+        let product = r#"{"id": "1", "description": "Chaise", "reference": "CH 01"}"#;
+        assert_eq!(send_json(&server, "POST", "/products", product).0, 201);
+        let (status, product) = send_json(&server, "GET", "/products/CH%2001", "");
+        assert_eq!(status, 200);
+        assert_eq!(product["description"], "Chaise");
+        assert_eq!(product["vat_rate"], "20");
+        let book = r#"{"id": "2", "description": "Livre", "vat_rate": "5,5"}"#;
+        let (status, book) = send_json(&server, "POST", "/products", book);
+        assert_eq!(status, 201);
+        assert_eq!(book["vat_rate"], "5.5");
+        let invalid = r#"{"id": "3", "vat_rate": "cinq"}"#;
+        assert_eq!(send_json(&server, "POST", "/products", invalid).0, 422);
+        assert_eq!(send_json(&server, "GET", "/unknown", "").0, 404);
+    }
+
+    #[test]
+    fn test_create_note() {
+        let server = start("test_server_notes");
+        send_json(
+            &server,
+            "POST",
+            "/clients",
+            r#"{"id": "1", "name": "Alice", "address1": "1 place Bellecour",
+                "postal_code": "69002", "city": "Lyon"}"#,
+        );
+        let product = r#"{"id": "1", "description": "Chaise", "reference": "CH-01"}"#;
+        send_json(&server, "POST", "/products", product);
+
+        let note = r#"{"client": "1", "sender": "main", "items": [{"product": "CH-01", "quantity": 3, "price": "12,50"}], "date": "2026-05-02"}"#;
+        let (status, head, body) = send(&server, "POST", "/notes", Some(TOKEN), note);
+        assert_eq!(status, 201);
+        assert!(head.contains("Content-Type: application/pdf"), "{}", head);
+        assert!(head.contains("X-Note-Number: BL-2026-00001"), "{}", head);
+        assert!(body.starts_with(b"%PDF"));
+
+        // A note that cannot be rendered is saved, and its number is sent
+        let broken = r#"{"client": "1", "sender": "missing-logo", "items": [{"product": "CH-01", "quantity": 1}]}"#;
+        let (status, head, _) = send(&server, "POST", "/notes", Some(TOKEN), broken);
+        assert_eq!(status, 500);
+        assert!(head.contains("Location: /notes/BL-"), "{}", head);
+        let number = head
+            .lines()
+            .find_map(|line| line.strip_prefix("X-Note-Number: "))
+            .unwrap()
+            .to_string();
+        let path = format!("/notes/{}?format=txt", number);
+        assert_eq!(send(&server, "GET", &path, Some(TOKEN), "").0, 200);
+
+        let invalid = r#"{"client": "1", "sender": "main", "items": [{"product": "CH-01", "quantity": "trois"}]}"#;
+        assert_eq!(send_json(&server, "POST", "/notes", invalid).0, 422);
+        let unknown = r#"{"client": "2", "items": [{"product": "CH-01", "quantity": 1}]}"#;
+        assert_eq!(send_json(&server, "POST", "/notes", unknown).0, 404);
+
+        let (_, notes) = send_json(&server, "GET", "/notes", "");
+        assert_eq!(notes.as_array().unwrap().len(), 2);
+        assert_eq!(notes[0]["items"][0]["quantity"], "3");
+        let (status, _, text) = send(
+            &server,
+            "GET",
+            "/notes/BL-2026-00001?format=txt",
+            Some(TOKEN),
+            "",
+        );
+        assert_eq!(status, 200);
+        assert!(String::from_utf8(text).unwrap().contains("Chaise"));
+    }
+}
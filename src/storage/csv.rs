@@ -0,0 +1,203 @@
+use super::StorageError;
+
+/// Formats one record, quoting the values that contain the delimiter, a quote
+/// or a line break (RFC 4180, with a configurable delimiter).
+pub fn write_record(values: &[String], delimiter: char) -> String {
+    values
+        .iter()
+        .map(|value| quote(value, delimiter))
+        .collect::<Vec<String>>()
+        .join(&delimiter.to_string())
+}
+
+fn quote(value: &str, delimiter: char) -> String {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// One parsed record, with the line it starts on.
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    pub line: usize,
+    pub values: Vec<String>,
+}
+
+/// Parses every record of `content`. Quoted values may contain the delimiter,
+/// doubled quotes and line breaks. A quote inside an unquoted value is kept as
+/// is, and so is a value starting with a quote whose closing quote is not
+/// followed by a delimiter or the end of the line, so files written before
+/// quoting was supported still load. Empty lines are skipped.
+pub fn parse_records(content: &str, delimiter: char) -> Result<Vec<Record>, StorageError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut value = String::new();
+    // Whether the current record has any content, to tell empty lines apart
+    // from records holding a single empty value.
+    let mut in_record = false;
+    let mut chars = content.chars().peekable();
+    let mut line = 1;
+    let mut record_line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            // A quote only opens a quoted value at the very start of a value
+            '"' if value.is_empty() => {
+                in_record = true;
+                let start_line = line;
+                // Where to read the value again from if it turns out not to be quoted
+                let unquoted = chars.clone();
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            value.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            value.push(c);
+                        }
+                        None => {
+                            return Err(StorageError::Parse {
+                                line: Some(start_line),
+                                message: "unterminated quoted value".to_string(),
+                            })
+                        }
+                    }
+                }
+                match chars.peek() {
+                    None | Some('\n') | Some('\r') => {}
+                    Some(c) if *c == delimiter => {}
+                    // Such as `"Big" box`, written before quoting was supported
+                    Some(_) => {
+                        chars = unquoted;
+                        line = start_line;
+                        value = "\"".to_string();
+                    }
+                }
+            }
+            c if c == delimiter => {
+                in_record = true;
+                record.push(std::mem::take(&mut value));
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                line += 1;
+                if in_record {
+                    record.push(std::mem::take(&mut value));
+                    records.push(Record {
+                        line: record_line,
+                        values: std::mem::take(&mut record),
+                    });
+                    in_record = false;
+                }
+                record_line = line;
+            }
+            c => {
+                in_record = true;
+                value.push(c);
+            }
+        }
+    }
+    if in_record {
+        record.push(value);
+        records.push(Record {
+            line: record_line,
+            values: record,
+        });
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_plain_values_are_not_quoted() {
+        assert_eq!(write_record(&record(&["1", "Alice"]), ';'), "1;Alice");
+    }
+
+    #[test]
+    fn test_special_values_are_quoted() {
+        assert_eq!(
+            write_record(&record(&["Bât. B; 2e étage", "12\" écran", "a\nb"]), ';'),
+            "\"Bât. B; 2e étage\";\"12\"\" écran\";\"a\nb\""
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let records = vec![
+            record(&["id", "address", "description"]),
+            record(&["1", "Bât. B; 2e étage", "first line\nsecond \"line\""]),
+            record(&["2", "", "\r\n;\""]),
+        ];
+        let content = records
+            .iter()
+            .map(|r| write_record(r, ';') + "\n")
+            .collect::<String>();
+        let parsed = parse_records(&content, ';').unwrap();
+        assert_eq!(
+            parsed.iter().map(|r| r.line).collect::<Vec<usize>>(),
+            vec![1, 2, 4]
+        );
+        assert_eq!(
+            parsed.into_iter().map(|r| r.values).collect::<Vec<_>>(),
+            records
+        );
+    }
+
+    #[test]
+    fn test_parse_legacy_unquoted_file() {
+        let content = "id;name\r\n1;12\" screen\r\n\r\n2;\n";
+        assert_eq!(
+            parse_records(content, ';').unwrap(),
+            vec![
+                Record {
+                    line: 1,
+                    values: record(&["id", "name"])
+                },
+                Record {
+                    line: 2,
+                    values: record(&["1", "12\" screen"])
+                },
+                Record {
+                    line: 4,
+                    values: record(&["2", ""])
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_legacy_value_starting_with_quote() {
+        let content = "id;description;reference\n1;\"Big\" box;CH-01\n2;\"a;b\";\"\"x\n";
+        assert_eq!(
+            parse_records(content, ';')
+                .unwrap()
+                .into_iter()
+                .map(|r| r.values)
+                .collect::<Vec<_>>(),
+            vec![
+                record(&["id", "description", "reference"]),
+                record(&["1", "\"Big\" box", "CH-01"]),
+                record(&["2", "a;b", "\"\"x"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_unterminated_quote() {
+        assert!(parse_records("id;name\n1;\"Alice\n", ';').is_err());
+    }
+}
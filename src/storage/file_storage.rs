@@ -1,10 +1,16 @@
 use std::{
-    fs::{File, OpenOptions},
-    io::{BufRead, BufReader, Seek, SeekFrom, Write},
-    path::Path,
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
 };
 
-use super::Storage;
+use super::{
+    csv::{self, Record},
+    Savable, Storage, StorageError,
+};
+
+const DELIMITER: char = ';';
 
 pub struct FileStorage {
     folder: String,
@@ -16,176 +22,256 @@ impl FileStorage {
             folder: folder.to_owned(),
         }
     }
+
+    fn file_path(&self, name: &str) -> PathBuf {
+        Path::new(&self.folder).join(format!("{}.csv", name))
+    }
+}
+
+/// Reads every record of a file, header included. A missing file has no records.
+fn read_records(path: &Path) -> Result<Vec<Record>, StorageError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| StorageError::io(path, e))?;
+    csv::parse_records(&content, DELIMITER)
+}
+
+/// Replaces the content of a file without ever leaving it half written.
+fn write_records(path: &Path, records: &[Vec<String>]) -> Result<(), StorageError> {
+    write_atomic(path, |file| {
+        let mut writer = BufWriter::new(file);
+        for record in records {
+            writer.write_all(csv::write_record(record, DELIMITER).as_bytes())?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    })
+}
+
+/// Writes a file through `write` into a temporary file of the same folder,
+/// syncs it to disk and renames it over `path`. The previous version of the
+/// file is kept as a `.bak`. If anything fails, `path` is left untouched.
+fn write_atomic<F>(path: &Path, write: F) -> Result<(), StorageError>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+{
+    let tmp_path = path.with_extension("csv.tmp");
+    let result = File::create(&tmp_path).and_then(|mut file| {
+        write(&mut file)?;
+        file.sync_all()
+    });
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(StorageError::io(&tmp_path, e));
+    }
+
+    if path.exists() {
+        let bak_path = path.with_extension("csv.bak");
+        fs::copy(path, &bak_path)
+            .and_then(|_| File::open(&bak_path)?.sync_all())
+            .map_err(|e| StorageError::io(&bak_path, e))?;
+    }
+    fs::rename(&tmp_path, path).map_err(|e| StorageError::io(path, e))?;
+    // Persist the rename itself. Directories cannot be opened on every
+    // platform, in which case the rename is left to the OS.
+    if let Some(folder) = path.parent() {
+        if let Ok(folder) = File::open(folder) {
+            let _ = folder.sync_all();
+        }
+    }
+    Ok(())
+}
+
+/// Reads the entities of a CSV file whose first line names the columns, in
+/// any order, such as a file exported by another application.
+pub fn load_file<S: Savable>(path: &Path) -> Result<Vec<S>, StorageError> {
+    Ok(load_file_lines(path)?
+        .into_iter()
+        .map(|(_, savable)| savable)
+        .collect())
+}
+
+/// Like `load_file`, with the line each entity starts on.
+pub fn load_file_lines<S: Savable>(path: &Path) -> Result<Vec<(usize, S)>, StorageError> {
+    let mut records = read_records(path)?.into_iter();
+    let headers = match records.next() {
+        Some(header) => header.values,
+        None => {
+            return Err(StorageError::Parse {
+                line: Some(1),
+                message: "file is empty".to_string(),
+            })
+        }
+    };
+    let mut savables = Vec::new();
+
+    for record in records {
+        let mut fields = Vec::new();
+        for (header, value) in headers.iter().zip(record.values) {
+            fields.push((header.clone(), value));
+        }
+        match S::from_fields(fields) {
+            Ok(savable) => savables.push((record.line, savable)),
+            Err(e) => return Err(e.at_line(record.line)),
+        }
+    }
+
+    Ok(savables)
 }
 
 impl Storage for FileStorage {
-    fn init(&self) -> Result<(), String> {
+    fn init(&self) -> Result<(), StorageError> {
         let folder = Path::new(&self.folder);
         if folder.exists() {
             Ok(())
         } else {
-            Err("Folder does not exist".to_string())
+            Err(StorageError::MissingFolder(folder.to_owned()))
         }
     }
 
-    fn save<S: super::Savable>(&self, savable: &S) -> Result<(), String> {
-        let fields = savable.to_fields();
-        if fields.is_empty() {
-            return Err("Fields are empty".to_string());
-        }
-        let mut id = None;
-        for (idx, field) in fields.iter().enumerate() {
-            if field.0 == "id" {
-                id = Some((idx, field.1.clone()));
-                break;
+    fn save<S: Savable>(&self, savable: &S) -> Result<(), StorageError> {
+        self.save_all(std::slice::from_ref(savable))
+    }
+
+    fn save_all<S: Savable>(&self, savables: &[S]) -> Result<(), StorageError> {
+        let id_column = S::id_column();
+        let mut header = Vec::new();
+        let mut rows = Vec::new();
+        for savable in savables {
+            let fields = savable.to_fields();
+            if fields.is_empty() {
+                return Err(StorageError::EmptyFields(S::savable_name()));
+            }
+            if !fields.iter().any(|field| field.0 == id_column) {
+                return Err(StorageError::MissingId(S::savable_name()));
+            }
+            for (column, _) in &fields {
+                if !header.contains(column) {
+                    header.push(column.clone());
+                }
             }
+            rows.push(fields.into_iter().collect::<HashMap<String, String>>());
         }
-        if id.is_none() {
-            return Err("Cannot save because there is no \"ID\" in the Savable struct".to_string());
+        if rows.is_empty() {
+            return Ok(());
         }
-        let id = id.unwrap();
-        let file_name = S::savable_name();
-        let file_path_str = format!("{}/{}.csv", self.folder, file_name);
-        let path = Path::new(&file_path_str);
-
-        // Check if file exists
-        let file_exists = path.exists();
-        let mut file = match OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .open(&path)
-        {
-            Ok(f) => f,
-            Err(e) => return Err(e.to_string()),
-        };
 
-        let mut lines = BufReader::new(&file).lines();
-        let header_line;
-        let mut data_lines = Vec::new();
+        let path = self.file_path(&S::savable_name());
+        let mut records = read_records(&path)?.into_iter();
+        let file_header = records
+            .next()
+            .map(|record| record.values)
+            .unwrap_or_default();
+        // Columns only found in the file are kept after the ones of the entity
+        for column in &file_header {
+            if !header.contains(column) {
+                header.push(column.clone());
+            }
+        }
 
-        if file_exists {
-            header_line = match lines.next() {
-                Some(Ok(line)) => line,
-                Some(Err(e)) => return Err(e.to_string()),
-                None => {
-                    // File is empty, create a header
-                    fields
-                        .iter()
-                        .map(|field| field.0.clone())
-                        .collect::<Vec<String>>()
-                        .join(";")
-                }
+        let mut data_rows = Vec::new();
+        let mut positions = HashMap::new();
+        for record in records {
+            let row = file_header
+                .iter()
+                .cloned()
+                .zip(record.values)
+                .collect::<HashMap<String, String>>();
+            match row.get(&id_column) {
+                Some(id) => positions.insert(id.clone(), data_rows.len()),
+                None => return Err(StorageError::missing_field(&id_column).at_line(record.line)),
             };
-
-            let mut id_found = false;
-            while let Some(Ok(line)) = lines.next() {
-                let splitted = line.split(";").collect::<Vec<&str>>();
-                let curr_id = match splitted.get(id.0) {
-                    Some(id) => id.to_string(),
-                    None => return Err("DB not well formatted".to_string()),
-                };
-                if curr_id == id.1 {
-                    id_found = true;
-                    data_lines.push(
-                        fields
-                            .iter()
-                            .map(|field| field.1.clone())
-                            .collect::<Vec<String>>()
-                            .join(";"),
-                    );
-                } else {
-                    data_lines.push(line);
+            data_rows.push(row);
+        }
+        for row in rows {
+            match positions.get(&row[&id_column]) {
+                Some(idx) => data_rows[*idx].extend(row),
+                None => {
+                    positions.insert(row[&id_column].clone(), data_rows.len());
+                    data_rows.push(row);
                 }
             }
-
-            if !id_found {
-                data_lines.push(
-                    fields
-                        .iter()
-                        .map(|field| field.1.clone())
-                        .collect::<Vec<String>>()
-                        .join(";"),
-                );
-            }
-
-            // Rewind and write
-            file.set_len(0).map_err(|e| e.to_string())?;
-            file.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
-            file.write_all(header_line.as_bytes())
-                .map_err(|e| e.to_string())?;
-            file.write_all(b"\n").map_err(|e| e.to_string())?;
-            for line in data_lines {
-                file.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
-                file.write_all(b"\n").map_err(|e| e.to_string())?;
-            }
-        } else {
-            // File does not exist, create it with header and data
-            header_line = fields
-                .iter()
-                .map(|field| field.0.clone())
-                .collect::<Vec<String>>()
-                .join(";");
-            let data_line = fields
-                .iter()
-                .map(|field| field.1.clone())
-                .collect::<Vec<String>>()
-                .join(";");
-
-            file.write_all(header_line.as_bytes())
-                .map_err(|e| e.to_string())?;
-            file.write_all(b"\n").map_err(|e| e.to_string())?;
-            file.write_all(data_line.as_bytes())
-                .map_err(|e| e.to_string())?;
-            file.write_all(b"\n").map_err(|e| e.to_string())?;
         }
 
-        Ok(())
+        let mut data_records = vec![header.clone()];
+        for mut row in data_rows {
+            data_records.push(
+                header
+                    .iter()
+                    .map(|column| row.remove(column).unwrap_or_default())
+                    .collect(),
+            );
+        }
+        write_records(&path, &data_records)
     }
 
-    fn load<S: super::Savable>(&self) -> Result<Vec<S>, String> {
-        let file_name = S::savable_name();
-        let file_path_str = format!("{}/{}.csv", self.folder, file_name);
-        let path = Path::new(&file_path_str);
+    fn delete<S: Savable>(&self, id: &str) -> Result<(), StorageError> {
+        let path = self.file_path(&S::savable_name());
+        let mut records = read_records(&path)?
+            .into_iter()
+            .map(|record| record.values)
+            .collect::<Vec<Vec<String>>>();
+        if records.is_empty() {
+            return Ok(());
+        }
+        let id_column = S::id_column();
+        let id_idx = match records[0].iter().position(|header| *header == id_column) {
+            Some(idx) => idx,
+            None => return Err(StorageError::missing_field(&id_column).at_line(1)),
+        };
+        let count = records.len();
+        records.retain(|record| record.get(id_idx).map(|curr_id| curr_id.as_str()) != Some(id));
+        if records.len() == count {
+            return Ok(());
+        }
+        write_records(&path, &records)
+    }
 
+    fn load<S: Savable>(&self) -> Result<Vec<S>, StorageError> {
+        let path = self.file_path(&S::savable_name());
         if !path.exists() {
             return Ok(Vec::new());
         }
+        load_file(&path)
+    }
 
-        let file = match File::open(&path) {
-            Ok(f) => f,
-            Err(e) => return Err(e.to_string()),
-        };
-
-        let mut reader = BufReader::new(file);
-        let mut lines = reader.lines();
-
-        let header_line = match lines.next() {
-            Some(Ok(line)) => line,
-            Some(Err(e)) => return Err(e.to_string()),
-            None => return Err("File is empty".to_string()),
+    fn load_fields(&self, name: &str) -> Result<Vec<Vec<(String, String)>>, StorageError> {
+        let mut records = read_records(&self.file_path(name))?.into_iter();
+        let headers = match records.next() {
+            Some(header) => header.values,
+            None => return Ok(Vec::new()),
         };
+        Ok(records
+            .map(|record| headers.iter().cloned().zip(record.values).collect())
+            .collect())
+    }
 
-        let headers: Vec<String> = header_line.split(";").map(|s| s.to_string()).collect();
-        let mut savables = Vec::new();
-
-        for line in lines {
-            let line = match line {
-                Ok(l) => l,
-                Err(e) => return Err(e.to_string()),
-            };
-            let values: Vec<String> = line.split(";").map(|s| s.to_string()).collect();
-            let mut fields = Vec::new();
-            for (header, value) in headers.iter().zip(values.iter()) {
-                fields.push((header.clone(), value.clone()));
-            }
-            match S::from_fields(fields) {
-                Ok(savable) => savables.push(savable),
-                Err(e) => return Err(e),
+    fn replace_fields(
+        &self,
+        name: &str,
+        id_column: &str,
+        rows: &[Vec<(String, String)>],
+    ) -> Result<(), StorageError> {
+        let mut header = vec![id_column.to_owned()];
+        for fields in rows {
+            for (column, _) in fields {
+                if !header.contains(column) {
+                    header.push(column.clone());
+                }
             }
         }
-
-        Ok(savables)
+        let mut records = vec![header.clone()];
+        for fields in rows {
+            let mut row = fields.iter().cloned().collect::<HashMap<String, String>>();
+            records.push(
+                header
+                    .iter()
+                    .map(|column| row.remove(column).unwrap_or_default())
+                    .collect(),
+            );
+        }
+        write_records(&self.file_path(name), &records)
     }
 }
 
@@ -211,7 +297,7 @@ mod tests {
             ]
         }
 
-        fn from_fields(fields: Vec<(String, String)>) -> Result<Self, String> {
+        fn from_fields(fields: Vec<(String, String)>) -> Result<Self, StorageError> {
             let mut id = None;
             let mut name = None;
 
@@ -223,11 +309,10 @@ mod tests {
                 }
             }
 
-            if let (Some(id), Some(name)) = (id, name) {
-                Ok(User { id, name })
-            } else {
-                Err("Missing fields".to_string())
-            }
+            Ok(User {
+                id: id.ok_or_else(|| StorageError::missing_field("id"))?,
+                name: name.ok_or_else(|| StorageError::missing_field("name"))?,
+            })
         }
 
         fn savable_name() -> String {
@@ -336,4 +421,188 @@ mod tests {
             name: "Bob".to_string()
         }));
     }
+
+    #[test]
+    fn test_save_special_characters() {
+        let folder = "test_storage_special_characters";
+        setup_test_folder(folder);
+        let storage = FileStorage::new(folder);
+        let user1 = User {
+            id: "1".to_string(),
+            name: "Bât. B; 2e \"étage\"\nporte 3".to_string(),
+        };
+        let user2 = User {
+            id: "2".to_string(),
+            name: "Bob".to_string(),
+        };
+
+        assert!(storage.save(&user1).is_ok());
+        assert!(storage.save(&user2).is_ok());
+
+        let loaded_users = storage.load::<User>().unwrap();
+        assert_eq!(loaded_users, vec![user1, user2]);
+    }
+
+    #[test]
+    fn test_load_unquoted_file() {
+        let folder = "test_storage_unquoted";
+        setup_test_folder(folder);
+        fs::write(
+            format!("{}/user.csv", folder),
+            "id;name\n1;Alice\n2;12\" Bob\n",
+        )
+        .unwrap();
+        let storage = FileStorage::new(folder);
+
+        let loaded_users = storage.load::<User>().unwrap();
+        assert_eq!(
+            loaded_users,
+            vec![
+                User {
+                    id: "1".to_string(),
+                    name: "Alice".to_string()
+                },
+                User {
+                    id: "2".to_string(),
+                    name: "12\" Bob".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delete_entry() {
+        let folder = "test_storage_delete";
+        setup_test_folder(folder);
+        let storage = FileStorage::new(folder);
+        let user1 = User {
+            id: "1".to_string(),
+            name: "Alice".to_string(),
+        };
+        let user2 = User {
+            id: "2".to_string(),
+            name: "Bob".to_string(),
+        };
+
+        assert!(storage.save(&user1).is_ok());
+        assert!(storage.save(&user2).is_ok());
+        assert!(storage.delete::<User>("1").is_ok());
+        assert!(storage.delete::<User>("3").is_ok());
+
+        let loaded_users = storage.load::<User>().unwrap();
+        assert_eq!(loaded_users, vec![user2]);
+    }
+
+    #[test]
+    fn test_save_all_entries() {
+        let folder = "test_storage_save_all";
+        setup_test_folder(folder);
+        let storage = FileStorage::new(folder);
+        fs::write(
+            format!("{}/user.csv", folder),
+            "id;name;note\n1;Alice;vip\n2;Bob;\n",
+        )
+        .unwrap();
+        let users = vec![
+            User {
+                id: "2".to_string(),
+                name: "Robert".to_string(),
+            },
+            User {
+                id: "3".to_string(),
+                name: "Carol".to_string(),
+            },
+        ];
+
+        assert!(storage.save_all(&users).is_ok());
+
+        // Unknown columns are kept for the rows that already had them
+        assert_eq!(
+            fs::read_to_string(format!("{}/user.csv", folder)).unwrap(),
+            "id;name;note\n1;Alice;vip\n2;Robert;\n3;Carol;\n"
+        );
+        assert!(!Path::new(&format!("{}/user.csv.tmp", folder)).exists());
+    }
+
+    #[test]
+    fn test_save_keeps_backup() {
+        let folder = "test_storage_backup";
+        setup_test_folder(folder);
+        let storage = FileStorage::new(folder);
+        let user1 = User {
+            id: "1".to_string(),
+            name: "Alice".to_string(),
+        };
+        let user2 = User {
+            id: "2".to_string(),
+            name: "Bob".to_string(),
+        };
+
+        assert!(storage.save(&user1).is_ok());
+        assert!(!Path::new(&format!("{}/user.csv.bak", folder)).exists());
+        assert!(storage.save(&user2).is_ok());
+
+        assert_eq!(
+            fs::read_to_string(format!("{}/user.csv.bak", folder)).unwrap(),
+            "id;name\n1;Alice\n"
+        );
+    }
+
+    #[test]
+    fn test_failed_write_keeps_file() {
+        let folder = "test_storage_failed_write";
+        setup_test_folder(folder);
+        let path = Path::new(folder).join("user.csv");
+        fs::write(&path, "id;name\n1;Alice\n").unwrap();
+
+        let result = write_atomic(&path, |file| {
+            file.write_all(b"id;name\n1;Al")?;
+            Err(io::Error::other("disk full"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "id;name\n1;Alice\n");
+        assert!(!path.with_extension("csv.tmp").exists());
+        assert!(!path.with_extension("csv.bak").exists());
+    }
+
+    #[test]
+    fn test_leftover_temporary_file_is_ignored() {
+        let folder = "test_storage_leftover_tmp";
+        setup_test_folder(folder);
+        let storage = FileStorage::new(folder);
+        // A crash while writing the temporary file leaves it behind
+        fs::write(format!("{}/user.csv", folder), "id;name\n1;Alice\n").unwrap();
+        fs::write(format!("{}/user.csv.tmp", folder), "id;name\n1;Al").unwrap();
+
+        let user = User {
+            id: "2".to_string(),
+            name: "Bob".to_string(),
+        };
+        assert!(storage.save(&user).is_ok());
+
+        let loaded_users = storage.load::<User>().unwrap();
+        assert_eq!(loaded_users.len(), 2);
+        assert!(!Path::new(&format!("{}/user.csv.tmp", folder)).exists());
+    }
+
+    #[test]
+    fn test_load_reports_missing_field_line() {
+        let folder = "test_storage_missing_field";
+        setup_test_folder(folder);
+        fs::write(
+            format!("{}/user.csv", folder),
+            "id;name\n1;\"Alice\nLiddell\"\n2\n",
+        )
+        .unwrap();
+        let storage = FileStorage::new(folder);
+
+        match storage.load::<User>() {
+            Err(StorageError::MissingField { field, line }) => {
+                assert_eq!(field, "name");
+                assert_eq!(line, Some(4));
+            }
+            _ => panic!("expected a missing field error"),
+        }
+    }
 }
@@ -0,0 +1,336 @@
+use std::{collections::HashMap, fmt};
+
+use savable_derive::Savable;
+
+use super::{Savable, Storage, StorageError};
+
+pub type Fields = Vec<(String, String)>;
+
+/// Turns the fields of an entity written with version N into the fields of
+/// version N + 1. Fields already in version N + 1 must be returned unchanged,
+/// as a migration interrupted before its version was recorded runs again.
+pub type Migration = fn(Fields) -> Result<Fields, StorageError>;
+
+/// The schema version of every entity store, kept in its own store.
+#[derive(Debug, PartialEq, Savable)]
+#[savable(name = "schema")]
+struct SchemaVersion {
+    id: String,
+    version: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RecordChange {
+    pub id: String,
+    pub before: Fields,
+    pub after: Fields,
+}
+
+/// What a migration of a store did, or would do when run as a dry run.
+#[derive(Debug, PartialEq)]
+pub struct MigrationReport {
+    pub store: String,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub changes: Vec<RecordChange>,
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: version {} -> {}, {} record(s) changed",
+            self.store,
+            self.from_version,
+            self.to_version,
+            self.changes.len()
+        )?;
+        for change in &self.changes {
+            let before = change.before.iter().cloned().collect::<HashMap<_, _>>();
+            let after = change.after.iter().cloned().collect::<HashMap<_, _>>();
+            for (field, value) in &change.before {
+                match after.get(field) {
+                    Some(new_value) if new_value != value => writeln!(
+                        f,
+                        "  {}: {} \"{}\" -> \"{}\"",
+                        change.id, field, value, new_value
+                    )?,
+                    Some(_) => {}
+                    None => writeln!(f, "  {}: {} removed", change.id, field)?,
+                }
+            }
+            for (field, value) in &change.after {
+                if !before.contains_key(field) {
+                    writeln!(f, "  {}: {} added \"{}\"", change.id, field, value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The registry of migrations, indexed by store name and by the version they
+/// migrate from.
+#[derive(Default)]
+pub struct Migrations {
+    migrations: HashMap<(String, u32), Migration>,
+}
+
+impl Migrations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(mut self, store: &str, from_version: u32, migration: Migration) -> Self {
+        self.migrations
+            .insert((store.to_owned(), from_version), migration);
+        self
+    }
+
+    /// Brings the store of `S` from its recorded version up to
+    /// `S::schema_version()`. Stores without a recorded version are at version
+    /// 1, unless they are empty. With `dry_run`, nothing is written and the
+    /// report tells what would change. Returns `None` when the store is up to date.
+    pub fn run<S: Savable>(
+        &self,
+        storage: &impl Storage,
+        dry_run: bool,
+    ) -> Result<Option<MigrationReport>, StorageError> {
+        let store = S::savable_name();
+        let target = S::schema_version();
+        let recorded = storage
+            .load::<SchemaVersion>()?
+            .into_iter()
+            .find(|version| version.id == store)
+            .map(|version| version.version);
+        let rows = storage.load_fields(&store)?;
+        let current = match recorded {
+            Some(version) => version,
+            None if rows.is_empty() => target,
+            None => 1,
+        };
+
+        if current > target {
+            return Err(StorageError::UnsupportedVersion {
+                store,
+                version: current,
+                supported: target,
+            });
+        }
+        if current == target {
+            if recorded.is_none() && !dry_run {
+                storage.save(&SchemaVersion {
+                    id: store,
+                    version: target,
+                })?;
+            }
+            return Ok(None);
+        }
+
+        let mut migrated = rows.clone();
+        for version in current..target {
+            let migration = match self.migrations.get(&(store.clone(), version)) {
+                Some(migration) => migration,
+                None => return Err(StorageError::MissingMigration { store, version }),
+            };
+            migrated = migrated
+                .into_iter()
+                .map(migration)
+                .collect::<Result<Vec<Fields>, StorageError>>()?;
+        }
+
+        let id_column = S::id_column();
+        let changes = rows
+            .into_iter()
+            .zip(migrated.iter())
+            .filter(|(before, after)| before != *after)
+            .map(|(before, after)| RecordChange {
+                id: after
+                    .iter()
+                    .chain(before.iter())
+                    .find(|field| field.0 == id_column)
+                    .map(|field| field.1.clone())
+                    .unwrap_or_default(),
+                before,
+                after: after.clone(),
+            })
+            .collect();
+
+        if !dry_run {
+            // Together, so the rows are never migrated again. Backends that write
+            // them separately rely on the migrations skipping migrated rows.
+            storage.replace_fields_and_save(
+                &store,
+                &id_column,
+                &migrated,
+                &SchemaVersion {
+                    id: store.clone(),
+                    version: target,
+                },
+            )?;
+        }
+        Ok(Some(MigrationReport {
+            store,
+            from_version: current,
+            to_version: target,
+            changes,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::StorageType;
+    use std::{fs, path::Path};
+
+    #[derive(Debug, PartialEq, Savable)]
+    #[savable(name = "user", version = 3)]
+    struct User {
+        id: String,
+        first_name: String,
+        last_name: String,
+        #[savable(default)]
+        email: String,
+    }
+
+    /// Version 2 splits `name` into `first_name` and `last_name`.
+    fn split_name(fields: Fields) -> Result<Fields, StorageError> {
+        let mut migrated = Vec::new();
+        for (key, value) in fields {
+            if key == "name" {
+                let mut parts = value.splitn(2, ' ');
+                let first_name = parts.next().unwrap_or_default().to_string();
+                let last_name = parts.next().unwrap_or_default().to_string();
+                migrated.push(("first_name".to_string(), first_name));
+                migrated.push(("last_name".to_string(), last_name));
+            } else {
+                migrated.push((key, value));
+            }
+        }
+        Ok(migrated)
+    }
+
+    /// Version 3 drops the `phone` column.
+    fn drop_phone(fields: Fields) -> Result<Fields, StorageError> {
+        Ok(fields
+            .into_iter()
+            .filter(|field| field.0 != "phone")
+            .collect())
+    }
+
+    fn migrations() -> Migrations {
+        Migrations::new()
+            .register("user", 1, split_name)
+            .register("user", 2, drop_phone)
+    }
+
+    fn setup_test_folder(folder: &str) {
+        let path = Path::new(folder);
+        if path.exists() {
+            fs::remove_dir_all(path).unwrap();
+        }
+        fs::create_dir(path).unwrap();
+        fs::write(
+            path.join("user.csv"),
+            "id;name;phone\n1;Ada Lovelace;0102\n2;Alan Turing;\n",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_migrate_unversioned_store() {
+        let folder = "test_migration_run";
+        setup_test_folder(folder);
+        let storage = StorageType::FileStorage(folder).build();
+
+        let report = migrations().run::<User>(&storage, false).unwrap().unwrap();
+        assert_eq!((report.from_version, report.to_version), (1, 3));
+        assert_eq!(report.changes.len(), 2);
+        assert_eq!(
+            storage.load::<User>().unwrap()[0],
+            User {
+                id: "1".to_string(),
+                first_name: "Ada".to_string(),
+                last_name: "Lovelace".to_string(),
+                email: String::new(),
+            }
+        );
+
+        // The version is recorded, so running again does nothing
+        assert_eq!(migrations().run::<User>(&storage, false).unwrap(), None);
+    }
+
+    #[test]
+    fn test_dry_run_does_not_write() {
+        let folder = "test_migration_dry_run";
+        setup_test_folder(folder);
+        let storage = StorageType::FileStorage(folder).build();
+
+        let report = migrations().run::<User>(&storage, true).unwrap().unwrap();
+        assert_eq!(
+            report.to_string(),
+            "user: version 1 -> 3, 2 record(s) changed\n\
+             \x20 1: name removed\n\
+             \x20 1: phone removed\n\
+             \x20 1: first_name added \"Ada\"\n\
+             \x20 1: last_name added \"Lovelace\"\n\
+             \x20 2: name removed\n\
+             \x20 2: phone removed\n\
+             \x20 2: first_name added \"Alan\"\n\
+             \x20 2: last_name added \"Turing\"\n"
+        );
+        assert!(storage.load::<User>().is_err());
+        assert!(!Path::new(folder).join("schema.csv").exists());
+    }
+
+    #[test]
+    fn test_migrate_sqlite_store() {
+        let folder = "test_migration_sqlite";
+        setup_test_folder(folder);
+        let rows = StorageType::FileStorage(folder)
+            .build()
+            .load_fields("user")
+            .unwrap();
+        let storage = StorageType::Sqlite("test_migration_sqlite/db.sqlite").build();
+        storage.replace_fields("user", "id", &rows).unwrap();
+
+        assert!(migrations().run::<User>(&storage, false).unwrap().is_some());
+        assert_eq!(storage.load::<User>().unwrap()[1].last_name, "Turing");
+        assert_eq!(
+            storage.load::<SchemaVersion>().unwrap(),
+            vec![SchemaVersion {
+                id: "user".to_string(),
+                version: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn test_missing_migration() {
+        let folder = "test_migration_missing";
+        setup_test_folder(folder);
+        let storage = StorageType::FileStorage(folder).build();
+
+        let migrations = Migrations::new().register("user", 1, split_name);
+        match migrations.run::<User>(&storage, false) {
+            Err(StorageError::MissingMigration { version, .. }) => assert_eq!(version, 2),
+            _ => panic!("expected a missing migration error"),
+        }
+        assert!(storage.load::<User>().is_err());
+    }
+
+    #[test]
+    fn test_new_store_gets_current_version() {
+        let folder = "test_migration_new_store";
+        setup_test_folder(folder);
+        fs::remove_file(Path::new(folder).join("user.csv")).unwrap();
+        let storage = StorageType::FileStorage(folder).build();
+
+        assert_eq!(migrations().run::<User>(&storage, false).unwrap(), None);
+        assert_eq!(
+            fs::read_to_string(Path::new(folder).join("schema.csv")).unwrap(),
+            "id;version\nuser;3\n"
+        );
+    }
+}
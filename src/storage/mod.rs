@@ -1,32 +1,392 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
 use file_storage::FileStorage;
+use sqlite_storage::SqliteStorage;
 
+pub mod csv;
 pub mod file_storage;
+pub mod migration;
+pub mod sqlite_storage;
 
 pub trait Savable {
     fn savable_name() -> String
     where
         Self: Sized;
+    /// Version of the fields written by `to_fields`. It must be increased, and a
+    /// migration registered, whenever the fields change.
+    fn schema_version() -> u32
+    where
+        Self: Sized,
+    {
+        1
+    }
+    /// Name of the field identifying an entity in its store.
+    fn id_column() -> String
+    where
+        Self: Sized,
+    {
+        "id".to_string()
+    }
     fn to_fields(&self) -> Vec<(String, String)>
     where
         Self: Sized;
-    fn from_fields(fields: Vec<(String, String)>) -> Result<Self, String>
+    fn from_fields(fields: Vec<(String, String)>) -> Result<Self, StorageError>
     where
         Self: Sized;
 }
 
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Folder {} does not exist", .0.display())]
+    MissingFolder(PathBuf),
+    #[error("Cannot save {0} because it has no fields")]
+    EmptyFields(String),
+    #[error("Cannot save {0} because it has no id field")]
+    MissingId(String),
+    #[error("Parse error{}: {message}", on_line(line))]
+    Parse {
+        line: Option<usize>,
+        message: String,
+    },
+    #[error("Missing field \"{field}\"{}", on_line(line))]
+    MissingField { field: String, line: Option<usize> },
+    #[error("No migration registered for {store} from version {version}")]
+    MissingMigration { store: String, version: u32 },
+    #[error("{store} has version {version}, newer than the supported version {supported}")]
+    UnsupportedVersion {
+        store: String,
+        version: u32,
+        supported: u32,
+    },
+    #[error(
+        "Invalid value \"{value}\" for field \"{field}\"{}: {message}",
+        on_line(line)
+    )]
+    InvalidValue {
+        field: String,
+        value: String,
+        message: String,
+        line: Option<usize>,
+    },
+}
+
+impl StorageError {
+    pub fn io(path: &Path, source: io::Error) -> Self {
+        StorageError::Io {
+            path: path.to_owned(),
+            source,
+        }
+    }
+
+    pub fn missing_field(field: &str) -> Self {
+        StorageError::MissingField {
+            field: field.to_owned(),
+            line: None,
+        }
+    }
+
+    pub fn invalid_value(field: &str, value: &str, message: String) -> Self {
+        StorageError::InvalidValue {
+            field: field.to_owned(),
+            value: value.to_owned(),
+            message,
+            line: None,
+        }
+    }
+
+    /// Attaches the line the error comes from, unless it already knows it.
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            StorageError::Parse {
+                line: None,
+                message,
+            } => StorageError::Parse {
+                line: Some(line),
+                message,
+            },
+            StorageError::MissingField { field, line: None } => StorageError::MissingField {
+                field,
+                line: Some(line),
+            },
+            StorageError::InvalidValue {
+                field,
+                value,
+                message,
+                line: None,
+            } => StorageError::InvalidValue {
+                field,
+                value,
+                message,
+                line: Some(line),
+            },
+            e => e,
+        }
+    }
+}
+
+fn on_line(line: &Option<usize>) -> String {
+    match line {
+        Some(line) => format!(" on line {}", line),
+        None => String::new(),
+    }
+}
+
 pub enum StorageType {
     FileStorage(&'static str),
+    Sqlite(&'static str),
 }
 
 impl StorageType {
-    pub fn build(&self) -> impl Storage {
+    pub fn build(&self) -> StorageBackend {
+        match self {
+            StorageType::FileStorage(path) => StorageBackend::File(FileStorage::new(path)),
+            StorageType::Sqlite(path) => StorageBackend::Sqlite(SqliteStorage::new(path)),
+        }
+    }
+}
+
+/// The storage built from a `StorageType`, dispatching to the matching backend.
+pub enum StorageBackend {
+    File(FileStorage),
+    Sqlite(SqliteStorage),
+}
+
+impl Storage for StorageBackend {
+    fn init(&self) -> Result<(), StorageError> {
+        match self {
+            StorageBackend::File(storage) => storage.init(),
+            StorageBackend::Sqlite(storage) => storage.init(),
+        }
+    }
+
+    fn save<S: Savable>(&self, savable: &S) -> Result<(), StorageError> {
+        match self {
+            StorageBackend::File(storage) => storage.save(savable),
+            StorageBackend::Sqlite(storage) => storage.save(savable),
+        }
+    }
+
+    fn save_all<S: Savable>(&self, savables: &[S]) -> Result<(), StorageError> {
+        match self {
+            StorageBackend::File(storage) => storage.save_all(savables),
+            StorageBackend::Sqlite(storage) => storage.save_all(savables),
+        }
+    }
+
+    fn load<S: Savable>(&self) -> Result<Vec<S>, StorageError> {
+        match self {
+            StorageBackend::File(storage) => storage.load(),
+            StorageBackend::Sqlite(storage) => storage.load(),
+        }
+    }
+
+    fn load_fields(&self, name: &str) -> Result<Vec<Vec<(String, String)>>, StorageError> {
         match self {
-            StorageType::FileStorage(path) => FileStorage::new(&path),
+            StorageBackend::File(storage) => storage.load_fields(name),
+            StorageBackend::Sqlite(storage) => storage.load_fields(name),
+        }
+    }
+
+    fn replace_fields(
+        &self,
+        name: &str,
+        id_column: &str,
+        rows: &[Vec<(String, String)>],
+    ) -> Result<(), StorageError> {
+        match self {
+            StorageBackend::File(storage) => storage.replace_fields(name, id_column, rows),
+            StorageBackend::Sqlite(storage) => storage.replace_fields(name, id_column, rows),
+        }
+    }
+
+    fn replace_fields_and_save<S: Savable>(
+        &self,
+        name: &str,
+        id_column: &str,
+        rows: &[Vec<(String, String)>],
+        savable: &S,
+    ) -> Result<(), StorageError> {
+        match self {
+            StorageBackend::File(storage) => {
+                storage.replace_fields_and_save(name, id_column, rows, savable)
+            }
+            StorageBackend::Sqlite(storage) => {
+                storage.replace_fields_and_save(name, id_column, rows, savable)
+            }
+        }
+    }
+
+    fn delete<S: Savable>(&self, id: &str) -> Result<(), StorageError> {
+        match self {
+            StorageBackend::File(storage) => storage.delete::<S>(id),
+            StorageBackend::Sqlite(storage) => storage.delete::<S>(id),
         }
     }
 }
+
 pub trait Storage {
-    fn init(&self) -> Result<(), String>;
-    fn save<S: Savable>(&self, savable: &S) -> Result<(), String>;
-    fn load<S: Savable>(&self) -> Result<Vec<S>, String>;
+    fn init(&self) -> Result<(), StorageError>;
+    fn save<S: Savable>(&self, savable: &S) -> Result<(), StorageError>;
+    /// Saves several entities of the same type at once, writing the store a single time.
+    fn save_all<S: Savable>(&self, savables: &[S]) -> Result<(), StorageError>;
+    fn load<S: Savable>(&self) -> Result<Vec<S>, StorageError>;
+    /// Removes the entity with the given `id`. Deleting an unknown id is not an error.
+    fn delete<S: Savable>(&self, id: &str) -> Result<(), StorageError>;
+    /// Reads the raw fields of every entity of the store `name`, without
+    /// building them. Used to migrate stores written by older versions.
+    fn load_fields(&self, name: &str) -> Result<Vec<Vec<(String, String)>>, StorageError>;
+    /// Replaces the whole content of the store `name` by the given raw fields.
+    fn replace_fields(
+        &self,
+        name: &str,
+        id_column: &str,
+        rows: &[Vec<(String, String)>],
+    ) -> Result<(), StorageError>;
+    /// Replaces the content of the store `name` and saves `savable` in a
+    /// single write when the backend supports it. Otherwise the rows are
+    /// written first, so an interruption leaves `savable` unsaved.
+    fn replace_fields_and_save<S: Savable>(
+        &self,
+        name: &str,
+        id_column: &str,
+        rows: &[Vec<(String, String)>],
+        savable: &S,
+    ) -> Result<(), StorageError> {
+        self.replace_fields(name, id_column, rows)?;
+        self.save(savable)
+    }
+}
+
+/// Copies every `S` from one storage to another, returning how many were copied.
+/// Entities already present in `to` are overwritten, so running it twice is harmless.
+pub fn migrate<S: Savable>(from: &impl Storage, to: &impl Storage) -> Result<usize, StorageError> {
+    let savables = from.load::<S>()?;
+    to.save_all(&savables)?;
+    Ok(savables.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use savable_derive::Savable;
+    use std::fs;
+
+    fn default_country() -> String {
+        "FR".to_string()
+    }
+
+    #[derive(Debug, PartialEq, Savable)]
+    #[savable(name = "warehouse")]
+    struct Warehouse {
+        #[savable(id, rename = "code")]
+        reference: String,
+        name: String,
+        #[savable(default)]
+        capacity: u32,
+        #[savable(default = "default_country")]
+        country: String,
+        #[savable(skip)]
+        selected: bool,
+    }
+
+    fn fields(fields: &[(&str, &str)]) -> Vec<(String, String)> {
+        fields
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_derive_to_fields() {
+        let warehouse = Warehouse {
+            reference: "W1".to_string(),
+            name: "Troyes".to_string(),
+            capacity: 40,
+            country: "FR".to_string(),
+            selected: true,
+        };
+        assert_eq!(Warehouse::savable_name(), "warehouse");
+        assert_eq!(Warehouse::id_column(), "code");
+        assert_eq!(
+            warehouse.to_fields(),
+            fields(&[
+                ("code", "W1"),
+                ("name", "Troyes"),
+                ("capacity", "40"),
+                ("country", "FR")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_derive_from_fields_in_any_order() {
+        let warehouse = Warehouse::from_fields(fields(&[
+            ("unknown", "ignored"),
+            ("name", "Troyes"),
+            ("code", "W1"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            warehouse,
+            Warehouse {
+                reference: "W1".to_string(),
+                name: "Troyes".to_string(),
+                capacity: 0,
+                country: "FR".to_string(),
+                selected: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_derive_from_fields_errors() {
+        match Warehouse::from_fields(fields(&[("code", "W1")])) {
+            Err(StorageError::MissingField { field, .. }) => assert_eq!(field, "name"),
+            _ => panic!("expected a missing field error"),
+        }
+        match Warehouse::from_fields(fields(&[("code", "W1"), ("name", "A"), ("capacity", "x")])) {
+            Err(StorageError::InvalidValue { field, value, .. }) => {
+                assert_eq!(field, "capacity");
+                assert_eq!(value, "x");
+            }
+            _ => panic!("expected an invalid value error"),
+        }
+    }
+
+    #[test]
+    fn test_derive_with_storage() {
+        let folder = "test_storage_derive";
+        if Path::new(folder).exists() {
+            fs::remove_dir_all(folder).unwrap();
+        }
+        fs::create_dir(folder).unwrap();
+        let storage = StorageType::FileStorage(folder).build();
+        let mut warehouse = Warehouse {
+            reference: "W1".to_string(),
+            name: "Troyes".to_string(),
+            capacity: 40,
+            country: "BE".to_string(),
+            selected: false,
+        };
+        storage.save(&warehouse).unwrap();
+        warehouse.capacity = 50;
+        storage.save(&warehouse).unwrap();
+
+        assert_eq!(storage.load::<Warehouse>().unwrap(), vec![warehouse]);
+        storage.delete::<Warehouse>("W1").unwrap();
+        assert!(storage.load::<Warehouse>().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,442 @@
+use std::collections::HashSet;
+
+use rusqlite::{params_from_iter, Connection};
+
+use super::{Savable, Storage, StorageError};
+
+pub struct SqliteStorage {
+    path: String,
+}
+
+impl SqliteStorage {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_owned(),
+        }
+    }
+
+    fn open(&self) -> Result<Connection, StorageError> {
+        Connection::open(&self.path).map_err(StorageError::from)
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool, StorageError> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|count| count > 0)
+    .map_err(StorageError::from)
+}
+
+/// Creates the table for `table` if needed and adds any column that the
+/// entity knows about but the table does not have yet.
+fn ensure_table(
+    conn: &Connection,
+    table: &str,
+    id_column: &str,
+    columns: &[&str],
+) -> Result<(), StorageError> {
+    if !table_exists(conn, table)? {
+        let definitions = columns
+            .iter()
+            .map(|column| {
+                if *column == id_column {
+                    format!("{} TEXT PRIMARY KEY NOT NULL", quote_identifier(column))
+                } else {
+                    format!("{} TEXT", quote_identifier(column))
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        conn.execute(
+            &format!("CREATE TABLE {} ({})", quote_identifier(table), definitions),
+            [],
+        )?;
+        return Ok(());
+    }
+
+    let mut statement = conn.prepare(&format!("PRAGMA table_info({})", quote_identifier(table)))?;
+    let existing = statement
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<HashSet<String>, _>>()?;
+    for column in columns {
+        if !existing.contains(*column) {
+            conn.execute(
+                &format!(
+                    "ALTER TABLE {} ADD COLUMN {} TEXT",
+                    quote_identifier(table),
+                    quote_identifier(column)
+                ),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Inserts or updates every savable in its table, adding missing columns.
+fn upsert<S: Savable>(conn: &Connection, savables: &[S]) -> Result<(), StorageError> {
+    let table = S::savable_name();
+    let id_column = S::id_column();
+    for savable in savables {
+        let fields = savable.to_fields();
+        if fields.is_empty() {
+            return Err(StorageError::EmptyFields(table));
+        }
+        if !fields.iter().any(|field| field.0 == id_column) {
+            return Err(StorageError::MissingId(table));
+        }
+        let columns = fields
+            .iter()
+            .map(|field| field.0.as_str())
+            .collect::<Vec<&str>>();
+        ensure_table(conn, &table, &id_column, &columns)?;
+
+        let column_list = columns
+            .iter()
+            .map(|column| quote_identifier(column))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let placeholders = (1..=columns.len())
+            .map(|idx| format!("?{}", idx))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let updates = columns
+            .iter()
+            .filter(|column| **column != id_column)
+            .map(|column| {
+                format!(
+                    "{} = excluded.{}",
+                    quote_identifier(column),
+                    quote_identifier(column)
+                )
+            })
+            .collect::<Vec<String>>();
+        let conflict = if updates.is_empty() {
+            "DO NOTHING".to_string()
+        } else {
+            format!("DO UPDATE SET {}", updates.join(", "))
+        };
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT({}) {}",
+            quote_identifier(&table),
+            column_list,
+            placeholders,
+            quote_identifier(&id_column),
+            conflict
+        );
+        conn.prepare_cached(&sql).and_then(|mut statement| {
+            statement.execute(params_from_iter(fields.iter().map(|field| &field.1)))
+        })?;
+    }
+    Ok(())
+}
+
+/// Drops the table `name` and fills it again with `rows`.
+fn replace(
+    conn: &Connection,
+    name: &str,
+    id_column: &str,
+    rows: &[Vec<(String, String)>],
+) -> Result<(), StorageError> {
+    let mut columns = Vec::new();
+    for fields in rows {
+        for (column, _) in fields {
+            if !columns.contains(&column.as_str()) {
+                columns.push(column.as_str());
+            }
+        }
+    }
+    if !columns.contains(&id_column) {
+        columns.insert(0, id_column);
+    }
+
+    conn.execute(
+        &format!("DROP TABLE IF EXISTS {}", quote_identifier(name)),
+        [],
+    )?;
+    ensure_table(conn, name, id_column, &columns)?;
+    for fields in rows {
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote_identifier(name),
+            fields
+                .iter()
+                .map(|field| quote_identifier(&field.0))
+                .collect::<Vec<String>>()
+                .join(", "),
+            (1..=fields.len())
+                .map(|idx| format!("?{}", idx))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+        conn.execute(&sql, params_from_iter(fields.iter().map(|field| &field.1)))?;
+    }
+    Ok(())
+}
+
+impl Storage for SqliteStorage {
+    fn init(&self) -> Result<(), StorageError> {
+        self.open().map(|_| ())
+    }
+
+    fn save<S: Savable>(&self, savable: &S) -> Result<(), StorageError> {
+        self.save_all(std::slice::from_ref(savable))
+    }
+
+    fn save_all<S: Savable>(&self, savables: &[S]) -> Result<(), StorageError> {
+        let mut conn = self.open()?;
+        let transaction = conn.transaction()?;
+        upsert(&transaction, savables)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn delete<S: Savable>(&self, id: &str) -> Result<(), StorageError> {
+        let table = S::savable_name();
+        let conn = self.open()?;
+        if !table_exists(&conn, &table)? {
+            return Ok(());
+        }
+        conn.execute(
+            &format!(
+                "DELETE FROM {} WHERE {} = ?1",
+                quote_identifier(&table),
+                quote_identifier(&S::id_column())
+            ),
+            [id],
+        )?;
+        Ok(())
+    }
+
+    fn load<S: Savable>(&self) -> Result<Vec<S>, StorageError> {
+        let mut savables = Vec::new();
+        for fields in self.load_fields(&S::savable_name())? {
+            savables.push(S::from_fields(fields)?);
+        }
+        Ok(savables)
+    }
+
+    fn load_fields(&self, name: &str) -> Result<Vec<Vec<(String, String)>>, StorageError> {
+        let conn = self.open()?;
+        if !table_exists(&conn, name)? {
+            return Ok(Vec::new());
+        }
+
+        let mut statement = conn.prepare(&format!(
+            "SELECT * FROM {} ORDER BY rowid",
+            quote_identifier(name)
+        ))?;
+        let headers = statement
+            .column_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<String>>();
+        let rows = statement.query_map([], |row| {
+            let mut fields = Vec::new();
+            for (idx, header) in headers.iter().enumerate() {
+                // NULL columns were added after the row was written: leave
+                // them out so the entity can apply its own defaults.
+                if let Some(value) = row.get::<_, Option<String>>(idx)? {
+                    fields.push((header.clone(), value));
+                }
+            }
+            Ok(fields)
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn replace_fields(
+        &self,
+        name: &str,
+        id_column: &str,
+        rows: &[Vec<(String, String)>],
+    ) -> Result<(), StorageError> {
+        let mut conn = self.open()?;
+        let transaction = conn.transaction()?;
+        replace(&transaction, name, id_column, rows)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn replace_fields_and_save<S: Savable>(
+        &self,
+        name: &str,
+        id_column: &str,
+        rows: &[Vec<(String, String)>],
+        savable: &S,
+    ) -> Result<(), StorageError> {
+        let mut conn = self.open()?;
+        let transaction = conn.transaction()?;
+        replace(&transaction, name, id_column, rows)?;
+        upsert(&transaction, std::slice::from_ref(savable))?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    #[derive(Debug, PartialEq)]
+    pub struct User {
+        pub id: String,
+        pub name: String,
+    }
+
+    impl Savable for User {
+        fn to_fields(&self) -> Vec<(String, String)> {
+            vec![
+                ("id".to_string(), self.id.clone()),
+                ("name".to_string(), self.name.clone()),
+            ]
+        }
+
+        fn from_fields(fields: Vec<(String, String)>) -> Result<Self, StorageError> {
+            let mut id = None;
+            let mut name = None;
+
+            for (key, value) in fields {
+                match key.as_str() {
+                    "id" => id = Some(value),
+                    "name" => name = Some(value),
+                    _ => {}
+                }
+            }
+
+            Ok(User {
+                id: id.ok_or_else(|| StorageError::missing_field("id"))?,
+                name: name.ok_or_else(|| StorageError::missing_field("name"))?,
+            })
+        }
+
+        fn savable_name() -> String {
+            "user".to_owned()
+        }
+    }
+
+    fn setup_test_db(folder: &str) -> String {
+        let path = Path::new(folder);
+        if path.exists() {
+            fs::remove_dir_all(path).unwrap();
+        }
+        fs::create_dir(path).unwrap();
+        format!("{}/db.sqlite", folder)
+    }
+
+    #[test]
+    fn test_init_creates_database() {
+        let db = setup_test_db("test_sqlite_init");
+        let storage = SqliteStorage::new(&db);
+        assert!(storage.init().is_ok());
+        assert!(Path::new(&db).exists());
+    }
+
+    #[test]
+    fn test_init_non_existing_folder() {
+        let storage = SqliteStorage::new("non_existing_sqlite_folder/db.sqlite");
+        assert!(storage.init().is_err());
+    }
+
+    #[test]
+    fn test_load_missing_table() {
+        let db = setup_test_db("test_sqlite_missing_table");
+        let storage = SqliteStorage::new(&db);
+        assert!(storage.load::<User>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_upsert_existing_entry() {
+        let db = setup_test_db("test_sqlite_upsert");
+        let storage = SqliteStorage::new(&db);
+        let user1 = User {
+            id: "1".to_string(),
+            name: "Alice".to_string(),
+        };
+        let user2 = User {
+            id: "2".to_string(),
+            name: "Bob".to_string(),
+        };
+        let user1_renamed = User {
+            id: "1".to_string(),
+            name: "Carol".to_string(),
+        };
+
+        assert!(storage.save(&user1).is_ok());
+        assert!(storage.save(&user2).is_ok());
+        assert!(storage.save(&user1_renamed).is_ok());
+
+        let loaded_users = storage.load::<User>().unwrap();
+        assert_eq!(loaded_users, vec![user1_renamed, user2]);
+    }
+
+    #[test]
+    fn test_save_all_entries() {
+        let db = setup_test_db("test_sqlite_save_all");
+        let storage = SqliteStorage::new(&db);
+        let users = (0..100)
+            .map(|idx| User {
+                id: idx.to_string(),
+                name: format!("User {}", idx),
+            })
+            .collect::<Vec<User>>();
+
+        assert!(storage.save_all(&users).is_ok());
+        assert!(storage.save_all(&users[..10]).is_ok());
+
+        assert_eq!(storage.load::<User>().unwrap(), users);
+    }
+
+    #[test]
+    fn test_delete_entry() {
+        let db = setup_test_db("test_sqlite_delete");
+        let storage = SqliteStorage::new(&db);
+        let user1 = User {
+            id: "1".to_string(),
+            name: "Alice".to_string(),
+        };
+        let user2 = User {
+            id: "2".to_string(),
+            name: "Bob".to_string(),
+        };
+
+        assert!(storage.delete::<User>("1").is_ok());
+        assert!(storage.save(&user1).is_ok());
+        assert!(storage.save(&user2).is_ok());
+        assert!(storage.delete::<User>("1").is_ok());
+
+        assert_eq!(storage.load::<User>().unwrap(), vec![user2]);
+    }
+
+    #[test]
+    fn test_new_column_is_added() {
+        let db = setup_test_db("test_sqlite_new_column");
+        let conn = Connection::open(&db).unwrap();
+        conn.execute("CREATE TABLE \"user\" (\"id\" TEXT PRIMARY KEY)", [])
+            .unwrap();
+        conn.execute("INSERT INTO \"user\" (\"id\") VALUES ('0')", [])
+            .unwrap();
+
+        let storage = SqliteStorage::new(&db);
+        let user = User {
+            id: "1".to_string(),
+            name: "Alice".to_string(),
+        };
+        assert!(storage.save(&user).is_ok());
+
+        // The old row has no name and cannot be loaded as a User anymore.
+        assert!(storage.load::<User>().is_err());
+        conn.execute("DELETE FROM \"user\" WHERE \"id\" = '0'", [])
+            .unwrap();
+        assert_eq!(storage.load::<User>().unwrap(), vec![user]);
+    }
+}